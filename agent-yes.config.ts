@@ -90,6 +90,17 @@ function getDefaultConfig() {
         typingRespond: {
           "1\n": [/│ Do you want to use this API key\?/],
         },
+        banner: /╭[\s\S]*?Welcome to Claude[\s\S]*?╯[^\n]*\r?\n?/, // welcome box, removed with --strip-banner
+        notices: [
+          /^\s*Press Enter to dismiss/, // what's new / changelog screen after an update
+          /Update available!.*Press Enter to continue/,
+        ],
+        mcpTrust: {
+          detect: [/New MCP servers? found in \.mcp\.json/, /Do you trust this MCP server/i],
+          yes: "2", // 2. Use this MCP server
+          no: "3", // 3. Continue without using this MCP server
+        },
+        sessionPicker: [/Modified\s+Created\s+# Messages/], // --resume session list, see --pick-session
         enter: [
           /^.{0,4} 1\. Yes/m,
           /^.{0,4} 1\. Yes, continue/m,
@@ -100,8 +111,10 @@ function getDefaultConfig() {
           /Press Enter to continue…/m,
         ],
         fatal: [/⎿  Claude usage limit reached\./, /^error: unknown option/],
+        rateLimit: [/usage limit reached/i, /limit reached.*resets?/i, /rate limit/i], // "Claude AI usage limit reached|1749924000"
+        busy: [/esc to interrupt/, /to run in background/], // "✻ Thinking… (3s · esc to interrupt)"
         restoreArgs: ["--continue"], // restart with --continue when crashed
-        restartWithoutContinueArg: [/No conversation found to continue/],
+        restartWithoutContinueArg: [/No conversation found to continue/, /No conversation found with session ID/],
         exitCommand: ["/exit"],
         bunx: true, // use bunx to run the binary, start time is 5s faster than node
         defaultArgs: ["--model=sonnet"], // default to sonnet, to prevent opus model overload
@@ -112,6 +125,7 @@ function getDefaultConfig() {
         ready: [/Type your message/], // used with line index check
         enter: [/│ ● 1. Yes, allow once/, /│ ● 1. Allow once/],
         fatal: [/Error resuming session/, /No previous sessions found for this project./],
+        busy: [/esc to cancel/], // "⠏ Reading files (esc to cancel, 3s)"
         restoreArgs: ["--resume"], // restart with --resume when crashed
         restartWithoutContinueArg: [
           /No previous sessions found for this project\./,
@@ -133,6 +147,7 @@ function getDefaultConfig() {
           /> 1. Approve and run now/,
         ],
         fatal: [/Error: The cursor position could not be read within/],
+        busy: [/esc to interrupt/], // "• Working (5s • esc to interrupt)"
         // add to codex --search by default when not provided by the user
         defaultArgs: ["--search"],
         noEOL: true, // codex use cursor moving instead of EOL when rendering output
@@ -69,4 +69,24 @@ describe("ReadyManager", () => {
     manager.ready();
     await expect(wait2).resolves.toBeUndefined();
   });
+
+  it("should report true when ready within the timeout", async () => {
+    const manager = new ReadyManager();
+    const waiting = manager.waitTimeout(1000);
+    manager.ready();
+    await expect(waiting).resolves.toBe(true);
+    await expect(manager.waitTimeout(0)).resolves.toBe(true); // already ready
+  });
+
+  it("should report false once the timeout elapsed, and stop waiting", async () => {
+    const manager = new ReadyManager();
+    const start = Date.now();
+    await expect(manager.waitTimeout(50)).resolves.toBe(false);
+    expect(Date.now() - start).toBeGreaterThanOrEqual(45);
+    expect(manager.isReady).toBe(false);
+
+    const waiting = manager.wait(); // other waiters still get released
+    manager.ready();
+    await expect(waiting).resolves.toBeUndefined();
+  });
 });
@@ -1,13 +1,56 @@
+/**
+ * An async gate that callers can wait on until it's marked ready.
+ *
+ * Used by agent-yes to hold stdin (and auto-responses) until the agent is
+ * ready for input, and to wait for the next chunk of agent output.
+ *
+ * @example
+ * const stdinReady = new ReadyManager();
+ *
+ * // writer side: block until ready
+ * await stdinReady.wait();
+ * shell.write("hello");
+ *
+ * // reader side: open the gate when the prompt shows up
+ * if (line.includes("? for shortcuts")) stdinReady.ready();
+ */
 export class ReadyManager {
+  /** Whether the gate is currently open */
   isReady = false;
   private readyQueue: (() => void)[] = [];
+
+  /**
+   * Wait until ready.
+   * Returns undefined right away when already ready, so callers on the hot path
+   * don't pay for a promise; always safe to `await`.
+   */
   wait() {
     if (this.isReady) return;
     return new Promise<void>((resolve) => this.readyQueue.push(resolve));
   }
+
+  /**
+   * Wait until ready, but at most `ms` milliseconds
+   * @returns true when ready, false when the time ran out first
+   */
+  async waitTimeout(ms: number): Promise<boolean> {
+    if (this.isReady) return true;
+    let release!: () => void;
+    const ready = new Promise<void>((resolve) => this.readyQueue.push((release = resolve)));
+    let timer: ReturnType<typeof setTimeout> | undefined;
+    const timedOut = new Promise<boolean>((resolve) => (timer = setTimeout(() => resolve(false), ms)));
+    const result = await Promise.race([ready.then(() => true), timedOut]);
+    clearTimeout(timer);
+    if (!result) this.readyQueue = this.readyQueue.filter((resolve) => resolve !== release); // stop waiting
+    return result;
+  }
+
+  /** Close the gate, subsequent wait() calls block until the next ready() */
   unready() {
     this.isReady = false;
   }
+
+  /** Open the gate and release all pending waiters */
   ready() {
     this.isReady = true;
     if (!this.readyQueue.length) return; // check len for performance
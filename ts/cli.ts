@@ -7,8 +7,32 @@ import { PidStore } from "./pidStore.ts";
 
 // Import the CLI module
 
+// Handle `schema [config|patterns]` subcommand: print JSON Schema of the config or a --patterns-file and exit
+if (process.argv[2] === "schema") {
+  const { SCHEMAS } = await import("./schema.ts");
+  const name = process.argv[3] ?? "config";
+  if (!(name in SCHEMAS)) {
+    console.error(`Unknown schema "${name}", expected one of: ${Object.keys(SCHEMAS).join(", ")}`);
+    process.exit(1);
+  }
+  console.log(JSON.stringify(SCHEMAS[name as keyof typeof SCHEMAS], null, 2));
+  process.exit(0);
+}
+
+// Handle `watch` subcommand: launch a session per matching file change, see watch.ts
+if (process.argv[2] === "watch") {
+  const { runWatchCli } = await import("./watch.ts");
+  process.exit(await runWatchCli(process.argv.slice(3)));
+}
+
+// Handle `replay-log` subcommand: run a captured raw log or cast through prompt detection, see replayLog.ts
+if (process.argv[2] === "replay-log") {
+  const { runReplayLogCli } = await import("./replayLog.ts");
+  process.exit(await runReplayLogCli(process.argv.slice(3), cliYesConfig.clis));
+}
+
 // Parse CLI arguments
-const config = parseCliArgs(process.argv);
+const config = parseCliArgs(process.argv, { profiles: cliYesConfig.profiles });
 
 // Handle --append-prompt: write to active IPC (FIFO/Named Pipe) and exit
 if (config.appendPrompt) {
@@ -55,6 +79,16 @@ if (config.appendPrompt) {
   process.exit(0);
 }
 
+// Handle --prompt-stdin (the default for piped stdin without a prompt): read the prompt from stdin first, then take input from the terminal
+let stdin: NodeJS.ReadStream | undefined; // defaults to process.stdin
+const { shouldReadPromptFromStdin, readPromptFromStdin, openTerminalStdin } = await import("./core/promptStdin.ts");
+if (shouldReadPromptFromStdin(config.promptStdin, !!process.stdin.isTTY, !!config.prompt)) {
+  const stdinPrompt = await readPromptFromStdin(process.stdin);
+  config.prompt = [stdinPrompt, config.prompt].filter(Boolean).join("\n\n") || undefined;
+  stdin = openTerminalStdin();
+  if (!stdin) logger.warn("No terminal for interactive input, running the session non-interactively.");
+}
+
 // Validate CLI name
 if (!config.cli) {
   // logger.error(process.argv);
@@ -67,6 +101,13 @@ if (!config.cli) {
 
 // console.log(`Using CLI: ${config.cli}`);
 
+// Handle --print-config: show the effective configuration and exit
+if (config.printConfig) {
+  const { formatResolvedConfig } = await import("./core/printConfig.ts");
+  console.log(formatResolvedConfig({ ...config }, cliYesConfig.clis[config.cli]));
+  process.exit(0);
+}
+
 if (config.verbose) {
   process.env.VERBOSE = "true"; // enable verbose logging in yesLog.ts
   console.log(config);
@@ -74,6 +115,10 @@ if (config.verbose) {
 }
 
 const { default: cliYes } = await import("./index.ts");
-const { exitCode } = await cliYes(config);
-console.log("exiting process");
+const { exitCode, summary } = await cliYes({ ...config, stdin, handleSignals: true });
+if (!config.quiet) {
+  const { formatSessionSummary } = await import("./core/sessionSummary.ts");
+  process.stderr.write(`${formatSessionSummary(summary)}\n`);
+  console.log("exiting process");
+}
 process.exit(exitCode ?? 1);
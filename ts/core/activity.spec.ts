@@ -0,0 +1,41 @@
+import { describe, expect, it } from "vitest";
+import { ActivityTracker, classifyActivity } from "./activity";
+
+describe("classifyActivity", () => {
+  it.each([
+    ["✻ Thinking… (3s · esc to interrupt)", { activity: "thinking" }],
+    ["✽ Pondering… (12s · ↑ 1.2k tokens · esc to interrupt)", { activity: "thinking" }],
+    ["⏺ Bash(npm test)", { activity: "running-tool", tool: "Bash" }],
+    ["⏺ Update(src/parser.ts)", { activity: "running-tool", tool: "Update" }],
+    ["  ⎿  Running…", { activity: "running-tool" }],
+    ["  ? for shortcuts", { activity: "awaiting-input" }],
+    [" Do you want to proceed?", { activity: "awaiting-input" }],
+  ])("should classify %j", (line, expected) => {
+    expect(classifyActivity(line)).toEqual(expected);
+  });
+
+  it.each(["", "PASS src/parser.spec.ts", "⏺ I fixed the parser, all tests pass now.", "* a markdown bullet"])(
+    "should not tell from %j",
+    (line) => {
+      expect(classifyActivity(line)).toBeNull();
+    },
+  );
+});
+
+describe("ActivityTracker", () => {
+  it("should start unknown and report changes only", () => {
+    const tracker = new ActivityTracker();
+    expect(tracker.current).toEqual({ activity: "unknown" });
+    expect(tracker.observe("✻ Thinking… (1s · esc to interrupt)")).toEqual({ activity: "thinking" });
+    expect(tracker.observe("✻ Thinking… (2s · esc to interrupt)")).toBeNull();
+    expect(tracker.observe("some output")).toBeNull();
+    expect(tracker.current).toEqual({ activity: "thinking" });
+  });
+
+  it("should keep the tool of the call for its running line", () => {
+    const tracker = new ActivityTracker();
+    expect(tracker.observe("⏺ Bash(npm test)")).toEqual({ activity: "running-tool", tool: "Bash" });
+    expect(tracker.observe("  ⎿  Running…")).toBeNull();
+    expect(tracker.observe("? for shortcuts")).toEqual({ activity: "awaiting-input" });
+  });
+});
@@ -0,0 +1,62 @@
+/**
+ * What the agent is doing, read from its output for `--status-port` and `--json-log`
+ *
+ * Finer than the busy/awaiting-input split of agentState.ts, e.g. to show
+ * "Running: Bash" or "Thinking…" for a background session. Parsed from
+ * Claude-style activity lines and tolerant of anything else: lines that say
+ * nothing about the activity keep the last one, and a session starts as
+ * `unknown`.
+ *
+ * - `thinking`: a spinner line, e.g. `✻ Thinking… (3s · esc to interrupt)`
+ * - `running-tool`: a tool call is running, e.g. `⏺ Bash(npm test)` then `⎿  Running…`
+ * - `awaiting-input`: the input prompt or a permission question is shown
+ */
+export type Activity = "unknown" | "thinking" | "running-tool" | "awaiting-input";
+
+export interface ActivityInfo {
+  activity: Activity;
+  tool?: string; // with running-tool, e.g. "Bash"
+}
+
+const SPINNER = /^\s*[✻✽✶✳✢·*◦•⠋⠙⠹⠸⠼⠴⠦⠧⠇⠏]\s+\p{L}[\p{L} ]*…/u;
+const TOOL_CALL = /^\s*⏺\s+([A-Z]\w*)\(/;
+const TOOL_RUNNING = /^\s*⎿\s+(Running|Waiting)…/;
+const AWAITING_INPUT = /\? for shortcuts|Do you want to (proceed|make this edit|create)|^\s*❯ 1\. Yes/;
+
+/**
+ * Tell the activity from one output line
+ * @returns The activity, or null when the line doesn't tell
+ *
+ * @example
+ * ```typescript
+ * classifyActivity("⏺ Bash(npm test)"); // { activity: "running-tool", tool: "Bash" }
+ * classifyActivity("Some output"); // null
+ * ```
+ */
+export function classifyActivity(line: string): ActivityInfo | null {
+  const tool = line.match(TOOL_CALL)?.[1];
+  if (tool) return { activity: "running-tool", tool };
+  if (TOOL_RUNNING.test(line)) return { activity: "running-tool" };
+  if (SPINNER.test(line)) return { activity: "thinking" };
+  if (AWAITING_INPUT.test(line)) return { activity: "awaiting-input" };
+  return null;
+}
+
+export class ActivityTracker {
+  current: ActivityInfo = { activity: "unknown" };
+  private lastTool?: string; // from the tool call line, kept for its "⎿  Running…" line
+
+  /**
+   * Update the activity from an output line
+   * @returns The new activity when it changed, null otherwise
+   */
+  observe(line: string): ActivityInfo | null {
+    const info = classifyActivity(line);
+    if (!info) return null;
+    if (info.tool) this.lastTool = info.tool;
+    const next = info.activity === "running-tool" ? { activity: info.activity, tool: info.tool ?? this.lastTool } : info;
+    if (next.activity === this.current.activity && next.tool === this.current.tool) return null;
+    this.current = next;
+    return next;
+  }
+}
@@ -0,0 +1,91 @@
+import { describe, expect, it } from "vitest";
+import { AgentStateTracker, busyMarkerPattern, matchBusyMarker, parseBusyMarkersFile } from "./agentState";
+
+const idleScreen = "╭────╮\n│ >  │\n╰────╯\n  ? for shortcuts";
+const busyScreen = "✻ Thinking… (3s · esc to interrupt)\n╭────╮\n│ >  │\n╰────╯";
+
+describe("AgentStateTracker", () => {
+  it("should start as starting with input closed", () => {
+    const tracker = new AgentStateTracker();
+    expect(tracker.state).toBe("starting");
+    expect(tracker.awaitingInput.isReady).toBe(false);
+  });
+
+  it("should stay starting while nothing is recognizable", () => {
+    const tracker = new AgentStateTracker();
+    tracker.observe(false, "Loading…");
+    expect(tracker.state).toBe("starting");
+  });
+
+  it("should await input once the prompt shows without a busy marker", () => {
+    const tracker = new AgentStateTracker();
+    tracker.observe(true, idleScreen);
+    expect(tracker.state).toBe("awaiting-input");
+    expect(tracker.awaitingInput.isReady).toBe(true);
+  });
+
+  it("should be busy while a busy marker shows, even with the prompt", () => {
+    const tracker = new AgentStateTracker();
+    tracker.observe(true, busyScreen);
+    expect(tracker.state).toBe("busy");
+    expect(tracker.awaitingInput.isReady).toBe(false);
+
+    tracker.observe(true, idleScreen);
+    expect(tracker.state).toBe("awaiting-input");
+  });
+
+  it("should turn busy after input is sent", () => {
+    const tracker = new AgentStateTracker();
+    tracker.observe(true, idleScreen);
+    tracker.sent();
+    expect(tracker.state).toBe("busy");
+    expect(tracker.awaitingInput.wait()).toBeInstanceOf(Promise);
+  });
+
+  it("should go back to starting on reset, and be forced out of it", () => {
+    const tracker = new AgentStateTracker();
+    tracker.observe(true, idleScreen);
+    tracker.reset();
+    expect(tracker.state).toBe("starting");
+
+    tracker.force();
+    expect(tracker.state).toBe("awaiting-input");
+    tracker.sent();
+    tracker.force(); // only leaves starting
+    expect(tracker.state).toBe("busy");
+  });
+
+  it("should use the cli's own busy markers", () => {
+    const tracker = new AgentStateTracker([/esc to cancel/]);
+    tracker.observe(true, "⠏ Reading files (esc to cancel, 3s)\n> Type your message");
+    expect(tracker.state).toBe("busy");
+    tracker.observe(true, busyScreen); // claude's marker means nothing to this cli
+    expect(tracker.state).toBe("awaiting-input");
+  });
+});
+
+describe("matchBusyMarker", () => {
+  it("should find a marker across wrapped whitespace", () => {
+    expect(matchBusyMarker("✻ Thinking… (3s · esc to\n   interrupt)")?.[0]).toBe("esc to interrupt");
+    expect(matchBusyMarker(idleScreen)).toBeUndefined();
+  });
+});
+
+describe("busyMarkerPattern", () => {
+  it("should match the text literally, ignoring case and whitespace runs", () => {
+    const marker = busyMarkerPattern("Press ESC to stop (or ctrl+c)");
+    expect(matchBusyMarker("⠋ Working…  press esc to  stop (or Ctrl+C)", [marker])?.[0]).toBe(
+      "press esc to stop (or Ctrl+C)",
+    );
+    expect(matchBusyMarker("Press ESC to stop or ctrl+c", [marker])).toBeUndefined();
+  });
+});
+
+describe("parseBusyMarkersFile", () => {
+  it("should read one marker per line, skipping blank lines and comments", () => {
+    expect(parseBusyMarkersFile("# claude 2.x\nesc to interrupt\n\n  ctrl+b to run in background  \r\n")).toEqual([
+      "esc to interrupt",
+      "ctrl+b to run in background",
+    ]);
+  });
+});
@@ -0,0 +1,99 @@
+import { ReadyManager } from "../ReadyManager.ts";
+import { logger } from "../logger.ts";
+
+/**
+ * What the agent is doing, as far as its screen tells
+ *
+ * - `starting`: spawned, nothing recognizable on screen yet
+ * - `busy`: working on something, e.g. "esc to interrupt" is shown
+ * - `awaiting-input`: its input prompt is shown and it isn't working
+ *
+ * `stdinReady` only tells that the agent got through startup, which is
+ * right for forwarding the user's keys (Esc must reach a busy agent), but
+ * messages sent by agent-yes (the prompt, --append-prompt, control socket)
+ * should wait for `awaiting-input`, or they land in the middle of a task.
+ */
+export type AgentState = "starting" | "busy" | "awaiting-input";
+
+/** Screen text meaning the agent is still working, even though its input box is shown */
+export const BUSY_MARKER = /esc to interrupt|to run in background/;
+
+/**
+ * A user busy marker, `--busy-marker`: plain text, matched case-insensitively
+ *
+ * @example
+ * ```typescript
+ * busyMarkerPattern("Press ESC to stop"); // /Press ESC to stop/i
+ * ```
+ */
+export function busyMarkerPattern(text: string): RegExp {
+  return new RegExp(text.trim().replace(/[.*+?^${}()|[\]\\]/g, "\\$&").replace(/\s+/g, " "), "i");
+}
+
+/**
+ * Read a `--busy-markers-file`: one marker per line, blank lines and `#` comments skipped
+ */
+export function parseBusyMarkersFile(content: string): string[] {
+  return content
+    .split(/\r?\n/)
+    .map((line) => line.trim())
+    .filter((line) => line && !line.startsWith("#"));
+}
+
+/**
+ * Find a busy marker on the screen
+ *
+ * @param screen - Rendered screen text, whitespace runs count as one space
+ * @param markers - The cli's busy patterns, see AgentCliConfig.busy
+ * @returns The match, e.g. `["esc to interrupt"]`, or undefined when the agent looks idle
+ */
+export function matchBusyMarker(screen: string, markers: RegExp[] = [BUSY_MARKER]) {
+  const text = screen.replace(/\s+/g, " ");
+  for (const marker of markers) {
+    const match = text.match(marker);
+    if (match) return match;
+  }
+}
+
+export class AgentStateTracker {
+  state: AgentState = "starting";
+  /** Open while the agent is awaiting input */
+  readonly awaitingInput = new ReadyManager();
+
+  /** @param busyMarkers - The cli's busy patterns, see AgentCliConfig.busy */
+  constructor(private busyMarkers: RegExp[] = [BUSY_MARKER]) {}
+
+  /**
+   * Update the state from the screen
+   *
+   * @param promptVisible - The agent's ready pattern is on screen
+   * @param screen - Rendered screen text, checked for busy markers
+   */
+  observe(promptVisible: boolean, screen: string) {
+    if (matchBusyMarker(screen, this.busyMarkers)) return this.set("busy");
+    if (promptVisible) return this.set("awaiting-input");
+  }
+
+  /** We just sent input, the agent is busy until its prompt comes back idle */
+  sent() {
+    if (this.state === "awaiting-input") this.set("busy");
+  }
+
+  /** Agent (re)spawned */
+  reset() {
+    this.set("starting");
+  }
+
+  /** Give up waiting for a recognizable prompt, e.g. when the ready patterns don't match this agent version */
+  force() {
+    if (this.state === "starting") this.set("awaiting-input");
+  }
+
+  private set(next: AgentState) {
+    if (this.state === next) return;
+    logger.debug(`state |${this.state} -> ${next}`);
+    this.state = next;
+    if (next === "awaiting-input") this.awaitingInput.ready();
+    else this.awaitingInput.unready();
+  }
+}
@@ -0,0 +1,59 @@
+import { mkdtemp, readFile, rm } from "fs/promises";
+import os from "os";
+import path from "path";
+import { afterEach, beforeEach, describe, expect, it } from "vitest";
+import { CastRecorder, castEvent, castHeader, parseCastOutput } from "./cast";
+
+describe("castHeader / castEvent", () => {
+  it("should serialize asciicast v2 lines", () => {
+    expect(castHeader(80, 24, new Date("2026-01-01T00:00:00Z"))).toBe(
+      '{"version":2,"width":80,"height":24,"timestamp":1767225600}\n',
+    );
+    expect(castEvent(1234.5678, "o", "hello\r\n")).toBe('[1.234568,"o","hello\\r\\n"]\n');
+    expect(castEvent(0, "i", "\u001b")).toBe('[0,"i","\\u001b"]\n');
+  });
+});
+
+describe("CastRecorder", () => {
+  let dir: string;
+
+  beforeEach(async () => {
+    dir = await mkdtemp(path.join(os.tmpdir(), "agent-yes-cast-"));
+  });
+
+  afterEach(async () => {
+    await rm(dir, { recursive: true, force: true });
+  });
+
+  it("should write the header, then output and input events timed from the start", async () => {
+    let now = 1000;
+    const filePath = path.join(dir, "casts", "run.cast");
+    const cast = new CastRecorder(filePath, { cols: 120, rows: 40 }, () => now);
+    now = 1500;
+    cast.output("❯ 1. Yes\r\n");
+    now = 2250;
+    cast.input("\r");
+    cast.output(""); // nothing to record
+    await cast.flush();
+
+    const [header, ...events] = (await readFile(filePath, "utf8")).trimEnd().split("\n");
+    expect(JSON.parse(header!)).toMatchObject({ version: 2, width: 120, height: 40 });
+    expect(events.map((line) => JSON.parse(line))).toEqual([
+      [0.5, "o", "❯ 1. Yes\r\n"],
+      [1.25, "i", "\r"],
+    ]);
+  });
+});
+
+describe("parseCastOutput", () => {
+  it("should return the output events, skipping input", () => {
+    const cast = '{"version":2,"width":80,"height":24}\n[0.5,"o","hi"]\n[1,"i","\\r"]\n[1.5,"o","\\u001b[0m"]\n';
+    expect(parseCastOutput(cast)).toEqual(["hi", "\u001b[0m"]);
+  });
+
+  it("should return null for anything but an asciicast v2 file", () => {
+    expect(parseCastOutput("plain raw log\n")).toBeNull();
+    expect(parseCastOutput('{"version":1,"width":80,"height":24,"stdout":[]}')).toBeNull();
+    expect(parseCastOutput("")).toBeNull();
+  });
+});
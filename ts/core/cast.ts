@@ -0,0 +1,135 @@
+import path from "path";
+import { appendFile, mkdir, writeFile } from "fs/promises";
+import { logger } from "../logger.ts";
+
+/**
+ * `--cast-file`: record the session as an asciicast v2 file
+ *
+ * The raw log has the output but not its timing, nor what agent-yes typed.
+ * A cast has both: a header with the pty size, then one `[seconds, "o", data]`
+ * event per output chunk and one `[seconds, "i", data]` event per
+ * auto-response, timed from the session start. Play it back with
+ * `asciinema play <file>` to see a run exactly as it happened, or share it
+ * when a prompt was missed or answered wrongly. `agent-yes replay-log` runs
+ * the output of a cast through prompt detection again, see replayLog.ts.
+ *
+ * @see https://docs.asciinema.org/manual/asciicast/v2/
+ */
+
+export type CastEventType = "o" | "i"; // output from the agent, input sent to it
+
+export interface CastHeader {
+  version: 2;
+  width: number;
+  height: number;
+  timestamp: number; // session start, unix seconds
+}
+
+/**
+ * Serialize the header line of a cast
+ *
+ * @example
+ * ```typescript
+ * castHeader(80, 24, new Date("2026-01-01T00:00:00Z"));
+ * // '{"version":2,"width":80,"height":24,"timestamp":1767225600}\n'
+ * ```
+ */
+export function castHeader(width: number, height: number, startedAt: Date): string {
+  const header: CastHeader = { version: 2, width, height, timestamp: Math.floor(startedAt.getTime() / 1000) };
+  return JSON.stringify(header) + "\n";
+}
+
+/**
+ * Serialize an event line of a cast, the time rounded to microseconds
+ *
+ * @param elapsedMs - Time since the session start
+ *
+ * @example
+ * ```typescript
+ * castEvent(1234.5678, "o", "hello\r\n"); // '[1.234568,"o","hello\\r\\n"]\n'
+ * ```
+ */
+export function castEvent(elapsedMs: number, type: CastEventType, data: string): string {
+  return JSON.stringify([Math.round(elapsedMs * 1000) / 1e6, type, data]) + "\n";
+}
+
+/**
+ * The output chunks of a cast, in order, as the agent wrote them
+ *
+ * @returns The data of the `"o"` events, or null when the content isn't an asciicast v2 file
+ * @throws Error when an event line is not valid JSON
+ *
+ * @example
+ * ```typescript
+ * parseCastOutput('{"version":2,"width":80,"height":24}\n[0.5,"o","hi"]\n[1,"i","\\r"]\n'); // ["hi"]
+ * parseCastOutput("plain raw log\n"); // null
+ * ```
+ */
+export function parseCastOutput(content: string): string[] | null {
+  const [first, ...lines] = content.split("\n");
+  try {
+    if ((JSON.parse(first!) as Partial<CastHeader>)?.version !== 2) return null;
+  } catch {
+    return null; // e.g. a raw log
+  }
+  return lines.flatMap((line, i) => {
+    if (!line.trim()) return [];
+    let event: unknown;
+    try {
+      event = JSON.parse(line);
+    } catch {
+      throw new Error(`Invalid cast event on line ${i + 2}: ${line.slice(0, 80)}`);
+    }
+    return Array.isArray(event) && event[1] === "o" ? [String(event[2])] : [];
+  });
+}
+
+export class CastRecorder {
+  private queue: Promise<unknown>;
+  private readonly start: number;
+
+  /**
+   * @param filePath - Cast file, truncated, its directory is created
+   * @param size - Pty size for the header
+   * @param now - Clock in ms, for tests
+   */
+  constructor(
+    readonly filePath: string,
+    { cols, rows }: { cols: number; rows: number },
+    private now: () => number = () => performance.now(),
+  ) {
+    this.start = now();
+    this.queue = mkdir(path.dirname(filePath), { recursive: true })
+      .then(() => writeFile(filePath, castHeader(cols, rows, new Date())))
+      .catch((error) => logger.warn(`Failed to create cast file ${filePath}: ${String(error)}`));
+  }
+
+  /**
+   * Record an output chunk of the agent
+   */
+  output(data: string) {
+    this.append("o", data);
+  }
+
+  /**
+   * Record keys agent-yes sent to the agent
+   */
+  input(data: string) {
+    this.append("i", data);
+  }
+
+  /**
+   * Wait for all pending writes to finish
+   */
+  async flush() {
+    await this.queue;
+  }
+
+  private append(type: CastEventType, data: string) {
+    if (!data) return;
+    const line = castEvent(this.now() - this.start, type, data); // timed now, not when written
+    this.queue = this.queue
+      .then(() => appendFile(this.filePath, line))
+      .catch((error) => logger.debug(`cast|failed to append: ${String(error)}`));
+  }
+}
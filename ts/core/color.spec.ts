@@ -0,0 +1,18 @@
+import { describe, expect, it } from "vitest";
+import { resolveColorMode } from "./color";
+
+describe("resolveColorMode", () => {
+  it("should default to auto", () => {
+    expect(resolveColorMode(undefined, {})).toBe("auto");
+  });
+
+  it("should respect NO_COLOR", () => {
+    expect(resolveColorMode(undefined, { NO_COLOR: "1" })).toBe("never");
+    expect(resolveColorMode(undefined, { NO_COLOR: "" })).toBe("auto"); // empty means unset
+  });
+
+  it("should let an explicit --color win over NO_COLOR", () => {
+    expect(resolveColorMode("always", { NO_COLOR: "1" })).toBe("always");
+    expect(resolveColorMode("never", {})).toBe("never");
+  });
+});
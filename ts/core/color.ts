@@ -0,0 +1,23 @@
+/**
+ * Color handling behind `--color` and the NO_COLOR convention (https://no-color.org)
+ */
+
+export const COLOR_MODES = ["auto", "always", "never"] as const;
+export type ColorMode = (typeof COLOR_MODES)[number];
+
+/**
+ * Pick the color mode: an explicit `--color` wins, otherwise a non-empty
+ * NO_COLOR means never
+ *
+ * @example
+ * ```typescript
+ * resolveColorMode(undefined, { NO_COLOR: "1" }); // "never"
+ * resolveColorMode("always", { NO_COLOR: "1" }); // "always"
+ * ```
+ */
+export function resolveColorMode(
+  flag: ColorMode | undefined,
+  env: Record<string, string | undefined> = process.env,
+): ColorMode {
+  return flag ?? (env.NO_COLOR ? "never" : "auto");
+}
@@ -0,0 +1,44 @@
+import { describe, expect, it } from "vitest";
+import { ConfirmGate } from "./confirmGate";
+
+const tick = () => new Promise((resolve) => setTimeout(resolve, 0));
+
+describe("ConfirmGate", () => {
+  it("should only guard dangerous categories", () => {
+    const gate = new ConfirmGate(true, () => {});
+    expect(gate.guards("delete")).toBe(true);
+    expect(gate.guards("edit")).toBe(false);
+    expect(gate.guards(undefined)).toBe(false);
+  });
+
+  it("should ask once and remember the answer", async () => {
+    const output: string[] = [];
+    const gate = new ConfirmGate(true, (text) => void output.push(text));
+
+    const first = gate.confirm("delete", "rm -rf dist");
+    await tick(); // let the question open
+    expect(gate.handleInput("y")).toBe(true);
+    expect(await first).toBe("yes");
+
+    expect(await gate.confirm("delete", "rm -rf build")).toBe("yes");
+    expect(output.join("")).toContain("rm -rf dist");
+    expect(output.join("")).not.toContain("rm -rf build");
+  });
+
+  it("should treat anything but y as no", async () => {
+    const gate = new ConfirmGate(true, () => {});
+    const answer = gate.confirm("delete", "rm -rf dist");
+    await tick(); // let the question open
+    gate.handleInput("\r");
+    expect(await answer).toBe("no");
+  });
+
+  it("should pass input through when no question is open", () => {
+    expect(new ConfirmGate(true, () => {}).handleInput("y")).toBe(false);
+  });
+
+  it("should decline without a terminal", async () => {
+    const gate = new ConfirmGate(false, () => {});
+    expect(await gate.confirm("delete", "rm -rf dist")).toBe("no");
+  });
+});
@@ -0,0 +1,77 @@
+import type { Answer, PromptCategory } from "./promptCategories.ts";
+
+/**
+ * Ask the user before accepting dangerous prompts, behind `--confirm-dangerous-once`
+ *
+ * The first prompt of a dangerous category (e.g. `delete`) is not answered
+ * automatically: the user is asked on the terminal, out of band from the
+ * agent, and their answer is remembered for that category for the rest of
+ * the session. While a question is open, keystrokes go to the gate instead
+ * of the agent. Without a terminal to ask on, the prompt is declined.
+ */
+
+export const DANGEROUS_CATEGORIES: PromptCategory[] = ["delete"];
+
+export class ConfirmGate {
+  private decisions = new Map<PromptCategory, Answer>();
+  private pending: ((answer: Answer) => void) | null = null;
+  private running: Promise<unknown> = Promise.resolve();
+
+  /**
+   * @param interactive - Whether there is a terminal to ask on
+   * @param write - Shows the question, e.g. on stderr so it doesn't mix into the agent's screen
+   */
+  constructor(
+    private interactive: boolean,
+    private write: (text: string) => void,
+  ) {}
+
+  /** Whether prompts of this category need confirming */
+  guards(category?: PromptCategory): category is PromptCategory {
+    return !!category && DANGEROUS_CATEGORIES.includes(category);
+  }
+
+  /**
+   * Decide a dangerous prompt, asking the user the first time per category
+   *
+   * @param category - Category of the prompt
+   * @param line - Prompt text shown with the question
+   */
+  confirm(category: PromptCategory, line: string): Promise<Answer> {
+    const asked = this.running.then(() => this.decide(category, line)); // one question at a time
+    this.running = asked;
+    return asked;
+  }
+
+  /**
+   * Route user input to an open question
+   *
+   * @returns true if the input answered the question and must not reach the agent
+   */
+  handleInput(data: string): boolean {
+    if (!this.pending) return false;
+    const resolve = this.pending;
+    this.pending = null;
+    const answer: Answer = /^y/i.test(data.trim()) ? "yes" : "no";
+    this.write(`${answer}\n`);
+    resolve(answer);
+    return true;
+  }
+
+  private async decide(category: PromptCategory, line: string): Promise<Answer> {
+    const remembered = this.decisions.get(category);
+    if (remembered) return remembered;
+    if (!this.interactive) {
+      this.write(`\n[agent-yes] Declined a ${category} prompt, no terminal to confirm it on: ${line.trim()}\n`);
+      this.decisions.set(category, "no");
+      return "no";
+    }
+    this.write(
+      `\n[agent-yes] The agent asks for a ${category} action: ${line.trim()}\n` +
+        `[agent-yes] Allow ${category} actions for the rest of this session? [y/N] `,
+    );
+    const answer = await new Promise<Answer>((resolve) => (this.pending = resolve));
+    this.decisions.set(category, answer);
+    return answer;
+  }
+}
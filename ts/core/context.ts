@@ -5,6 +5,10 @@ import type { PidStore } from "../pidStore.ts";
 import type { AgentCliConfig } from "../index.ts";
 import type { SUPPORTED_CLIS } from "../SUPPORTED_CLIS.ts";
 import type { LogPaths } from "./logging.ts";
+import { EventLog } from "./events.ts";
+import { AgentStateTracker } from "./agentState.ts";
+import { ActivityTracker } from "./activity.ts";
+import type { CastRecorder } from "./cast.ts";
 
 /**
  * Shared context for agent session
@@ -45,12 +49,22 @@ export class AgentContext {
   cliConf: AgentCliConfig;
   verbose: boolean;
   robust: boolean;
+  typingDelay = 0;
+  nextAnswerJitter: () => number = () => 0; // extra ms before each auto-response, see --answer-delay-jitter
+  inputGating = true; // false: write input without waiting for the agent to be ready, see --no-input-gating
+  dryRun = false; // detect and log prompts without answering them, see --dry-run
+  responseDelay = 400; // ms of idle output before answering a prompt with Enter, see --response-delay
+  readyTimeout = 10000; // ms of silence after which messages are sent even though the prompt wasn't seen, see --ready-timeout
+  cast?: CastRecorder; // records output and auto-responses, see --cast-file
 
   // State managers
   stdinReady = new ReadyManager();
   stdinFirstReady = new ReadyManager();
   nextStdout = new ReadyManager();
-  idleWaiter = new IdleWaiter();
+  agentState: AgentStateTracker; // starting -> busy <-> awaiting-input, gates messages we send
+  activity = new ActivityTracker(); // thinking, running a tool, ..., for --status-port and --json-log
+  idleWaiter: IdleWaiter;
+  events: EventLog;
 
   // Flags
   isFatal = false;
@@ -64,14 +78,21 @@ export class AgentContext {
     cliConf: AgentCliConfig;
     verbose: boolean;
     robust: boolean;
+    jsonLogPath?: string;
+    eventsFd?: number; // --events-fd, takes precedence over jsonLogPath
+    idlePollInterval?: number; // --idle-poll-interval, ms between idle checks
+    prefix?: string;
   }) {
     this.shell = params.shell;
     this.pidStore = params.pidStore;
     this.logPaths = params.logPaths;
     this.cli = params.cli;
     this.cliConf = params.cliConf;
+    this.agentState = new AgentStateTracker(params.cliConf.busy);
     this.verbose = params.verbose;
     this.robust = params.robust;
+    this.idleWaiter = new IdleWaiter(params.idlePollInterval);
+    this.events = new EventLog(params.eventsFd ?? params.jsonLogPath ?? false, { prefix: params.prefix });
   }
 
   /**
@@ -88,6 +109,11 @@ export class AgentContext {
       idleWaiter: this.idleWaiter,
       stdinReady: this.stdinReady,
       nextStdout: this.nextStdout,
+      // without ready patterns the prompt is never recognized, so awaiting-input would never come back after a message
+      agentState: this.cliConf.ready?.length ? this.agentState : undefined,
+      typingDelay: this.typingDelay,
+      inputGating: this.inputGating,
+      readyTimeout: this.readyTimeout,
     };
   }
 }
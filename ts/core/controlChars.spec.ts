@@ -0,0 +1,48 @@
+import { describe, expect, it } from "vitest";
+import {
+  applyControlCharPolicy,
+  defaultStdoutControlCharPolicy,
+  LOG_CONTROL_CHAR_POLICY,
+  parseControlCharPolicy,
+} from "./controlChars";
+
+describe("applyControlCharPolicy", () => {
+  it("should forward the bell to an interactive terminal", () => {
+    expect(applyControlCharPolicy("done\u0007", defaultStdoutControlCharPolicy(true))).toBe("done\u0007");
+  });
+
+  it("should drop the bell when stdout is piped", () => {
+    expect(applyControlCharPolicy("done\u0007", defaultStdoutControlCharPolicy(false))).toBe("done");
+  });
+
+  it("should keep the BEL ending an OSC title sequence and drop only standalone ones", () => {
+    const text = "\u001b]0;✳ Claude Code\u0007ready\u0007\u001b]2;done\u001b\\\u0007";
+    expect(applyControlCharPolicy(text, defaultStdoutControlCharPolicy(false))).toBe(
+      "\u001b]0;✳ Claude Code\u0007ready\u001b]2;done\u001b\\",
+    );
+  });
+
+  it("should keep an OSC whole across chunks with a shared state", () => {
+    const state = { osc: false, last: "" };
+    const chunks = ["title: \u001b", "]0;agent", "\u0007 bell\u0007"];
+    expect(chunks.map((chunk) => applyControlCharPolicy(chunk, { bel: "drop" }, state)).join("")).toBe(
+      "title: \u001b]0;agent\u0007 bell",
+    );
+  });
+
+  it("should drop all benign controls from logs but keep escapes and line breaks", () => {
+    const text = "\u001b[1mok\u001b[0m\u0007\u0000\r\n\tnext\u000c";
+    expect(applyControlCharPolicy(text, LOG_CONTROL_CHAR_POLICY)).toBe("\u001b[1mok\u001b[0m\r\n\tnext");
+  });
+});
+
+describe("parseControlCharPolicy", () => {
+  it("should parse name=action entries", () => {
+    expect(parseControlCharPolicy(["bel=drop", "FF=forward"])).toEqual({ bel: "drop", ff: "forward" });
+  });
+
+  it("should reject unknown names and actions", () => {
+    expect(() => parseControlCharPolicy(["beep=drop"])).toThrow(/Unknown control character "beep"/);
+    expect(() => parseControlCharPolicy(["bel=render"])).toThrow(/Invalid action "render"/);
+  });
+});
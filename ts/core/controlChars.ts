@@ -0,0 +1,100 @@
+/**
+ * Policy for benign control characters in agent output
+ *
+ * Escape sequences are handled by the terminal render and
+ * `removeControlCharacters`; this covers the single-byte controls around
+ * them. Each one is either forwarded or dropped, per destination:
+ * - stdout: forwarded, so the user hears/sees the bell in interactive use.
+ *   BEL is dropped when stdout isn't a TTY. Override with `--control-chars`.
+ * - logs: always dropped, keeping log files clean
+ *
+ * Only standalone characters are dropped: the BEL ending an OSC sequence
+ * (e.g. a window title, `\x1b]0;title\x07`) is part of that sequence, and
+ * dropping it would leave the terminal swallowing the output that follows.
+ */
+
+export const CONTROL_CHARACTERS = {
+  nul: "\u0000",
+  bel: "\u0007", // bell, beep or flash
+  bs: "\u0008", // backspace
+  vt: "\u000b", // vertical tab
+  ff: "\u000c", // form feed
+  so: "\u000e", // shift out
+  si: "\u000f", // shift in
+  del: "\u007f",
+} as const;
+
+export type ControlCharName = keyof typeof CONTROL_CHARACTERS;
+export type ControlCharAction = "forward" | "drop";
+export type ControlCharPolicy = Partial<Record<ControlCharName, ControlCharAction>>; // unlisted are forwarded
+
+const CONTROL_CHAR_NAMES = Object.keys(CONTROL_CHARACTERS) as ControlCharName[];
+
+/** Policy for log files: drop them all */
+export const LOG_CONTROL_CHAR_POLICY: ControlCharPolicy = Object.fromEntries(
+  CONTROL_CHAR_NAMES.map((name) => [name, "drop"]),
+);
+
+/**
+ * Default policy for stdout
+ * @param isTTY - Whether stdout is an interactive terminal
+ */
+export function defaultStdoutControlCharPolicy(isTTY: boolean): ControlCharPolicy {
+  return { bel: isTTY ? "forward" : "drop" };
+}
+
+/** Where the previous chunk ended, for an OSC sequence split across chunks */
+export interface ControlCharState {
+  osc: boolean; // inside an OSC sequence, `\x1b]` up to BEL or `\x1b\\`
+  last: string; // last char seen
+}
+
+/**
+ * Remove the control characters the policy drops, keeping OSC sequences whole
+ *
+ * @param state - Carried between chunks of a stream, see createControlCharFilter
+ *
+ * @example
+ * ```typescript
+ * applyControlCharPolicy("done\u0007", { bel: "drop" }); // "done"
+ * applyControlCharPolicy("\u001b]0;title\u0007done\u0007", { bel: "drop" }); // "\u001b]0;title\u0007done"
+ * ```
+ */
+export function applyControlCharPolicy(
+  text: string,
+  policy: ControlCharPolicy,
+  state: ControlCharState = { osc: false, last: "" },
+): string {
+  const dropped = new Set(CONTROL_CHAR_NAMES.filter((name) => policy[name] === "drop").map((name) => CONTROL_CHARACTERS[name]));
+  if (!dropped.size) return text;
+  let result = "";
+  for (const char of text) {
+    const afterEsc = state.last === "\u001b";
+    state.last = char;
+    if (state.osc) {
+      if (char === CONTROL_CHARACTERS.bel || (afterEsc && char === "\\")) state.osc = false;
+      result += char; // part of the sequence
+      continue;
+    }
+    if (afterEsc && char === "]") state.osc = true;
+    if (!dropped.has(char)) result += char;
+  }
+  return result;
+}
+
+/**
+ * Parse `--control-chars` entries like "bel=drop"
+ * @throws Error on an unknown character name or action
+ */
+export function parseControlCharPolicy(entries: string[]): ControlCharPolicy {
+  const policy: ControlCharPolicy = {};
+  for (const entry of entries) {
+    const [name, action] = entry.split("=").map((s) => s.trim().toLowerCase());
+    if (!CONTROL_CHAR_NAMES.includes(name as ControlCharName))
+      throw new Error(`Unknown control character "${name}", expected one of: ${CONTROL_CHAR_NAMES.join(", ")}`);
+    if (action !== "forward" && action !== "drop")
+      throw new Error(`Invalid action "${action}" for ${name}, expected forward or drop`);
+    policy[name as ControlCharName] = action;
+  }
+  return policy;
+}
@@ -0,0 +1,71 @@
+import { connect } from "net";
+import { mkdtemp, readFile, rm, stat, writeFile } from "fs/promises";
+import os from "os";
+import path from "path";
+import { afterEach, beforeEach, describe, expect, it } from "vitest";
+import { parseControlCommand, startControlSocket, type ControlCommand } from "./controlSocket";
+
+describe("parseControlCommand", () => {
+  it("should parse send, keys and ping", () => {
+    expect(parseControlCommand("send run the tests")).toEqual({ type: "send", text: "run the tests" });
+    expect(parseControlCommand('keys "\\u001b"')).toEqual({ type: "keys", keys: "\u001b" });
+    expect(parseControlCommand("ping\r")).toEqual({ type: "ping" });
+  });
+
+  it("should reject malformed commands", () => {
+    expect(() => parseControlCommand("send ")).toThrow(/send needs a text/);
+    expect(() => parseControlCommand("keys \\r")).toThrow(/keys needs a JSON string/);
+    expect(() => parseControlCommand("exit")).toThrow(/unknown command "exit"/);
+  });
+});
+
+describe.skipIf(process.platform === "win32")("startControlSocket", () => {
+  let dir: string;
+
+  beforeEach(async () => {
+    dir = await mkdtemp(path.join(os.tmpdir(), "agent-yes-control-"));
+  });
+
+  afterEach(async () => {
+    await rm(dir, { recursive: true, force: true });
+  });
+
+  it("should run commands in order and reply per line", async () => {
+    const socketPath = path.join(dir, "control.sock");
+    const received: ControlCommand[] = [];
+    const server = await startControlSocket(socketPath, async (command) => {
+      received.push(command);
+    });
+
+    expect((await stat(socketPath)).mode & 0o777).toBe(0o600);
+
+    const replies = await new Promise<string>((resolve) => {
+      const client = connect(socketPath, () => client.write("ping\nsend hello\nbogus\n"));
+      let data = "";
+      client.on("data", (chunk) => {
+        data += chunk.toString();
+        if (data.split("\n").length <= 3) return; // wait for all 3 replies
+        client.end();
+        resolve(data);
+      });
+    });
+    await new Promise((resolve) => server.close(resolve));
+
+    expect(replies).toBe('pong\nok\nerror: unknown command "bogus", expected send, keys or ping\n');
+    expect(received).toEqual([{ type: "ping" }, { type: "send", text: "hello" }]);
+  });
+
+  it("should replace a stale socket but never a regular file", async () => {
+    const socketPath = path.join(dir, "control.sock");
+    const stale = await startControlSocket(socketPath, async () => {}); // left behind, as by a crashed run
+    const server = await startControlSocket(socketPath, async () => {});
+    expect((await stat(socketPath)).isSocket()).toBe(true);
+    await new Promise((resolve) => server.close(resolve));
+    await new Promise((resolve) => stale.close(resolve));
+
+    const filePath = path.join(dir, "notes.txt");
+    await writeFile(filePath, "keep me");
+    await expect(startControlSocket(filePath, async () => {})).rejects.toThrow(/is not a socket/);
+    expect(await readFile(filePath, "utf8")).toBe("keep me");
+  });
+});
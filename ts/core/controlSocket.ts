@@ -0,0 +1,119 @@
+import { createServer, type Server } from "net";
+import { lstat, unlink } from "fs/promises";
+import { logger } from "../logger.ts";
+
+/**
+ * Control socket, behind `--control-socket <path>`
+ *
+ * Lets a local process (e.g. a supervising agent) steer a running session
+ * without TTY access. Listens on a Unix socket (a named pipe on Windows),
+ * readable and writable by the current user only. The protocol is newline
+ * delimited, one command per line, each answered with one line:
+ *
+ * - `send <text>`: type `<text>` and press Enter, once the agent is ready
+ * - `keys <json string>`: write raw keys, e.g. `keys "\u001b"` for Esc
+ * - `ping`: answered with `pong`
+ *
+ * Replies are `ok`, `pong`, or `error: <reason>`. See docs/control-socket.md.
+ */
+
+export type ControlCommand = { type: "send"; text: string } | { type: "keys"; keys: string } | { type: "ping" };
+
+/**
+ * Parse one line of the control protocol
+ * @throws Error describing what's wrong with the line
+ *
+ * @example
+ * ```typescript
+ * parseControlCommand("send run the tests"); // { type: "send", text: "run the tests" }
+ * parseControlCommand('keys "\\u001b"'); // { type: "keys", keys: "\u001b" }
+ * ```
+ */
+export function parseControlCommand(line: string): ControlCommand {
+  const trimmed = line.replace(/\r$/, "");
+  const [name = "", ...rest] = trimmed.split(" ");
+  const arg = rest.join(" ");
+  if (name === "ping") return { type: "ping" };
+  if (name === "send") {
+    if (!arg.trim()) throw new Error("send needs a text");
+    return { type: "send", text: arg };
+  }
+  if (name === "keys") {
+    let keys: unknown;
+    try {
+      keys = JSON.parse(arg);
+    } catch {
+      throw new Error("keys needs a JSON string, e.g. keys \"\\r\"");
+    }
+    if (typeof keys !== "string") throw new Error("keys needs a JSON string, e.g. keys \"\\r\"");
+    return { type: "keys", keys };
+  }
+  throw new Error(`unknown command "${name}", expected send, keys or ping`);
+}
+
+/**
+ * Remove a stale socket left by a crashed run, refusing to delete anything else at the path
+ * @throws Error when the path exists and isn't a socket, e.g. a typo naming a real file
+ */
+async function removeStaleSocket(socketPath: string) {
+  const stats = await lstat(socketPath).catch((error: NodeJS.ErrnoException) => {
+    if (error.code === "ENOENT") return null;
+    throw error;
+  });
+  if (!stats) return;
+  if (!stats.isSocket()) throw new Error(`Refusing to replace ${socketPath} with the control socket: it exists and is not a socket`);
+  await unlink(socketPath);
+}
+
+/**
+ * Listen for control commands on `socketPath`
+ * @param onCommand - Runs each command, commands from one client run in order
+ * @returns The server, close it when the session ends
+ * @throws Error when something other than a stale socket is at `socketPath`
+ */
+export async function startControlSocket(
+  socketPath: string,
+  onCommand: (command: ControlCommand) => Promise<unknown>,
+): Promise<Server> {
+  if (process.platform !== "win32") await removeStaleSocket(socketPath);
+
+  const server = createServer((socket) => {
+    let buffer = "";
+    let queue: Promise<unknown> = Promise.resolve();
+    socket.setEncoding("utf8");
+    socket.on("data", (chunk: string) => {
+      buffer += chunk;
+      const lines = buffer.split("\n");
+      buffer = lines.pop() ?? "";
+      for (const line of lines.filter((l) => l.trim())) {
+        queue = queue.then(async () => {
+          try {
+            const command = parseControlCommand(line);
+            logger.debug(`control|${line}`);
+            await onCommand(command);
+            socket.write(command.type === "ping" ? "pong\n" : "ok\n");
+          } catch (error) {
+            socket.write(`error: ${(error as Error).message}\n`);
+          }
+        });
+      }
+    });
+    socket.on("error", (error) => logger.debug(`control|socket error: ${String(error)}`));
+  });
+
+  await new Promise<void>((resolve, reject) => {
+    server.once("error", reject);
+    // local user only: created 0600, a chmod after listen would leave a window where others can connect
+    const umask = process.platform !== "win32" ? process.umask(0o177) : undefined;
+    try {
+      server.listen(socketPath, () => resolve()); // binds synchronously, before the umask is restored
+    } finally {
+      if (umask !== undefined) process.umask(umask);
+    }
+  });
+  server.on("close", () => {
+    if (process.platform !== "win32") unlink(socketPath).catch(() => null);
+  });
+  logger.info(`Control socket listening at ${socketPath}`);
+  return server;
+}
@@ -0,0 +1,42 @@
+import { describe, expect, it } from "vitest";
+import { dewrapLines, isSoftWrapped, logicalLineEndingWith } from "./dewrap";
+
+// "Do you want to proceed?" wrapped by a 16 columns wide terminal
+const screen = ["Bash command", "  rm -rf dist", "", "Do you want to", "proceed?", "❯ 1. Yes", "  2. No"];
+
+describe("isSoftWrapped", () => {
+  it("should tell forced wraps from intended line breaks", () => {
+    expect(isSoftWrapped("Do you want to", "proceed?", 16)).toBe(true);
+    expect(isSoftWrapped("Do you want to", "proceed?", 80)).toBe(false);
+    expect(isSoftWrapped("proceed?", "❯ 1. Yes", 16)).toBe(false);
+    expect(isSoftWrapped("", "proceed?", 16)).toBe(false);
+  });
+});
+
+describe("dewrapLines", () => {
+  it("should join soft-wrapped lines only", () => {
+    expect(dewrapLines(screen, 16)).toEqual([
+      "Bash command",
+      "  rm -rf dist",
+      "",
+      "Do you want to proceed?",
+      "❯ 1. Yes",
+      "  2. No",
+    ]);
+    expect(dewrapLines(screen, 80)).toEqual(screen);
+  });
+});
+
+describe("logicalLineEndingWith", () => {
+  it("should return the whole logical line for its last part", () => {
+    expect(logicalLineEndingWith(screen, "proceed?", 16)).toBe("Do you want to proceed?");
+    expect(logicalLineEndingWith(["a long prompt that", "wraps over", "three lines"], "three lines", 18)).toBe(
+      "a long prompt that wraps over three lines",
+    );
+  });
+
+  it("should return null for lines that weren't wrapped into", () => {
+    expect(logicalLineEndingWith(screen, "❯ 1. Yes", 16)).toBeNull();
+    expect(logicalLineEndingWith(screen, "not on screen", 16)).toBeNull();
+  });
+});
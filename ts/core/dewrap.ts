@@ -0,0 +1,51 @@
+/**
+ * Undo soft wraps of the rendered screen for prompt detection
+ *
+ * On a narrow terminal the agent wraps long prompt text at word boundaries,
+ * so "Do you want to proceed?" can arrive as "Do you want to" and
+ * "proceed?" on two lines, and a pattern matched line by line misses it.
+ * A line counts as soft-wrapped when the first word of the next line
+ * wouldn't have fit after it within the terminal width, i.e. the wrap was
+ * forced rather than an intended line break.
+ */
+
+/** Whether `line` was wrapped into `next` by a `width` columns wide terminal */
+export function isSoftWrapped(line: string, next: string, width: number): boolean {
+  const head = line.trimEnd();
+  const firstWord = next.trimStart().split(/\s/)[0] ?? "";
+  if (!head || !firstWord) return false;
+  return head.length + 1 + firstWord.length > width;
+}
+
+/**
+ * Join soft-wrapped runs of lines into logical lines
+ *
+ * @example
+ * ```typescript
+ * dewrapLines(["Do you want to", "proceed?", "❯ 1. Yes"], 16);
+ * // ["Do you want to proceed?", "❯ 1. Yes"]
+ * ```
+ */
+export function dewrapLines(lines: string[], width: number): string[] {
+  const result: string[] = [];
+  lines.forEach((line, i) => {
+    if (i > 0 && isSoftWrapped(lines[i - 1]!, line, width)) result[result.length - 1] += " " + line.trim();
+    else result.push(line);
+  });
+  return result;
+}
+
+/**
+ * The logical line that the screen line `line` is the last part of
+ *
+ * @param lines - Screen lines
+ * @returns The joined text, or null when `line` isn't on screen or wasn't wrapped into
+ */
+export function logicalLineEndingWith(lines: string[], line: string, width: number): string | null {
+  const end = lines.findLastIndex((l) => l.trimEnd() === line.trimEnd());
+  if (end <= 0) return null;
+  let start = end;
+  while (start > 0 && isSoftWrapped(lines[start - 1]!, lines[start]!, width)) start--;
+  if (start === end) return null;
+  return [lines[start]!.trimEnd(), ...lines.slice(start + 1, end + 1).map((l) => l.trim())].join(" ");
+}
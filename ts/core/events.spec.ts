@@ -0,0 +1,55 @@
+import { closeSync, openSync } from "fs";
+import { mkdtemp, readFile, rm } from "fs/promises";
+import os from "os";
+import path from "path";
+import { afterEach, beforeEach, describe, expect, it } from "vitest";
+import { EventLog } from "./events";
+
+describe("EventLog", () => {
+  let dir: string;
+
+  beforeEach(async () => {
+    dir = await mkdtemp(path.join(os.tmpdir(), "agent-yes-events-"));
+  });
+
+  afterEach(async () => {
+    await rm(dir, { recursive: true, force: true });
+  });
+
+  it("should number and timestamp events in order", async () => {
+    const filePath = path.join(dir, "events.jsonl");
+    const events = new EventLog(filePath, { prefix: "[api] " });
+
+    events.emit({ type: "idle_busy", marker: "esc to interrupt", idleMs: 1000 });
+    events.emit({ type: "idle_busy", marker: "to run in background", idleMs: 1000 });
+    await events.flush();
+
+    const lines = (await readFile(filePath, "utf8")).trim().split("\n").map((line) => JSON.parse(line));
+    expect(lines.map((e) => e.seq)).toEqual([1, 2]);
+    expect(lines[0]).toMatchObject({ pid: process.pid, prefix: "[api] ", type: "idle_busy" });
+    expect(lines[1].ts).toBeGreaterThanOrEqual(lines[0].ts);
+  });
+
+  it("should write to a file descriptor", async () => {
+    const filePath = path.join(dir, "events-fd.jsonl");
+    const fd = openSync(filePath, "a");
+    const events = new EventLog(fd);
+
+    events.emit({ type: "ready" });
+    events.emit({ type: "exit", exitCode: 0, reason: "idle" });
+    await events.flush();
+    closeSync(fd);
+
+    const lines = (await readFile(filePath, "utf8")).trim().split("\n").map((line) => JSON.parse(line));
+    expect(lines.map((e) => e.type)).toEqual(["ready", "exit"]);
+    expect(lines[1]).toMatchObject({ seq: 2, exitCode: 0, reason: "idle" });
+    expect(events.enabled).toBe(true);
+  });
+
+  it("should keep the history without a file", () => {
+    const events = new EventLog(false);
+    events.emit({ type: "idle_busy", marker: "esc to interrupt", idleMs: 1000 });
+    expect(events.history).toHaveLength(1);
+    expect(events.enabled).toBe(false);
+  });
+});
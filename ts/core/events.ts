@@ -0,0 +1,155 @@
+import path from "path";
+import { write } from "fs";
+import { appendFile, mkdir } from "fs/promises";
+import { logger } from "../logger.ts";
+import type { Activity } from "./activity.ts";
+import type { ExitReason } from "./exitHook.ts";
+
+/**
+ * Structured event log (newline-delimited JSON)
+ *
+ * Machine-readable record of what agent-yes did during a session, written
+ * to the path given by `--json-log` (alias `--events-file`), or to an
+ * inherited file descriptor with `--events-fd`. One JSON object per line,
+ * see docs/json-log.md for the schema. Writes are queued, they never hold
+ * up the agent's output on stdout.
+ */
+
+export interface PromptEvent {
+  type: "prompt";
+  rule: string; // rule id of the matched pattern, e.g. "enter[3]" or "typingRespond[1\n][0]"
+  pattern: string; // source of the matched regex
+  action: "enter" | "type" | "key";
+  response: string; // what was sent to the agent
+  category?: string; // prompt category, e.g. "delete", see promptCategories.ts
+  line: string; // the output line that matched
+  dryRun?: boolean; // --dry-run: detected only, nothing was sent
+}
+
+/** The agent is ready for input, once per start (and restart) of the agent */
+export interface ReadyEvent {
+  type: "ready";
+}
+
+/** The idle watcher fired, but the agent still looked busy so it didn't exit */
+export interface IdleBusyEvent {
+  type: "idle_busy";
+  marker: string; // the busy marker found on screen, e.g. "esc to interrupt"
+  idleMs: number; // the --exit-on-idle timeout that elapsed
+}
+
+/** The idle watcher fired without a busy marker on screen, so Enter was pressed in case of a missed prompt */
+export interface IdleNudgeEvent {
+  type: "idle_nudge";
+  count: number; // nudges so far, at most MAX_IDLE_NUDGES
+  idleMs: number; // the --exit-on-idle timeout that elapsed
+}
+
+/** --exit-on-idle fired with nothing on screen saying the agent is busy, the agent is stopped */
+export interface IdleTimeoutEvent {
+  type: "idle_timeout";
+  idleMs: number; // the --exit-on-idle timeout that elapsed
+}
+
+/** The agent crashed and is restarted with its resume args, see `--robust` */
+export interface CrashRestartEvent {
+  type: "crash_restart";
+  exitCode: number | null; // exit code of the crashed agent
+  attempt: number; // restarts so far, starting at 1
+  delayMs: number; // --restart-backoff wait before this restart
+}
+
+/** The session ended, the last event of the log */
+export interface ExitEvent {
+  type: "exit";
+  exitCode: number | null; // the code agent-yes exits with
+  reason: ExitReason; // why the session ended, e.g. "idle", "exited" or "crashed"
+}
+
+/** A usage/rate-limit message was seen with --wait-on-rate-limit, the agent is continued after the reset */
+export interface RateLimitEvent {
+  type: "rate_limit";
+  resetAt: string; // ISO time the limit resets, as announced
+  waitMs: number; // how long agent-yes waits, including a small buffer
+}
+
+/** The agent's activity changed, see activity.ts */
+export interface ActivityEvent {
+  type: "activity";
+  activity: Activity;
+  tool?: string; // with running-tool, e.g. "Bash"
+}
+
+export type AgentEvent =
+  | PromptEvent
+  | ReadyEvent
+  | IdleBusyEvent
+  | IdleNudgeEvent
+  | IdleTimeoutEvent
+  | CrashRestartEvent
+  | RateLimitEvent
+  | ActivityEvent
+  | ExitEvent;
+
+/** Fields added to every event written to the log */
+export interface EventEnvelope {
+  seq: number; // per-session sequence number, starting at 1, for ordering
+  ts: number; // epoch ms with sub-ms precision
+  pid: number; // agent-yes process id, to tell sessions apart in merged logs
+  prefix?: string; // the --prefix label of the instance, if any
+}
+
+export class EventLog {
+  private queue: Promise<unknown> = Promise.resolve();
+  readonly history: AgentEvent[] = []; // all events of this session, also kept when not writing a file
+  private seq = 0;
+
+  /**
+   * @param target - NDJSON file to append to, an open file descriptor (e.g. 3 for a pipe), or false to only keep the history
+   * @param options.prefix - Instance label added to every event, see `--prefix`
+   */
+  constructor(
+    private target: string | number | false = false,
+    private options: { prefix?: string } = {},
+  ) {
+    if (typeof target !== "string" || !target) return;
+    this.queue = mkdir(path.dirname(target), { recursive: true }).catch(() => null);
+  }
+
+  get enabled() {
+    return this.target !== false && this.target !== "";
+  }
+
+  /**
+   * Append an event to the log, writes are serialized to keep line order
+   *
+   * The envelope (seq, ts, ...) is assigned synchronously here, so events
+   * from the output and response tasks get distinct, ordered sequence numbers.
+   */
+  emit(event: AgentEvent) {
+    this.history.push(event);
+    const envelope: EventEnvelope = {
+      seq: ++this.seq,
+      ts: performance.timeOrigin + performance.now(),
+      pid: process.pid,
+      ...(this.options.prefix !== undefined && { prefix: this.options.prefix }),
+    };
+    const target = this.target;
+    if (target === false || target === "") return;
+    const line = JSON.stringify({ ...envelope, ...event }) + "\n";
+    this.queue = this.queue
+      .then(() => (typeof target === "number" ? writeToFd(target, line) : appendFile(target, line)))
+      .catch((error) => logger.debug(`events|failed to write event: ${String(error)}`));
+  }
+
+  /**
+   * Wait for all pending writes to finish
+   */
+  async flush() {
+    await this.queue;
+  }
+}
+
+function writeToFd(fd: number, text: string) {
+  return new Promise<void>((resolve, reject) => write(fd, text, (error) => (error ? reject(error) : resolve())));
+}
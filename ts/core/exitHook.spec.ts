@@ -0,0 +1,32 @@
+import { describe, expect, it } from "vitest";
+import { exitHookEnv, runExitHook } from "./exitHook";
+
+describe("exitHookEnv", () => {
+  it("should describe the outcome with both prefixes", () => {
+    expect(exitHookEnv({ exitCode: 3, reason: "output-limit", logPath: "/tmp/1.log", rawLogPath: false })).toEqual({
+      AGENT_YES_EXIT_CODE: "3",
+      AGENT_YES_REASON: "output-limit",
+      AGENT_YES_LOG_PATH: "/tmp/1.log",
+      CLAUDE_YES_EXIT_CODE: "3",
+      CLAUDE_YES_REASON: "output-limit",
+      CLAUDE_YES_LOG_PATH: "/tmp/1.log",
+    });
+  });
+
+  it("should include the runtime and response count when known", () => {
+    expect(exitHookEnv({ exitCode: 0, reason: "idle", durationMs: 1500, responses: 0 })).toMatchObject({
+      AGENT_YES_DURATION_MS: "1500",
+      AGENT_YES_RESPONSES: "0",
+    });
+  });
+});
+
+describe.skipIf(process.platform === "win32")("runExitHook", () => {
+  it("should pass the outcome to the command", async () => {
+    expect(await runExitHook('test "$AGENT_YES_REASON" = idle', { exitCode: 0, reason: "idle" })).toBe(0);
+  });
+
+  it("should report the command's failure", async () => {
+    expect(await runExitHook("exit 7", { exitCode: 0, reason: "exited" })).toBe(7);
+  });
+});
@@ -0,0 +1,78 @@
+import { execaCommand } from "execa";
+import { logger } from "../logger.ts";
+
+/**
+ * `--on-exit <command>` hook, run after the agent exited and logs are saved
+ *
+ * The command runs in a shell with the outcome in its environment, so
+ * follow-up actions (commit results, notify, start the next task) need no
+ * external glue. A failing hook only warns, unless `--on-exit-affects-code`.
+ */
+
+/** Why the session ended, as passed to the hook */
+export type ExitReason =
+  | "exited"
+  | "crashed"
+  | "idle"
+  | "fatal"
+  | "interrupted"
+  | "output-limit"
+  | "parent-exit"
+  | "timeout";
+
+export interface ExitHookInfo {
+  exitCode: number | null;
+  reason: ExitReason;
+  logPath?: string | false;
+  rawLogPath?: string | false;
+  jsonLogPath?: string;
+  durationMs?: number; // total runtime of the session
+  responses?: number; // prompts auto-answered during the session
+  restarts?: number; // crash restarts during the session
+}
+
+/**
+ * Environment variables describing the outcome, each as AGENT_YES_* and CLAUDE_YES_*
+ *
+ * @example
+ * ```typescript
+ * exitHookEnv({ exitCode: 0, reason: "idle" });
+ * // { AGENT_YES_EXIT_CODE: "0", AGENT_YES_REASON: "idle", CLAUDE_YES_EXIT_CODE: "0", ... }
+ * ```
+ */
+export function exitHookEnv(info: ExitHookInfo): Record<string, string> {
+  const vars: Record<string, string | false | undefined> = {
+    EXIT_CODE: String(info.exitCode ?? ""),
+    REASON: info.reason,
+    LOG_PATH: info.logPath,
+    RAW_LOG_PATH: info.rawLogPath,
+    JSON_LOG_PATH: info.jsonLogPath,
+    DURATION_MS: info.durationMs?.toString(),
+    RESPONSES: info.responses?.toString(),
+    RESTARTS: info.restarts?.toString(),
+  };
+  const env: Record<string, string> = {};
+  for (const [name, value] of Object.entries(vars)) {
+    if (value === undefined || value === false) continue;
+    env[`AGENT_YES_${name}`] = value;
+    env[`CLAUDE_YES_${name}`] = value;
+  }
+  return env;
+}
+
+/**
+ * Run the hook command in a shell, with its output on our stdio
+ * @returns The hook's exit code, 0 on success
+ */
+export async function runExitHook(command: string, info: ExitHookInfo, flag = "--on-exit"): Promise<number> {
+  logger.debug(`onexit|${command}`);
+  const result = await execaCommand(command, {
+    shell: true,
+    stdio: "inherit",
+    reject: false,
+    env: exitHookEnv(info),
+  });
+  const code = result.exitCode ?? 1;
+  if (code !== 0) logger.warn(`${flag} command failed with exit code ${code}: ${command}`);
+  return code;
+}
@@ -0,0 +1,34 @@
+import { describe, expect, it } from "vitest";
+import {
+  checkExpectations,
+  checkFailPatterns,
+  formatExpectationReport,
+  parseExpectations,
+} from "./expectations";
+
+describe("checkExpectations", () => {
+  it("should pass only the matching expectations", () => {
+    const results = checkExpectations("Done.\nAll 12 tests passed\n", parseExpectations(["tests passed$", "0 failed"]));
+    expect(results.map((r) => r.passed)).toEqual([true, false]);
+  });
+
+  it("should report each expectation", () => {
+    const results = checkExpectations("ok", [/ok/, /fail/]);
+    expect(formatExpectationReport(results)).toBe("✓ expect /ok/\n✗ expect /fail/");
+  });
+});
+
+describe("checkFailPatterns", () => {
+  it("should fail when an error banner is in the output", () => {
+    const results = checkFailPatterns("⏺ Done\n  ⎿  API Error: 500\n", [/API Error/, /panicked/]);
+    expect(results.map((r) => r.passed)).toEqual([false, true]);
+    expect(formatExpectationReport(results)).toBe("✗ fail-on /API Error/\n✓ fail-on /panicked/");
+  });
+});
+
+describe("parseExpectations", () => {
+  it("should reject invalid regexes", () => {
+    expect(() => parseExpectations(["(unclosed"])).toThrow(/Invalid --expect pattern "\(unclosed"/);
+    expect(() => parseExpectations(["(unclosed"], "--fail-on")).toThrow(/Invalid --fail-on pattern/);
+  });
+});
@@ -0,0 +1,64 @@
+/**
+ * Post-run assertions, behind `--expect <regex>` and `--fail-on <regex>`
+ *
+ * After the session ends the final rendered output is checked against each
+ * expectation; the run only passes when all `--expect` patterns match and
+ * no `--fail-on` pattern does. Turns an agent run into a testable CI step,
+ * e.g. with `--exit-on-idle`.
+ */
+
+export interface ExpectationResult {
+  kind: "expect" | "fail-on";
+  pattern: RegExp;
+  passed: boolean;
+}
+
+/**
+ * Check the final rendered output against every expectation
+ *
+ * @example
+ * ```typescript
+ * checkExpectations("All tests passed", [/passed/, /0 failed/]);
+ * // [{ kind: "expect", pattern: /passed/, passed: true }, { kind: "expect", pattern: /0 failed/, passed: false }]
+ * ```
+ */
+export function checkExpectations(output: string, patterns: RegExp[]): ExpectationResult[] {
+  return patterns.map((pattern) => ({ kind: "expect", pattern, passed: pattern.test(output) }));
+}
+
+/**
+ * Check the final rendered output against `--fail-on` patterns, each passes when it doesn't match
+ *
+ * @example
+ * ```typescript
+ * checkFailPatterns("API Error: 500", [/API Error/]);
+ * // [{ kind: "fail-on", pattern: /API Error/, passed: false }]
+ * ```
+ */
+export function checkFailPatterns(output: string, patterns: RegExp[]): ExpectationResult[] {
+  return patterns.map((pattern) => ({ kind: "fail-on", pattern, passed: !pattern.test(output) }));
+}
+
+/**
+ * One line per expectation, e.g. "✓ expect /passed/" or "✗ fail-on /Error/"
+ */
+export function formatExpectationReport(results: ExpectationResult[]): string {
+  return results
+    .map(({ kind, pattern, passed }) => `${passed ? "✓" : "✗"} ${kind} ${pattern}`)
+    .join("\n");
+}
+
+/**
+ * Parse `--expect` / `--fail-on` values into regexes, also used for other regex flags like `--only-answer`
+ * @param flag - Flag name used in the error message
+ * @throws Error naming the offending pattern if it isn't a valid regex
+ */
+export function parseExpectations(patterns: string[], flag = "--expect"): RegExp[] {
+  return patterns.map((source) => {
+    try {
+      return new RegExp(source, "m");
+    } catch (error) {
+      throw new Error(`Invalid ${flag} pattern "${source}": ${(error as Error).message}`);
+    }
+  });
+}
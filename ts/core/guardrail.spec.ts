@@ -0,0 +1,38 @@
+import { mkdtemp, rm, writeFile } from "fs/promises";
+import os from "os";
+import path from "path";
+import { afterEach, beforeEach, describe, expect, it } from "vitest";
+import { readGuardrailFile, withGuardrail } from "./guardrail";
+
+describe("readGuardrailFile", () => {
+  let dir: string;
+  beforeEach(async () => {
+    dir = await mkdtemp(path.join(os.tmpdir(), "agent-yes-guardrail-"));
+  });
+  afterEach(async () => {
+    await rm(dir, { recursive: true, force: true });
+  });
+
+  it("should read the file, trimmed", async () => {
+    await writeFile(path.join(dir, "rules.md"), "\nNever force push.\n");
+    expect(readGuardrailFile(path.join(dir, "rules.md"))).toBe("Never force push.");
+  });
+
+  it("should reject missing and empty files", async () => {
+    await writeFile(path.join(dir, "empty.md"), "\n");
+    expect(() => readGuardrailFile(path.join(dir, "empty.md"))).toThrow(/is empty/);
+    expect(() => readGuardrailFile(path.join(dir, "nope.md"))).toThrow(/Can't read --guardrail-file/);
+  });
+});
+
+describe("withGuardrail", () => {
+  it("should lead the prompt", () => {
+    expect(withGuardrail("fix the tests", "Never force push.")).toBe("Never force push.\n\nfix the tests");
+  });
+
+  it("should be sent alone without a prompt, and leave the prompt alone without one", () => {
+    expect(withGuardrail(undefined, "Never force push.")).toBe("Never force push.");
+    expect(withGuardrail("fix the tests", undefined)).toBe("fix the tests");
+    expect(withGuardrail(undefined, undefined)).toBeUndefined();
+  });
+});
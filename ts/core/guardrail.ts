@@ -0,0 +1,44 @@
+import { readFileSync } from "fs";
+
+/**
+ * `--guardrail` / `--guardrail-file`: a standing instruction sent before the prompt
+ *
+ * Auto-answering prompts is risky, so a safety instruction like "Never run
+ * destructive git commands" can be given to agent-yes itself rather than
+ * through each agent's own flags. It is sent ahead of the user's prompt, as
+ * the first message the agent reads, and sent again after every crash
+ * restart so a restored session gets it too.
+ *
+ * Unlike `--system-prompt-file` (systemPrompt.ts), which changes claude's
+ * system prompt, the guardrail is an ordinary message: it works with every
+ * cli, and shows up in the conversation.
+ */
+
+/**
+ * Read a guardrail file
+ * @throws Error when the file is missing or empty
+ */
+export function readGuardrailFile(filePath: string): string {
+  let content: string;
+  try {
+    content = readFileSync(filePath, "utf8");
+  } catch (error) {
+    throw new Error(`Can't read --guardrail-file ${filePath}: ${(error as Error).message}`);
+  }
+  if (!content.trim()) throw new Error(`--guardrail-file ${filePath} is empty`);
+  return content.trim();
+}
+
+/**
+ * Put the guardrail in front of the prompt, or send it alone without one
+ *
+ * @example
+ * ```typescript
+ * withGuardrail("fix the tests", "Never force push."); // "Never force push.\n\nfix the tests"
+ * withGuardrail(undefined, "Never force push."); // "Never force push."
+ * ```
+ */
+export function withGuardrail(prompt: string | undefined, guardrail: string | undefined): string | undefined {
+  if (!guardrail) return prompt;
+  return prompt ? `${guardrail}\n\n${prompt}` : guardrail;
+}
@@ -0,0 +1,44 @@
+import { describe, expect, it, vi } from "vitest";
+import { IdleWaiter } from "../idleWaiter";
+import { waitIdleWithNudges } from "./idleNudge";
+
+const idleWaiter = () => Object.assign(new IdleWaiter(), { checkInterval: 5 });
+
+describe("waitIdleWithNudges", () => {
+  it("should press Enter on an unrecognized prompt and wait for the agent to go on", async () => {
+    const idle = idleWaiter();
+    let screen = "Pick a color:\n❯ red\n  blue"; // no pattern matches, nothing answered it
+    const nudge = vi.fn(() => {
+      screen = "✻ Painting… (esc to interrupt)";
+      const output = setInterval(() => idle.ping(), 5); // the agent works for a while
+      setTimeout(() => {
+        clearInterval(output);
+        screen = "Done.\n> ";
+      }, 60);
+    });
+
+    const isBusy = () => screen.includes("esc to interrupt");
+    expect(await waitIdleWithNudges(idle, 30, { isBusy, nudge, maxNudges: 1 })).toBe(1);
+    expect(nudge).toHaveBeenCalledTimes(1);
+    expect(screen).toBe("Done.\n> ");
+  });
+
+  it("should not nudge a busy agent", async () => {
+    const nudge = vi.fn();
+    expect(await waitIdleWithNudges(idleWaiter(), 10, { isBusy: () => true, nudge })).toBe(0);
+    expect(nudge).not.toHaveBeenCalled();
+  });
+
+  it("should not accept a recognized prompt that was withheld on purpose", async () => {
+    const nudge = vi.fn();
+    const isPromptShown = () => true; // e.g. "Do you want to proceed?" declined by --answer delete=no
+    expect(await waitIdleWithNudges(idleWaiter(), 10, { isBusy: () => false, nudge, isPromptShown })).toBe(0);
+    expect(nudge).not.toHaveBeenCalled();
+  });
+
+  it("should stop nudging after the bound", async () => {
+    const nudge = vi.fn();
+    expect(await waitIdleWithNudges(idleWaiter(), 10, { isBusy: () => false, nudge, maxNudges: 2 })).toBe(2);
+    expect(nudge.mock.calls).toEqual([[1], [2]]);
+  });
+});
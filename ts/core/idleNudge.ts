@@ -0,0 +1,51 @@
+import type { IdleWaiter } from "../idleWaiter.ts";
+
+/**
+ * Press Enter on a seemingly stuck session before giving up on it, for `--nudge-on-idle`
+ *
+ * When the agent shows a prompt none of the patterns recognize, nothing
+ * answers it and the session just sits until `--exit-on-idle` ends it. If
+ * the screen shows no busy marker when idle fires, the agent is likely
+ * waiting on such a prompt, so it gets an Enter (the default choice of most
+ * prompts) and another idle period to react; output after the nudge resets
+ * the timer as usual. Nudges are bounded, a session that doesn't move after
+ * them is considered idle.
+ *
+ * A prompt agent-yes does recognize is never nudged: if it is still on
+ * screen it was left unanswered on purpose (`--answer delete=no`,
+ * `--confirm-destructive`, ...), and Enter would accept its default choice.
+ * For the same reason `--dry-run` and `--only-answer` turn nudging off.
+ */
+
+export const MAX_IDLE_NUDGES = 3;
+
+export interface IdleNudgeOptions {
+  isBusy: () => boolean; // a busy marker is on screen, the agent is working and must not be nudged
+  nudge: (count: number) => void; // press Enter, count starts at 1
+  isPromptShown?: () => boolean; // a recognized prompt is on screen, withheld by the answer policies, must not be accepted
+  maxNudges?: number;
+}
+
+/**
+ * Wait until idle for `ms`, nudging the agent while it looks stuck on a prompt
+ *
+ * @returns The number of nudges sent, once idle without nudges left, or idle while busy
+ *
+ * @example
+ * ```typescript
+ * await waitIdleWithNudges(ctx.idleWaiter, 60000, { isBusy: () => false, nudge: () => shell.write("\r") });
+ * ```
+ */
+export async function waitIdleWithNudges(
+  idleWaiter: IdleWaiter,
+  ms: number,
+  { isBusy, nudge, isPromptShown = () => false, maxNudges = MAX_IDLE_NUDGES }: IdleNudgeOptions,
+): Promise<number> {
+  let nudges = 0;
+  while (true) {
+    await idleWaiter.wait(ms);
+    if (nudges >= maxNudges || isBusy() || isPromptShown()) return nudges;
+    nudge(++nudges);
+    idleWaiter.ping(); // a full idle period for the agent to react
+  }
+}
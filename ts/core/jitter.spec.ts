@@ -0,0 +1,36 @@
+import { describe, expect, it } from "vitest";
+import { createJitter, seededRandom } from "./jitter";
+
+describe("createJitter", () => {
+  it("should be zero by default", () => {
+    const next = createJitter(0, 1);
+    expect([next(), next()]).toEqual([0, 0]);
+  });
+
+  it("should stay within the window", () => {
+    const next = createJitter(50);
+    for (let i = 0; i < 100; i++) expect(next()).toBeGreaterThanOrEqual(0);
+    for (let i = 0; i < 100; i++) expect(next()).toBeLessThanOrEqual(50);
+  });
+
+  it("should repeat the same delays for the same seed", () => {
+    const a = createJitter(1000, 42);
+    const b = createJitter(1000, 42);
+    const c = createJitter(1000, 7);
+    const run = (next: () => number) => Array.from({ length: 5 }, next);
+    const delays = run(a);
+    expect(run(b)).toEqual(delays);
+    expect(run(c)).not.toEqual(delays);
+  });
+});
+
+describe("seededRandom", () => {
+  it("should return floats in [0, 1)", () => {
+    const random = seededRandom(123);
+    for (let i = 0; i < 100; i++) {
+      const value = random();
+      expect(value).toBeGreaterThanOrEqual(0);
+      expect(value).toBeLessThan(1);
+    }
+  });
+});
@@ -0,0 +1,39 @@
+/**
+ * Random extra delay before auto-responses, behind `--answer-delay-jitter`
+ *
+ * Spreads answers over a window so a flaky timing race isn't hit at the
+ * same moment every run. With `--jitter-seed` the delays are a fixed
+ * sequence, so a run that failed can be reproduced.
+ */
+
+/**
+ * mulberry32, a tiny seedable PRNG returning floats in [0, 1)
+ */
+export function seededRandom(seed: number): () => number {
+  let state = seed >>> 0;
+  return () => {
+    state = (state + 0x6d2b79f5) >>> 0;
+    let t = state;
+    t = Math.imul(t ^ (t >>> 15), t | 1);
+    t ^= t + Math.imul(t ^ (t >>> 7), t | 61);
+    return ((t ^ (t >>> 14)) >>> 0) / 4294967296;
+  };
+}
+
+/**
+ * Create a source of jitter delays in [0, maxMs]
+ *
+ * @param maxMs - Largest extra delay, 0 disables jitter
+ * @param seed - Makes the sequence reproducible, Math.random when omitted
+ *
+ * @example
+ * ```typescript
+ * const nextJitter = createJitter(200, 42);
+ * await sleepms(nextJitter()); // same delays on every run with seed 42
+ * ```
+ */
+export function createJitter(maxMs: number, seed?: number): () => number {
+  if (maxMs <= 0) return () => 0;
+  const random = seed === undefined ? Math.random : seededRandom(seed);
+  return () => Math.round(random() * maxMs);
+}
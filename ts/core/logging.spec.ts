@@ -0,0 +1,81 @@
+import { mkdtemp, readFile, rm } from "fs/promises";
+import os from "os";
+import path from "path";
+import { describe, expect, it } from "vitest";
+import { compactLogLines, expandLogPathTemplate, saveDeprecatedLogFile, saveLogFile } from "./logging";
+import { composeOutputFilters, stripControlCharactersFilter } from "./outputFilters";
+
+describe("compactLogLines", () => {
+  it("should collapse identical consecutive lines", () => {
+    expect(compactLogLines("start\nwaiting\nwaiting\nwaiting\nend")).toBe(
+      "start\nwaiting (repeated 3 times)\nend",
+    );
+  });
+
+  it("should collapse lines differing only in spinner glyphs", () => {
+    expect(compactLogLines("✻ Thinking…\n✽ Thinking…\n✶ Thinking…\n⏺ Done")).toBe(
+      "✻ Thinking… (repeated 3 times)\n⏺ Done",
+    );
+  });
+
+  it("should keep lines that differ in other text", () => {
+    const content = "✻ Reading files\n✽ Writing files\n";
+    expect(compactLogLines(content)).toBe(content);
+  });
+
+  it("should keep blank lines as they are", () => {
+    expect(compactLogLines("a\n\n\nb")).toBe("a\n\n\nb");
+  });
+});
+
+describe("expandLogPathTemplate", () => {
+  const vars = { startedAt: new Date("2026-10-15T12:30:00.000Z"), pid: 4242, cwd: "/work/my api", session: "a1b2c3d4" };
+
+  it("should expand all placeholders", () => {
+    expect(expandLogPathTemplate("logs/{cwd}-{timestamp}-{pid}-{session}.log", vars)).toBe(
+      "logs/my_api-2026-10-15T12-30-00-000Z-4242-a1b2c3d4.log",
+    );
+  });
+
+  it("should keep fixed paths and unknown placeholders as they are", () => {
+    expect(expandLogPathTemplate("claude.log", vars)).toBe("claude.log");
+    expect(expandLogPathTemplate("logs/{user}.log", vars)).toBe("logs/{user}.log");
+  });
+
+  it("should name the root directory", () => {
+    expect(expandLogPathTemplate("{cwd}.log", { ...vars, cwd: "/" })).toBe("root.log");
+  });
+});
+
+describe("clean log files", () => {
+  const colored = "\u001b[32m✓ tests passed\u001b[0m\n\u001b[1mdone\u001b[22m";
+
+  it("should strip ANSI from the log files while stdout keeps it", async () => {
+    const dir = await mkdtemp(path.join(os.tmpdir(), "agent-yes-clean-log-"));
+    try {
+      const logPath = path.join(dir, "session.log");
+      const logFile = path.join(dir, "out", "run.log");
+      await saveLogFile(logPath, colored, { clean: true });
+      await saveDeprecatedLogFile(logFile, colored, false, { clean: true });
+      expect(await readFile(logPath, "utf8")).toBe("✓ tests passed\ndone");
+      expect(await readFile(logFile, "utf8")).not.toContain("\u001b[");
+
+      const removeControlCharactersFromStdout = false; // the stdout setting is independent
+      const stdoutFilter = composeOutputFilters(removeControlCharactersFromStdout && stripControlCharactersFilter);
+      expect(stdoutFilter(colored)).toContain("\u001b[32m");
+    } finally {
+      await rm(dir, { recursive: true, force: true });
+    }
+  });
+
+  it("should keep ANSI in the log files by default", async () => {
+    const dir = await mkdtemp(path.join(os.tmpdir(), "agent-yes-clean-log-"));
+    try {
+      const logPath = path.join(dir, "session.log");
+      await saveLogFile(logPath, colored);
+      expect(await readFile(logPath, "utf8")).toBe(colored);
+    } finally {
+      await rm(dir, { recursive: true, force: true });
+    }
+  });
+});
@@ -3,6 +3,7 @@ import { mkdir, writeFile } from "fs/promises";
 import winston from "winston";
 import { logger } from "../logger.ts";
 import { PidStore } from "../pidStore.ts";
+import { removeControlCharacters } from "../removeControlCharacters.ts";
 
 /**
  * Log path management for agent sessions
@@ -13,6 +14,7 @@ export interface LogPaths {
   rawLogPath: string | false;
   rawLinesLogPath: string | false;
   debuggingLogsPath: string | false;
+  stderrLogPath: string | false; // only written with --split-stderr
 }
 
 /**
@@ -26,12 +28,14 @@ export function initializeLogPaths(pidStore: PidStore, pid: number): LogPaths {
   const rawLogPath = path.resolve(path.dirname(logPath), `${pid}.raw.log`);
   const rawLinesLogPath = path.resolve(path.dirname(logPath), `${pid}.lines.log`);
   const debuggingLogsPath = path.resolve(path.dirname(logPath), `${pid}.debug.log`);
+  const stderrLogPath = path.resolve(path.dirname(logPath), `${pid}.stderr.log`);
 
   return {
     logPath,
     rawLogPath,
     rawLinesLogPath,
     debuggingLogsPath,
+    stderrLogPath,
   };
 }
 
@@ -50,34 +54,99 @@ export function setupDebugLogging(debuggingLogsPath: string | false) {
   }
 }
 
+// spinner frames of the supported clis (claude, codex, gemini, braille spinners)
+export const SPINNER_GLYPHS = /[✻✽✶✳✢·*◦•⠋⠙⠹⠸⠼⠴⠦⠧⠇⠏]/g;
+
+/**
+ * Collapse runs of repeated lines in rendered logs, for `--compact-logs`
+ *
+ * Consecutive lines that are identical, or differ only in spinner glyphs,
+ * are kept once with a `(repeated N times)` annotation.
+ *
+ * @example
+ * ```typescript
+ * compactLogLines("✻ Thinking…\n✽ Thinking…\n✶ Thinking…\ndone");
+ * // "✻ Thinking… (repeated 3 times)\ndone"
+ * ```
+ */
+export function compactLogLines(content: string): string {
+  const lines = content.split("\n");
+  const result: string[] = [];
+  for (let i = 0; i < lines.length; ) {
+    const key = lines[i]!.replace(SPINNER_GLYPHS, "*");
+    let count = 1;
+    while (i + count < lines.length && lines[i + count]!.replace(SPINNER_GLYPHS, "*") === key) count++;
+    const blank = !lines[i]!.trim(); // keep blank lines as they are, annotating them adds noise
+    if (count > 1 && !blank) result.push(`${lines[i]} (repeated ${count} times)`);
+    else result.push(...lines.slice(i, i + count));
+    i += count;
+  }
+  return result.join("\n");
+}
+
 /**
  * Save rendered terminal output to log file
  * @param logPath Path to log file
  * @param content Rendered content to save
+ * @param options.clean Remove ANSI control sequences, see --clean-log-file; stdout is not affected
  */
-export async function saveLogFile(logPath: string | false, content: string) {
+export async function saveLogFile(logPath: string | false, content: string, { clean = false } = {}) {
   if (!logPath) return;
 
   await mkdir(path.dirname(logPath), { recursive: true }).catch(() => null);
-  await writeFile(logPath, content).catch(() => null);
+  await writeFile(logPath, clean ? removeControlCharacters(content) : content).catch(() => null);
   logger.info(`Full logs saved to ${logPath}`);
 }
 
+export interface LogPathVars {
+  startedAt: Date; // for {timestamp}
+  pid: number; // for {pid}
+  cwd: string; // for {cwd}, only its last segment is used
+  session: string; // for {session}, unique per run
+}
+
+const fileNameSafe = (text: string) => text.replace(/[^\w.-]+/g, "_");
+
+/**
+ * Expand placeholders in a `--logFile` path, so repeated and parallel runs don't overwrite each other
+ *
+ * Placeholders: `{timestamp}` (start time, e.g. `2026-10-15T12-30-00-000Z`),
+ * `{pid}`, `{cwd}` (name of the working directory) and `{session}` (a random
+ * id, unique per run). Unknown placeholders are kept as they are.
+ *
+ * @example
+ * ```typescript
+ * expandLogPathTemplate("logs/{cwd}-{timestamp}.log", { startedAt, pid: 42, cwd: "/work/api", session: "a1b2c3d4" });
+ * // "logs/api-2026-10-15T12-30-00-000Z.log"
+ * ```
+ */
+export function expandLogPathTemplate(template: string, vars: LogPathVars): string {
+  const values: Record<string, string> = {
+    timestamp: vars.startedAt.toISOString().replace(/[:.]/g, "-"),
+    pid: String(vars.pid),
+    cwd: fileNameSafe(path.basename(path.resolve(vars.cwd)) || "root"),
+    session: fileNameSafe(vars.session),
+  };
+  return template.replace(/\{(\w+)\}/g, (placeholder, name: string) => values[name] ?? placeholder);
+}
+
 /**
  * Save logs to deprecated logFile option (for backward compatibility)
  * @param logFile User-specified log file path
  * @param content Rendered content to save
  * @param verbose Whether to log verbose messages
+ * @param options.clean Remove ANSI control sequences, see --clean-log-file
  */
 export async function saveDeprecatedLogFile(
   logFile: string | undefined,
   content: string,
   verbose: boolean,
+  { clean = false } = {},
 ) {
   if (!logFile) return;
 
   if (verbose) logger.info(`Writing rendered logs to ${logFile}`);
   const logFilePath = path.resolve(logFile);
   await mkdir(path.dirname(logFilePath), { recursive: true }).catch(() => null);
-  await writeFile(logFilePath, content);
+  await writeFile(logFilePath, clean ? removeControlCharacters(content) : content);
 }
@@ -0,0 +1,31 @@
+import { afterEach, beforeEach, describe, expect, it, vi } from "vitest";
+import { IdleWaiter } from "../idleWaiter";
+import { limitRuntime } from "./maxRuntime";
+
+describe("limitRuntime", () => {
+  beforeEach(() => vi.useFakeTimers());
+  afterEach(() => vi.useRealTimers());
+
+  it("should fire during a continuous stream of output", async () => {
+    const idle = new IdleWaiter();
+    const output = setInterval(() => idle.ping(), 10); // never idle
+    const onTimeout = vi.fn();
+    let exitedOnIdle = false;
+    idle.wait(500).then(() => (exitedOnIdle = true));
+
+    limitRuntime(1000, onTimeout);
+    await vi.advanceTimersByTimeAsync(999);
+    expect(onTimeout).not.toHaveBeenCalled();
+    await vi.advanceTimersByTimeAsync(1);
+    expect(onTimeout).toHaveBeenCalledTimes(1);
+    expect(exitedOnIdle).toBe(false);
+    clearInterval(output);
+  });
+
+  it("should not fire once cancelled", async () => {
+    const onTimeout = vi.fn();
+    limitRuntime(1000, onTimeout)();
+    await vi.advanceTimersByTimeAsync(2000);
+    expect(onTimeout).not.toHaveBeenCalled();
+  });
+});
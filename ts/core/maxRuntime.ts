@@ -0,0 +1,22 @@
+/**
+ * `--max-runtime`: a hard wall-clock limit for the session
+ *
+ * `--exit-on-idle` never fires while the agent keeps printing, e.g. a
+ * spinner or a runaway loop, so CI runs can't rely on it for an upper
+ * bound. The runtime limit doesn't care about output: once it is reached
+ * the agent is stopped (SIGTERM, then SIGKILL after `--kill-timeout`) and
+ * agent-yes exits with 124, like `timeout(1)`.
+ */
+
+/** Exit code when the session was ended by `--max-runtime`, as `timeout(1)` uses */
+export const EXIT_CODE_MAX_RUNTIME = 124;
+
+/**
+ * Call `onTimeout` once, `ms` after now
+ * @returns A function cancelling the limit, e.g. when the session ended first
+ */
+export function limitRuntime(ms: number, onTimeout: () => void): () => void {
+  const timer = setTimeout(onTimeout, ms);
+  timer.unref?.(); // don't keep a finished process alive
+  return () => clearTimeout(timer);
+}
@@ -0,0 +1,44 @@
+import { TerminalTextRender } from "terminal-render";
+import { describe, expect, it } from "vitest";
+import { IdleWaiter } from "../idleWaiter";
+import { MeaningfulOutputFilter, screenActivityKey } from "./meaningfulOutput";
+
+describe("screenActivityKey", () => {
+  it("should ignore spinner frames and status counters", () => {
+    expect(screenActivityKey("✻ Thinking… (3s · esc to interrupt)\n> ")).toBe(
+      screenActivityKey("✽ Thinking… (14s · ↑ 2 tokens · esc to interrupt)\n> "),
+    );
+  });
+
+  it("should keep changes outside of status lines", () => {
+    expect(screenActivityKey("Ran 3 tests")).not.toBe(screenActivityKey("Ran 4 tests"));
+    expect(screenActivityKey("✻ Thinking…")).not.toBe(screenActivityKey("✻ Thinking…\n⏺ Bash(ls)"));
+  });
+});
+
+describe("MeaningfulOutputFilter", () => {
+  it("should report the first screen and real changes only", () => {
+    const filter = new MeaningfulOutputFilter();
+    expect(filter.changed("✻ Thinking… (1s · esc to interrupt)")).toBe(true);
+    expect(filter.changed("✶ Thinking… (2s · esc to interrupt)")).toBe(false);
+    expect(filter.changed("⏺ Bash(npm test)\n✶ Thinking… (2s · esc to interrupt)")).toBe(true);
+  });
+
+  it("should let a constantly animating spinner go idle", async () => {
+    const render = new TerminalTextRender();
+    const filter = new MeaningfulOutputFilter();
+    const idle = new IdleWaiter();
+    idle.checkInterval = 5;
+    const frames = ["✻", "✽", "✶", "✳", "✢"];
+    let i = 0;
+    const spinner = setInterval(() => {
+      render.write(`\r${frames[i % frames.length]} Thinking… (${i++}s · esc to interrupt)`);
+      if (filter.changed(render.render())) idle.ping();
+    }, 5);
+    const start = Date.now();
+    await idle.wait(50);
+    clearInterval(spinner);
+    expect(i).toBeGreaterThan(3);
+    expect(Date.now() - start).toBeLessThan(500);
+  });
+});
@@ -0,0 +1,49 @@
+import { SPINNER_GLYPHS } from "./logging.ts";
+
+/**
+ * Tell real output apart from spinner animation, for `--idle-mode stable-screen`
+ *
+ * While an agent waits, stuck on a hung tool or a network call, its spinner
+ * keeps redrawing the status line ("✻ Thinking… (3s · esc to interrupt)")
+ * and every redraw counts as output, so the idle timer never fires. Screens
+ * are compared with spinner glyphs dropped and the counters on status lines
+ * blanked, so only changes a reader would notice count as activity.
+ *
+ * `output-silence`, the default, counts every byte of output instead.
+ * `--idle-ignore-spinners` is a shorthand for `--idle-mode stable-screen`.
+ */
+
+export const IDLE_MODES = ["output-silence", "stable-screen"] as const;
+export type IdleMode = (typeof IDLE_MODES)[number];
+
+// status lines: a spinner frame in front, or the interrupt hint of a busy agent
+const STATUS_LINE = /^\s*[✻✽✶✳✢·*◦•⠋⠙⠹⠸⠼⠴⠦⠧⠇⠏]\s|to interrupt/;
+
+/**
+ * Reduce a rendered screen to what matters for activity
+ *
+ * @example
+ * ```typescript
+ * screenActivityKey("✻ Thinking… (3s · esc to interrupt)") === screenActivityKey("✽ Thinking… (4s · esc to interrupt)"); // true
+ * ```
+ */
+export function screenActivityKey(screen: string): string {
+  return screen
+    .split("\n")
+    .map((line) => (STATUS_LINE.test(line) ? line.replace(/\d+/g, "#") : line).replace(SPINNER_GLYPHS, ""))
+    .join("\n")
+    .trimEnd();
+}
+
+/** Remembers the last screen, to report whether a new one changed meaningfully */
+export class MeaningfulOutputFilter {
+  private lastKey?: string;
+
+  /** Whether `screen` differs from the previous one beyond spinner frames and counters */
+  changed(screen: string): boolean {
+    const key = screenActivityKey(screen);
+    if (key === this.lastKey) return false;
+    this.lastKey = key;
+    return true;
+  }
+}
@@ -0,0 +1,47 @@
+import { describe, expect, it } from "vitest";
+import { parseMenuOptions, selectLabelKeys } from "./menuSelect";
+
+const menu = [
+  "Do you want to proceed?",
+  "  1. Yes",
+  "  2. Yes, and don't ask again for rm commands",
+  "❯ 3. No, and tell Claude what to do differently (esc)",
+].join("\n");
+
+describe("parseMenuOptions", () => {
+  it("should list the numbered options and the highlighted one", () => {
+    expect(parseMenuOptions(menu)).toEqual([
+      { index: 1, label: "Yes", selected: false },
+      { index: 2, label: "Yes, and don't ask again for rm commands", selected: false },
+      { index: 3, label: "No, and tell Claude what to do differently (esc)", selected: true },
+    ]);
+  });
+
+  it("should only take the last menu on screen", () => {
+    const screen = `Earlier output\n❯ 1. Old option\n  2. Other\n\n${menu}`;
+    expect(parseMenuOptions(screen)?.map((option) => option.label)[0]).toBe("Yes");
+    expect(parseMenuOptions(screen)).toHaveLength(3);
+  });
+
+  it("should return null without a menu or a highlighted option", () => {
+    expect(parseMenuOptions("Do you want to proceed?")).toBeNull();
+    expect(parseMenuOptions("  1. Yes\n  2. No")).toBeNull();
+  });
+});
+
+describe("selectLabelKeys", () => {
+  it("should move the highlight to the exact label, then press Enter", () => {
+    expect(selectLabelKeys(menu, "yes")).toBe("\u001b[A\u001b[A\r");
+    expect(selectLabelKeys("❯ 1. No\n  2. Yes", "Yes")).toBe("\u001b[B\r");
+  });
+
+  it("should fall back to a label containing the text", () => {
+    expect(selectLabelKeys(menu, "don't ask again")).toBe("\u001b[A\r");
+    expect(selectLabelKeys(menu, "tell Claude")).toBe("\r"); // already highlighted
+  });
+
+  it("should return null when the menu has no such option or can't be parsed", () => {
+    expect(selectLabelKeys(menu, "Always")).toBeNull();
+    expect(selectLabelKeys("Do you want to proceed?", "Yes")).toBeNull();
+  });
+});
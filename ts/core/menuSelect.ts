@@ -0,0 +1,69 @@
+/**
+ * `--select-label`: pick the menu option with a given label instead of the highlighted one
+ *
+ * Permission menus are accepted with Enter, which picks whatever option is
+ * highlighted, usually "1. Yes". A prompt highlighting another option by
+ * default would get that one. With a label, the numbered options on screen
+ * (`❯ 1. Yes`, `  2. No`) are parsed and the highlight is moved to the
+ * option with that label before pressing Enter, like `--pick-session` does
+ * in the session picker. Arrow keys are used rather than the option's
+ * number, which some menus take as the answer already, leaving the Enter
+ * for the next screen. When no option matches, Enter is sent as usual.
+ */
+
+export interface MenuOption {
+  index: number; // the option number shown, 1-based
+  label: string;
+  selected: boolean; // highlighted with ❯
+}
+
+const OPTION = /^\s*(❯)?\s*(\d+)\.\s+(.*\S)\s*$/;
+
+/**
+ * List the options of the last numbered menu on screen
+ *
+ * @param screen - Rendered screen text, e.g. the bottom lines around the prompt
+ * @returns The options from the last `1.` on, or null when no menu with a highlighted option is shown
+ *
+ * @example
+ * ```typescript
+ * parseMenuOptions("Do you want to proceed?\n❯ 1. Yes\n  2. No");
+ * // [{ index: 1, label: "Yes", selected: true }, { index: 2, label: "No", selected: false }]
+ * ```
+ */
+export function parseMenuOptions(screen: string): MenuOption[] | null {
+  const matches = screen.split("\n").map((line) => line.match(OPTION));
+  const start = matches.findLastIndex((match) => match?.[2] === "1");
+  if (start < 0) return null;
+  const options = matches
+    .slice(start)
+    .filter((match) => match !== null)
+    .map(([, marker, index, label]) => ({ index: Number(index), label: label!, selected: Boolean(marker) }));
+  return options.some((option) => option.selected) ? options : null;
+}
+
+/**
+ * Keys moving the highlight to the option labeled `label`, then Enter
+ *
+ * An option labeled exactly `label` (ignoring case) wins over one merely
+ * containing it, so "Yes" picks "Yes" rather than "Yes, and don't ask again".
+ *
+ * @returns The keys, or null when the menu can't be parsed or has no such option
+ *
+ * @example
+ * ```typescript
+ * selectLabelKeys("❯ 1. No\n  2. Yes", "Yes"); // "\u001b[B\r"
+ * ```
+ */
+export function selectLabelKeys(screen: string, label: string): string | null {
+  const options = parseMenuOptions(screen);
+  const selected = options?.find((option) => option.selected);
+  if (!options || !selected) return null;
+  const wanted = label.trim().toLowerCase();
+  const target =
+    options.find((option) => option.label.toLowerCase() === wanted) ??
+    options.find((option) => option.label.toLowerCase().includes(wanted));
+  if (!target) return null;
+  const moves = target.index - selected.index;
+  return (moves > 0 ? "\u001b[B" : "\u001b[A").repeat(Math.abs(moves)) + "\r";
+}
@@ -0,0 +1,101 @@
+import { describe, expect, it } from "vitest";
+import { IdleWaiter } from "../idleWaiter";
+import { ReadyManager } from "../ReadyManager";
+import { sendMessage, typeText, waitForInput } from "./messaging";
+import { AgentStateTracker } from "./agentState";
+
+const createContext = (typingDelay?: number) => {
+  const writes: string[] = [];
+  const context = {
+    shell: { write: (data: string) => void writes.push(data) },
+    idleWaiter: new IdleWaiter(),
+    stdinReady: new ReadyManager(),
+    nextStdout: new ReadyManager(),
+    typingDelay,
+  };
+  return { context, writes };
+};
+
+describe("typeText", () => {
+  it("should write the whole text at once by default", async () => {
+    const { context, writes } = createContext();
+    await typeText(context, "hello");
+    expect(writes).toEqual(["hello"]);
+  });
+
+  it("should write one character at a time when typing is simulated", async () => {
+    const { context, writes } = createContext(5);
+    const start = Date.now();
+    await typeText(context, "héllo");
+    expect(writes).toEqual(["h", "é", "l", "l", "o"]);
+    expect(Date.now() - start).toBeGreaterThanOrEqual(20);
+  });
+
+  it("should keep the idle waiter busy while typing", async () => {
+    const { context } = createContext(5);
+    const before = context.idleWaiter.lastActivityTime;
+    await typeText(context, "abc");
+    expect(context.idleWaiter.lastActivityTime).toBeGreaterThanOrEqual(before);
+  });
+});
+
+describe("sendMessage", () => {
+  it("should write right away without input gating", async () => {
+    const { context, writes } = createContext();
+    // stdinReady and nextStdout are never ready, a gated send would hang
+    await sendMessage({ ...context, inputGating: false }, "run the tests");
+    expect(writes).toEqual(["run the tests", "\r"]);
+  });
+
+  it("should hold messages until the agent awaits input", async () => {
+    const { context, writes } = createContext();
+    const agentState = new AgentStateTracker();
+    context.stdinReady.ready(); // started, but not at its prompt yet
+    void sendMessage({ ...context, agentState }, "next task");
+    await new Promise((resolve) => setTimeout(resolve, 10));
+    expect(writes).toEqual([]);
+
+    agentState.observe(true, "> ");
+    await new Promise((resolve) => setTimeout(resolve, 10));
+    expect(writes).toEqual(["next task"]);
+  });
+
+  it("should mark the agent busy after sending", async () => {
+    const { context } = createContext();
+    const agentState = new AgentStateTracker();
+    agentState.observe(true, "> ");
+    await sendMessage({ ...context, agentState, inputGating: false }, "run the tests");
+    expect(agentState.state).toBe("busy");
+  });
+});
+
+describe("waitForInput", () => {
+  it("should resolve once the agent awaits input", async () => {
+    const { context } = createContext();
+    const agentState = new AgentStateTracker();
+    const waiting = waitForInput({ ...context, agentState, readyTimeout: 1000 });
+    agentState.observe(true, "> ");
+    expect(await waiting).toBe(true);
+  });
+
+  it("should give up after readyTimeout of silence, e.g. when the prompt is never seen after a message", async () => {
+    const { context } = createContext();
+    const agentState = new AgentStateTracker();
+    agentState.force();
+    agentState.sent(); // busy, and no ready pattern will ever reopen the gate
+    expect(await waitForInput({ ...context, idleWaiter: new IdleWaiter(5), agentState, readyTimeout: 20 })).toBe(false);
+  });
+
+  it("should not give up while the agent keeps printing", async () => {
+    const { context } = createContext();
+    const idleWaiter = new IdleWaiter(5);
+    const agentState = new AgentStateTracker();
+    const output = setInterval(() => idleWaiter.ping(), 5);
+    let settled = false;
+    void waitForInput({ ...context, idleWaiter, agentState, readyTimeout: 30 }).then(() => (settled = true));
+    await new Promise((resolve) => setTimeout(resolve, 80));
+    expect(settled).toBe(false);
+    clearInterval(output);
+    agentState.observe(true, "> ");
+  });
+});
@@ -1,6 +1,7 @@
 import { IdleWaiter } from "../idleWaiter.ts";
 import { ReadyManager } from "../ReadyManager.ts";
 import { logger } from "../logger.ts";
+import type { AgentStateTracker } from "./agentState.ts";
 
 /**
  * Message sending utilities for agent CLI interaction
@@ -11,6 +12,28 @@ export interface MessageContext {
   idleWaiter: IdleWaiter;
   stdinReady: ReadyManager;
   nextStdout: ReadyManager;
+  agentState?: AgentStateTracker; // when given, messages wait until the agent awaits input, not just until it started
+  typingDelay?: number; // ms between characters when typing messages, 0 writes them at once
+  inputGating?: boolean; // false writes right away, without waiting for ready/idle/echo, see --no-input-gating
+  readyTimeout?: number; // ms of silence after which a message is sent anyway, see waitForInput
+}
+
+/**
+ * Write text to the shell, optionally one character at a time like a human typist
+ *
+ * Pings the idle waiter for every character so a slow typist doesn't look idle.
+ *
+ * @param context Message context with shell and state managers
+ * @param text Text to write
+ */
+export async function typeText(context: MessageContext, text: string) {
+  const delay = context.typingDelay ?? 0;
+  if (delay <= 0) return context.shell.write(text);
+  for (const char of text) {
+    context.shell.write(char);
+    context.idleWaiter.ping();
+    await new Promise((resolve) => setTimeout(resolve, delay));
+  }
 }
 
 /**
@@ -19,6 +42,8 @@ export interface MessageContext {
  * @param waitms Milliseconds to wait for idle before sending Enter (default: 1000)
  */
 export async function sendEnter(context: MessageContext, waitms = 1000) {
+  if (context.inputGating === false) return context.shell.write("\r"); // scripted runs, no waits or retries
+
   // wait for idle for a bit to let agent cli finish rendering
   const st = Date.now();
   await context.idleWaiter.wait(waitms); // wait for idle a while
@@ -55,6 +80,27 @@ export async function sendEnter(context: MessageContext, waitms = 1000) {
   ]);
 }
 
+/**
+ * Wait until the agent awaits input, or has been silent for `readyTimeout`
+ *
+ * The prompt may never be recognized again, e.g. after a restart into an
+ * agent version the ready patterns don't match. An agent still working keeps
+ * printing (spinners, output), so silence that long means nobody is going
+ * to open the gate, and the message is sent anyway.
+ *
+ * @returns true when the agent was ready, false when the wait timed out
+ */
+export async function waitForInput(context: MessageContext): Promise<boolean> {
+  const gate = context.agentState?.awaitingInput ?? context.stdinReady;
+  if (!context.readyTimeout) return await gate.wait().then(() => true);
+  const ready = await Promise.race([
+    gate.wait().then(() => true),
+    context.idleWaiter.wait(context.readyTimeout).then(() => false),
+  ]);
+  if (!ready) logger.warn(`[agent-yes] agent not ready after ${context.readyTimeout}ms of silence (--ready-timeout), sending anyway`);
+  return ready;
+}
+
 /**
  * Send a message to the shell
  * @param context Message context with shell and state managers
@@ -66,15 +112,17 @@ export async function sendMessage(
   message: string,
   { waitForReady = true } = {},
 ) {
-  if (waitForReady) await context.stdinReady.wait();
+  const gated = context.inputGating !== false;
+  if (waitForReady && gated) await waitForInput(context);
   // show in-place message: write msg and move cursor back start
   logger.debug(`send  |${message}`);
   context.nextStdout.unready();
-  context.shell.write(message);
+  await typeText(context, message);
   context.idleWaiter.ping(); // just sent a message, wait for echo
   logger.debug(`waiting next stdout|${message}`);
-  await context.nextStdout.wait();
+  if (gated) await context.nextStdout.wait();
   logger.debug(`sending enter`);
   await sendEnter(context, 1000);
+  context.agentState?.sent();
   logger.debug(`sent enter`);
 }
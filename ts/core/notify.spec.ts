@@ -0,0 +1,59 @@
+import { createServer, type IncomingMessage } from "http";
+import type { AddressInfo } from "net";
+import { describe, expect, it } from "vitest";
+import { postNotification, runNotifyCommand } from "./notify";
+import type { SessionSummary } from "./sessionSummary";
+
+const summary: SessionSummary = {
+  cli: "claude",
+  exitCode: 0,
+  reason: "idle",
+  durationMs: 5400000,
+  responses: 42,
+  restarts: 0,
+};
+
+const readBody = async (request: IncomingMessage) => {
+  let body = "";
+  for await (const chunk of request) body += chunk;
+  return body;
+};
+
+describe("postNotification", () => {
+  it("should POST the summary as JSON", async () => {
+    const received: { method?: string; type?: string; body: string }[] = [];
+    const server = createServer(async (request, response) => {
+      received.push({ method: request.method, type: request.headers["content-type"], body: await readBody(request) });
+      response.end("ok");
+    });
+    await new Promise<void>((resolve) => server.listen(0, "127.0.0.1", resolve));
+    const { port } = server.address() as AddressInfo;
+    try {
+      expect(await postNotification(`http://127.0.0.1:${port}/hook`, summary)).toBe(true);
+      expect(received).toHaveLength(1);
+      expect(received[0]).toMatchObject({ method: "POST", type: "application/json" });
+      expect(JSON.parse(received[0]!.body)).toEqual(summary);
+    } finally {
+      server.close();
+    }
+  });
+
+  it("should only report failures", async () => {
+    const server = createServer((_, response) => response.writeHead(500).end());
+    await new Promise<void>((resolve) => server.listen(0, "127.0.0.1", resolve));
+    const { port } = server.address() as AddressInfo;
+    server.close();
+    await expect(postNotification(`http://127.0.0.1:${port}/closed`, summary)).resolves.toBe(false);
+  });
+});
+
+describe.skipIf(process.platform === "win32")("runNotifyCommand", () => {
+  it("should pass the summary as env vars", async () => {
+    const check = 'test "$AGENT_YES_REASON" = idle && test "$AGENT_YES_DURATION_MS" = 5400000 && test "$AGENT_YES_RESPONSES" = 42';
+    expect(await runNotifyCommand(check, summary)).toBe(true);
+  });
+
+  it("should report a failing command without throwing", async () => {
+    expect(await runNotifyCommand("exit 3", summary)).toBe(false);
+  });
+});
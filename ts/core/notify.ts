@@ -0,0 +1,51 @@
+import { logger } from "../logger.ts";
+import { runExitHook, type ExitHookInfo } from "./exitHook.ts";
+import type { SessionSummary } from "./sessionSummary.ts";
+
+/**
+ * `--notify-url` / `--notify-command`: tell someone the session finished
+ *
+ * For long unattended runs, e.g. overnight in tmux. The URL gets a small
+ * JSON POST, the command runs in a shell with the same values as
+ * `AGENT_YES_*` env vars (like `--on-exit`). Notifications are best-effort:
+ * a failure only warns and never changes agent-yes's exit code.
+ */
+
+const NOTIFY_TIMEOUT_MS = 10_000; // don't hang the exit on a slow endpoint
+
+/**
+ * POST the summary as JSON to a webhook
+ * @returns Whether the endpoint accepted it
+ */
+export async function postNotification(url: string, summary: SessionSummary): Promise<boolean> {
+  try {
+    const response = await fetch(url, {
+      method: "POST",
+      headers: { "content-type": "application/json" },
+      body: JSON.stringify(summary),
+      signal: AbortSignal.timeout(NOTIFY_TIMEOUT_MS),
+    });
+    if (response.ok) return true;
+    logger.warn(`--notify-url ${url} answered ${response.status} ${response.statusText}`);
+  } catch (error) {
+    logger.warn(`--notify-url ${url} failed: ${error instanceof Error ? error.message : String(error)}`);
+  }
+  return false;
+}
+
+/**
+ * Run the notify command with the summary in its environment
+ * @returns Whether the command succeeded
+ */
+export async function runNotifyCommand(
+  command: string,
+  summary: SessionSummary,
+  paths: Pick<ExitHookInfo, "logPath" | "rawLogPath" | "jsonLogPath"> = {},
+): Promise<boolean> {
+  try {
+    return (await runExitHook(command, { ...summary, ...paths }, "--notify-command")) === 0;
+  } catch (error) {
+    logger.warn(`--notify-command failed: ${error instanceof Error ? error.message : String(error)}`);
+    return false;
+  }
+}
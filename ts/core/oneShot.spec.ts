@@ -0,0 +1,67 @@
+import { describe, expect, it } from "vitest";
+import { isPrintMode, runOneShot } from "./oneShot";
+
+const env = process.env as Record<string, string>;
+
+describe("isPrintMode", () => {
+  it("should detect claude print mode", () => {
+    expect(isPrintMode("claude", ["--print", "say hi"])).toBe(true);
+    expect(isPrintMode("claude", ["-p", "say hi"])).toBe(true);
+    expect(isPrintMode("claude", ["--model=sonnet"])).toBe(false);
+    expect(isPrintMode("gemini", ["-p", "say hi"])).toBe(false);
+  });
+});
+
+describe.skipIf(process.platform === "win32")("runOneShot", () => {
+  it("should capture the whole output and pass it through", async () => {
+    const chunks: string[] = [];
+    const result = await runOneShot({
+      cli: "claude",
+      cliConf: { binary: "sh" },
+      cliArgs: ["-c", "printf 'hello\\n'; sleep 0.05; printf 'world\\n'"],
+      cwd: process.cwd(),
+      env,
+      write: (chunk) => void chunks.push(chunk),
+    });
+    expect(result).toEqual({ exitCode: 0, output: "hello\nworld\n" });
+    expect(chunks.join("")).toBe("hello\nworld\n");
+  });
+
+  it("should return the exact exit code", async () => {
+    const result = await runOneShot({
+      cli: "claude",
+      cliConf: { binary: "sh" },
+      cliArgs: ["-c", "printf 'partial'; exit 3"],
+      cwd: process.cwd(),
+      env,
+    });
+    expect(result).toEqual({ exitCode: 3, output: "partial" });
+  });
+
+  it("should hand out the running agent so it can be stopped, e.g. on --max-runtime", async () => {
+    const result = await runOneShot({
+      cli: "claude",
+      cliConf: { binary: "sh" },
+      cliArgs: ["-c", "printf 'started'; sleep 10"],
+      cwd: process.cwd(),
+      env,
+      onSpawn: (child) => void setTimeout(() => child.kill("SIGTERM"), 50),
+    });
+    expect(result).toEqual({ exitCode: 1, output: "started" });
+  });
+});
+
+// talks to the real claude, run with AGENT_YES_CLAUDE_TESTS=1
+describe.skipIf(!process.env.AGENT_YES_CLAUDE_TESTS)("runOneShot with claude", () => {
+  it("should round-trip a print mode answer and exit code", async () => {
+    const result = await runOneShot({
+      cli: "claude",
+      cliConf: {},
+      cliArgs: ["--print", "Reply with exactly: hi"],
+      cwd: process.cwd(),
+      env,
+    });
+    expect(result.exitCode).toBe(0);
+    expect(result.output.toLowerCase()).toContain("hi");
+  }, 120e3);
+});
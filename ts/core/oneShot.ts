@@ -0,0 +1,70 @@
+import { execa, parseCommandString } from "execa";
+import { logger } from "../logger.ts";
+import type { AgentCliConfig } from "../index.ts";
+import type { KillableProcess } from "./shutdown.ts";
+
+/**
+ * One-shot print mode, e.g. `claude-yes -- --print "say hi"`
+ *
+ * In print mode the agent answers once and exits, there are no prompts to
+ * answer and the printed answer plus the exit code are the result. Running
+ * it in a pty through the interactive pipeline only risks losing the tail of
+ * the output on exit, so it runs as a plain child process instead: stdout is
+ * passed through and captured in full, and the agent's exit code is
+ * returned as is.
+ */
+
+/**
+ * Whether the cli args ask for a one-shot print mode run
+ *
+ * @example
+ * ```typescript
+ * isPrintMode("claude", ["--print", "say hi"]); // true
+ * isPrintMode("codex", ["--print"]); // false, only claude has a print mode
+ * ```
+ */
+export function isPrintMode(cli: string, cliArgs: string[]): boolean {
+  return cli === "claude" && cliArgs.some((arg) => arg === "--print" || arg === "-p");
+}
+
+/**
+ * Run the agent once and capture its whole output
+ *
+ * @param write - Receives stdout chunks as they arrive, e.g. to pass them through
+ * @param onSpawn - Receives the running agent, e.g. to stop it on --max-runtime
+ * @returns The agent's exit code (1 when killed by a signal) and its full stdout
+ */
+export async function runOneShot({
+  cli,
+  cliConf,
+  cliArgs,
+  cwd,
+  env,
+  write,
+  onSpawn,
+}: {
+  cli: string;
+  cliConf: AgentCliConfig;
+  cliArgs: string[];
+  cwd: string;
+  env: Record<string, string>;
+  write?: (chunk: string) => void;
+  onSpawn?: (child: KillableProcess) => void;
+}): Promise<{ exitCode: number; output: string }> {
+  const [bin, ...args] = [...parseCommandString(cliConf.binary || cli), ...cliArgs];
+  logger.info(`[${cli}-yes] Running ${bin} once in print mode with args: ${JSON.stringify(args)}`);
+  const child = execa(bin!, args, {
+    cwd,
+    env,
+    extendEnv: false, // env is the full environment already
+    stdin: "inherit", // print mode reads piped input, e.g. `cat log | claude -p "explain"`
+    stderr: "inherit",
+    reject: false,
+    stripFinalNewline: false, // keep the output byte for byte
+  });
+  if (child.pid) onSpawn?.({ pid: child.pid, kill: (signal) => void child.kill(signal as NodeJS.Signals) });
+  child.stdout?.on("data", (chunk: Buffer) => write?.(chunk.toString()));
+  const result = await child; // resolves after exit and once stdout is fully read
+  if (result.failed && result.exitCode === undefined) logger.error(`[${cli}-yes] ${result.shortMessage}`);
+  return { exitCode: result.exitCode ?? 1, output: String(result.stdout ?? "") };
+}
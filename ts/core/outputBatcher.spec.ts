@@ -0,0 +1,64 @@
+import { Writable } from "stream";
+import { describe, expect, it } from "vitest";
+import { OutputBatcher } from "./outputBatcher";
+
+const sink = () => {
+  const writes: string[] = [];
+  const stream = new Writable({
+    write(chunk, _encoding, callback) {
+      writes.push(chunk.toString());
+      callback();
+    },
+  });
+  return { stream, writes };
+};
+const tick = (ms = 0) => new Promise((resolve) => setTimeout(resolve, ms));
+
+describe("OutputBatcher", () => {
+  it("should write each chunk right away with a 0 interval", async () => {
+    const { stream, writes } = sink();
+    const batcher = new OutputBatcher(stream, 0);
+    await batcher.write("a");
+    await batcher.write("b");
+    expect(writes).toEqual(["a", "b"]);
+  });
+
+  it("should batch chunks until the interval passed", async () => {
+    const { stream, writes } = sink();
+    const batcher = new OutputBatcher(stream, 20);
+    await batcher.write("Do you want ");
+    await batcher.write("to proceed?\n");
+    expect(writes).toEqual([]);
+    await tick(40);
+    expect(writes).toEqual(["Do you want to proceed?\n"]);
+  });
+
+  it("should flush once the buffer is full, and when the output ends", async () => {
+    const { stream, writes } = sink();
+    const batcher = new OutputBatcher(stream, 1000, 10);
+    const writer = batcher.writable().getWriter();
+    await writer.write("12345");
+    await writer.write("67890");
+    await writer.write("tail");
+    expect(writes).toEqual(["1234567890"]);
+    await writer.close();
+    expect(writes).toEqual(["1234567890", "tail"]);
+  });
+
+  it("should cut write calls for chatty output", async () => {
+    const { stream, writes } = sink();
+    const chunks = Array.from({ length: 10000 }, (_, i) => `token ${i} `);
+
+    const unbatched = new OutputBatcher(sink().stream, 0);
+    for (const chunk of chunks) await unbatched.write(chunk);
+
+    const batched = new OutputBatcher(stream, 8);
+    const writer = batched.writable().getWriter();
+    for (const chunk of chunks) await writer.write(chunk);
+    await writer.close();
+
+    expect(unbatched.writes).toBe(10000);
+    expect(batched.writes).toBeLessThan(100); // one per 8ms of producing, or per 64KB
+    expect(writes.join("")).toBe(chunks.join(""));
+  });
+});
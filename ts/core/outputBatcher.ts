@@ -0,0 +1,62 @@
+import { once } from "events";
+
+/**
+ * Batched stdout writes, see `--stdout-flush-interval`
+ *
+ * Chatty agents print thousands of tiny chunks (spinner frames, streamed
+ * tokens), and one `write` per chunk costs a syscall each. Chunks are
+ * collected instead and written together:
+ *
+ * - after `flushIntervalMs` from the first buffered chunk, so output (and a
+ *   prompt waiting for an answer, after which output goes quiet) shows up
+ *   within that delay
+ * - once `maxBufferedChars` are buffered
+ * - when the output ends
+ *
+ * A `flushIntervalMs` of 0 writes every chunk right away, as before.
+ */
+export class OutputBatcher {
+  private buffer: string[] = [];
+  private buffered = 0;
+  private timer: ReturnType<typeof setTimeout> | undefined;
+  private writing: Promise<void> = Promise.resolve();
+  writes = 0; // number of writes to the stream, for tests and benchmarks
+
+  constructor(
+    private stream: NodeJS.WritableStream,
+    private flushIntervalMs = 8,
+    private maxBufferedChars = 64 * 1024,
+  ) {}
+
+  /** Buffer a chunk, flushing per the policy above */
+  async write(chunk: string) {
+    this.buffer.push(chunk);
+    this.buffered += chunk.length;
+    if (this.flushIntervalMs <= 0 || this.buffered >= this.maxBufferedChars) return await this.flush();
+    this.timer ??= setTimeout(() => void this.flush(), this.flushIntervalMs);
+  }
+
+  /** Write out everything buffered, resolves once the stream took it (after a drain if needed) */
+  flush(): Promise<void> {
+    clearTimeout(this.timer);
+    this.timer = undefined;
+    if (!this.buffer.length) return this.writing;
+    const text = this.buffer.join("");
+    this.buffer = [];
+    this.buffered = 0;
+    this.writes++;
+    this.writing = this.writing.then(async () => {
+      if (!this.stream.write(text)) await once(this.stream, "drain");
+    });
+    return this.writing;
+  }
+
+  /** Sink for a stream pipeline, flushes when the output ends */
+  writable(): WritableStream<string> {
+    return new WritableStream<string>({
+      write: (chunk) => this.write(chunk),
+      close: () => this.flush(),
+      abort: () => this.flush(),
+    });
+  }
+}
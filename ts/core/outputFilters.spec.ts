@@ -0,0 +1,116 @@
+import { describe, expect, it } from "vitest";
+import {
+  composeOutputFilters,
+  createLinePrefixer,
+  createLeadingStripper,
+  stripColorsFilter,
+  stripLeading,
+  stripControlCharactersFilter,
+} from "./outputFilters";
+
+describe("composeOutputFilters", () => {
+  it("should pass output through unchanged when no filters are given", () => {
+    expect(composeOutputFilters()("hello")).toBe("hello");
+  });
+
+  it("should skip disabled filters", () => {
+    const filter = composeOutputFilters(undefined, false, (s) => s.toUpperCase());
+    expect(filter("hello")).toBe("HELLO");
+  });
+
+  it("should apply filters left to right", () => {
+    const redact = (s: string) => s.replace(/secret/g, "***");
+    const filter = composeOutputFilters(redact, stripControlCharactersFilter);
+    expect(filter("\u001b[31msecret\u001b[0m")).toBe("***");
+  });
+});
+
+describe("stripColorsFilter", () => {
+  it("should drop colors but keep cursor movement", () => {
+    expect(stripColorsFilter("\x1b[1;31merror\x1b[0m\x1b[2K\x1b[1A done")).toBe("error\x1b[2K\x1b[1A done");
+    expect(stripColorsFilter("\x1b[38;2;255;0;0mred\x1b[m")).toBe("red");
+  });
+});
+
+describe("banner stripping", () => {
+  const banner = "╭──────────╮\n│ ✻ Welcome to Claude Code! │\n╰──────────╯\n";
+  const bannerPattern = /╭[\s\S]*?Welcome to Claude[\s\S]*?╯[^\n]*\r?\n?/;
+
+  it("should strip the banner from the start of the logs only", () => {
+    expect(stripLeading(`${banner}> hi\n`, [bannerPattern])).toBe("> hi\n");
+    expect(stripLeading(`${"x".repeat(100)}${banner}`, [bannerPattern], 50)).toBe(`${"x".repeat(100)}${banner}`);
+  });
+
+  it("should strip the banner from the first chunk and leave later output alone", () => {
+    const strip = createLeadingStripper([bannerPattern]);
+    expect(strip(`\x1b[2J${banner}> `)).toBe("\x1b[2J> ");
+    expect(strip(banner)).toBe(banner); // only once
+  });
+
+  it("should match a banner with real ANSI escapes and keep the escapes", () => {
+    const colored =
+      "\x1b[?25l\x1b[38;2;215;119;87m╭──────────╮\x1b[39m\r\n" +
+      "\x1b[38;2;215;119;87m│\x1b[39m \x1b[38;2;215;119;87m✻\x1b[39m Welcome to \x1b[1mClaude\x1b[22m Code! \x1b[38;2;215;119;87m│\x1b[39m\r\n" +
+      "\x1b[38;2;215;119;87m╰──────────╯\x1b[39m\r\n";
+    const strip = createLeadingStripper([bannerPattern]);
+    const out = strip(`${colored}> `);
+    expect(out).not.toMatch(/Welcome|╭|╰/);
+    expect(out).toBe(
+      "\x1b[?25l\x1b[38;2;215;119;87m\x1b[39m\x1b[38;2;215;119;87m\x1b[39m\x1b[38;2;215;119;87m\x1b[39m\x1b[1m\x1b[22m" +
+        "\x1b[38;2;215;119;87m\x1b[39m\x1b[38;2;215;119;87m\x1b[39m> ",
+    );
+    expect(stripLeading(`${colored}> hi`, [bannerPattern])).toMatch(/^(\x1b\[[\d;?]*[a-z])*> hi$/i);
+  });
+
+  it("should strip a banner split across chunks", () => {
+    const strip = createLeadingStripper([bannerPattern]);
+    const [head, tail] = [banner.slice(0, 20), banner.slice(20)];
+    expect(strip(`\x1b[2J${head}`)).toBe("\x1b[2J"); // held back from the "╭"
+    expect(strip(`${tail}> `)).toBe("> ");
+    expect(strip.flush()).toBe("");
+  });
+
+  it("should release a box that never becomes the banner", () => {
+    const strip = createLeadingStripper([bannerPattern], undefined, 3);
+    expect(strip("╭───╮\n")).toBe("");
+    expect(strip("│ > │\n")).toBe("");
+    expect(strip("╰───╯\n")).toBe("╭───╮\n│ > │\n╰───╯\n");
+    expect(strip("╭")).toBe("");
+    expect(strip.flush()).toBe("╭");
+  });
+
+  it("should stop looking after the window", () => {
+    const strip = createLeadingStripper([/BANNER/], 10);
+    expect(strip("0123456789")).toBe("0123456789");
+    expect(strip("BANNER")).toBe("BANNER");
+  });
+});
+
+describe("createLinePrefixer", () => {
+  it("should prefix every line of a chunk", () => {
+    const prefixer = createLinePrefixer("[a] ");
+    expect(prefixer("one\ntwo\n")).toBe("[a] one\n[a] two\n");
+  });
+
+  it("should only prefix real line starts across chunks", () => {
+    const prefixer = createLinePrefixer("[a] ");
+    expect(prefixer("hel")).toBe("[a] hel");
+    expect(prefixer("lo\nwor")).toBe("lo\n[a] wor");
+    expect(prefixer("ld\n")).toBe("ld\n");
+    expect(prefixer("next")).toBe("[a] next");
+  });
+
+  it("should leave empty chunks alone", () => {
+    const prefixer = createLinePrefixer("[a] ");
+    expect(prefixer("")).toBe("");
+    expect(prefixer("x")).toBe("[a] x");
+  });
+
+  it("should call a function prefix for each line", () => {
+    let n = 0;
+    const prefixer = createLinePrefixer(() => `${++n} `);
+    expect(prefixer("a\nb")).toBe("1 a\n2 b");
+    expect(prefixer("c\n")).toBe("c\n");
+    expect(prefixer("d\n")).toBe("3 d\n");
+  });
+});
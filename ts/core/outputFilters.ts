@@ -0,0 +1,206 @@
+import { removeControlCharacters } from "../removeControlCharacters.ts";
+import { applyControlCharPolicy, type ControlCharPolicy, type ControlCharState } from "./controlChars.ts";
+
+/**
+ * Output post-processing for agent sessions
+ *
+ * An output filter receives each chunk of agent output and returns the text
+ * that should be written instead. Library consumers can pass their own filter
+ * (highlighting, translation, redaction, ...) via the `outputFilter` option.
+ *
+ * Ordering:
+ * 1. Prompt detection and the terminal render always see the original output
+ * 2. The user `outputFilter` runs first, on the raw chunk (control chars included)
+ * 3. Built-in filters (control char policy, `removeControlCharactersFromStdout`, then `prefix`) run afterwards
+ *
+ * The raw log receives the chunk after step 2, stdout after step 3.
+ */
+
+export type OutputFilter = (chunk: string) => string;
+
+/**
+ * Chain several output filters into one, applied left to right
+ *
+ * @example
+ * ```typescript
+ * const filter = composeOutputFilters(redactSecrets, removeControlCharacters);
+ * filter("\u001b[31mtoken=abc\u001b[0m"); // "token=***"
+ * ```
+ */
+export function composeOutputFilters(...filters: (OutputFilter | false | undefined)[]): OutputFilter {
+  const active = filters.filter((f): f is OutputFilter => typeof f === "function");
+  return (chunk) => active.reduce((text, filter) => filter(text), chunk);
+}
+
+/**
+ * Built-in filter behind `--remove-control-characters-from-stdout`
+ */
+export const stripControlCharactersFilter: OutputFilter = (chunk) => removeControlCharacters(chunk);
+
+/**
+ * Built-in filter behind `--color never` / NO_COLOR, drops SGR color and
+ * style sequences but keeps cursor movement so the screen still renders
+ */
+export const stripColorsFilter: OutputFilter = (chunk) => chunk.replace(/\x1b\[[\d;:]*m/g, "");
+
+/**
+ * Create a filter forwarding or dropping benign control chars, see controlChars.ts
+ *
+ * Stateful: an OSC sequence split across chunks keeps its terminating BEL.
+ */
+export function createControlCharFilter(policy: ControlCharPolicy): OutputFilter {
+  const state: ControlCharState = { osc: false, last: "" };
+  return (chunk) => applyControlCharPolicy(chunk, policy, state);
+}
+
+/**
+ * Create a filter that labels every output line, behind `--prefix`
+ *
+ * Stateful: a line split across chunks is only prefixed once, at its real
+ * start, so merged output of many agents stays readable. A function prefix
+ * is called per line, e.g. for `--log-timestamps`.
+ *
+ * @example
+ * ```typescript
+ * const prefixer = createLinePrefixer("[api] ");
+ * prefixer("hello\nwor"); // "[api] hello\n[api] wor"
+ * prefixer("ld\n");       // "ld\n"
+ * ```
+ */
+export function createLinePrefixer(prefix: string | (() => string)): OutputFilter {
+  const prefixFor = typeof prefix === "function" ? prefix : () => prefix; // e.g. a timestamp per line
+  let atLineStart = true;
+  return (chunk) => {
+    if (!chunk) return chunk;
+    const lines = chunk.split("\n");
+    const prefixed = lines.map((line, i) => {
+      const isLineStart = i === 0 ? atLineStart : true;
+      const isTrailingEmpty = i === lines.length - 1 && line === "";
+      return isLineStart && !isTrailingEmpty ? prefixFor() + line : line;
+    });
+    atLineStart = chunk.endsWith("\n");
+    return prefixed.join("\n");
+  };
+}
+
+/** How far into the output `--strip-prefix` / `--strip-banner` look, later output is never touched */
+export const STRIP_WINDOW_CHARS = 16384;
+
+/** How many lines a banner that started but isn't complete yet is held back, waiting for the next chunk */
+export const STRIP_HOLD_LINES = 24;
+
+// CSI (colors, cursor moves), OSC (titles, links) up to BEL or ST, and two-char escapes
+const ESCAPE_SEQUENCE = /\x1b(?:\[[0-?]*[ -/]*[@-~]|\][^\x07\x1b]*(?:\x07|\x1b\\)?|[()#][0-9A-Za-z]|[@-Z\\-_=>])/g;
+
+/**
+ * Remove the first match of a pattern from the printable text, keeping the escape sequences
+ *
+ * The pattern is matched against the text without escapes, so color codes
+ * inside a banner don't break the match, and the escapes around and inside
+ * the match stay, so a color opened before it is still closed after it.
+ *
+ * @returns The text without the match, or null when the pattern doesn't match
+ *
+ * @example
+ * ```typescript
+ * removePrintableMatch("\x1b[2m╭─╮ Welcome\x1b[0m\nhi", /╭[\s\S]*?Welcome\n?/); // "\x1b[2m\x1b[0m\nhi"
+ * ```
+ */
+export function removePrintableMatch(text: string, pattern: RegExp): string | null {
+  let plain = "";
+  const rawIndex: number[] = []; // position in text of each char of plain
+  let last = 0;
+  const keepPrintable = (end: number) => {
+    for (let i = last; i < end; i++) {
+      plain += text[i];
+      rawIndex.push(i);
+    }
+  };
+  for (const escape of text.matchAll(ESCAPE_SEQUENCE)) {
+    keepPrintable(escape.index!);
+    last = escape.index! + escape[0].length;
+  }
+  keepPrintable(text.length);
+
+  const match = new RegExp(pattern.source, pattern.flags.replace(/[gy]/g, "")).exec(plain);
+  if (!match) return null;
+  const removed = new Set(rawIndex.slice(match.index, match.index + match[0].length));
+  let result = "";
+  for (let i = 0; i < text.length; i++) if (!removed.has(i)) result += text[i];
+  return result;
+}
+
+/**
+ * Remove the first match of each pattern from the start of a text, for saved logs
+ *
+ * @example
+ * ```typescript
+ * stripLeading("BANNER\nhello\nBANNER", [/BANNER\n/]); // "hello\nBANNER"
+ * ```
+ */
+export function stripLeading(text: string, patterns: RegExp[], windowChars = STRIP_WINDOW_CHARS): string {
+  return patterns.reduce((rest, pattern) => {
+    const head = rest.slice(0, windowChars);
+    return (removePrintableMatch(head, pattern) ?? head) + rest.slice(windowChars);
+  }, text);
+}
+
+export type LeadingStripper = OutputFilter & {
+  flush: () => string; // the held back text, at the end of the output
+};
+
+/**
+ * Create a filter removing a known banner from the start of the output,
+ * behind `--strip-prefix` and `--strip-banner`
+ *
+ * Stateful: each pattern is removed at most once, and only while the output
+ * is within its first `windowChars`, so later content is never stripped.
+ * Patterns are matched without escape sequences, see removePrintableMatch.
+ * A banner split across chunks is held back from its first char (e.g. the
+ * "╭" of a box) until it's complete, for at most `holdLines` lines, then
+ * released as is.
+ */
+export function createLeadingStripper(
+  patterns: RegExp[],
+  windowChars = STRIP_WINDOW_CHARS,
+  holdLines = STRIP_HOLD_LINES,
+): LeadingStripper {
+  let pending = [...patterns];
+  let seen = 0;
+  let held = "";
+  const strip = (chunk: string) => {
+    let text = held + chunk;
+    held = "";
+    if (!pending.length || seen >= windowChars) return text;
+    seen += chunk.length;
+    for (const pattern of [...pending]) {
+      const stripped = removePrintableMatch(text, pattern);
+      if (stripped === null) continue;
+      text = stripped;
+      pending = pending.filter((p) => p !== pattern);
+    }
+    if (!pending.length || seen >= windowChars) return text;
+    const holdFrom = Math.min(
+      ...pending.map((pattern) => {
+        const start = leadingLiteral(pattern);
+        const at = start ? text.indexOf(start) : -1;
+        const lines = at >= 0 ? text.slice(at).split("\n").length - 1 : Infinity;
+        return lines < holdLines ? at : text.length;
+      }),
+    );
+    held = text.slice(holdFrom);
+    return text.slice(0, holdFrom);
+  };
+  return Object.assign(strip, {
+    flush: () => {
+      const rest = held;
+      held = "";
+      return rest;
+    },
+  });
+}
+
+// the char a match must start with, e.g. "╭" for /╭[\s\S]*?Welcome/, or undefined when it isn't a plain char
+function leadingLiteral(pattern: RegExp): string | undefined {
+  return /^\^?([^\\[\](){}.*+?|^$])/u.exec(pattern.source)?.[1];
+}
@@ -0,0 +1,32 @@
+import { describe, expect, it, vi } from "vitest";
+import { OutputLimit, parseByteSize } from "./outputLimit";
+
+describe("parseByteSize", () => {
+  it("should parse sizes with binary units", () => {
+    expect(parseByteSize("1048576")).toBe(1048576);
+    expect(parseByteSize("64k")).toBe(65536);
+    expect(parseByteSize("10MB")).toBe(10 * 1024 ** 2);
+    expect(parseByteSize("1.5 GiB")).toBe(1.5 * 1024 ** 3);
+  });
+
+  it("should reject invalid sizes", () => {
+    expect(() => parseByteSize("lots")).toThrow(/Invalid size "lots"/);
+    expect(() => parseByteSize("10TB")).toThrow(/Invalid size/);
+  });
+});
+
+describe("OutputLimit", () => {
+  it("should count raw bytes and fire once past the cap", () => {
+    const onExceeded = vi.fn();
+    const limit = new OutputLimit(10, onExceeded);
+
+    limit.add("\u001b[1m1234"); // 8 bytes, escapes count too
+    expect(onExceeded).not.toHaveBeenCalled();
+    limit.add("é€"); // 5 bytes in utf-8
+    limit.add("more");
+
+    expect(limit.bytes).toBe(17);
+    expect(onExceeded).toHaveBeenCalledTimes(1);
+    expect(onExceeded).toHaveBeenCalledWith(13);
+  });
+});
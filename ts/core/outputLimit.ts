@@ -0,0 +1,50 @@
+/**
+ * Output size cap, behind `--max-session-bytes <size>`
+ *
+ * Safety limit for runaway agents: counts the raw bytes the agent writes
+ * (before rendering or filtering) and ends the session once the cap is
+ * exceeded, so output and log files can't grow without bound.
+ */
+
+/** Exit code when the session was ended by `--max-session-bytes` */
+export const EXIT_CODE_OUTPUT_LIMIT = 3;
+
+const UNITS: Record<string, number> = { "": 1, b: 1, k: 1024, m: 1024 ** 2, g: 1024 ** 3 };
+
+/**
+ * Parse a size like "500MB", "1.5g", "64k" or "1048576" into bytes (binary units)
+ * @throws Error on anything else
+ *
+ * @example
+ * ```typescript
+ * parseByteSize("10MB"); // 10485760
+ * ```
+ */
+export function parseByteSize(size: string): number {
+  const match = size.trim().match(/^(\d+(?:\.\d+)?)\s*([kmg]?)(?:i?b)?$/i);
+  if (!match) throw new Error(`Invalid size "${size}", expected e.g. 500MB, 1G or 1048576`);
+  return Math.floor(Number(match[1]) * UNITS[match[2]!.toLowerCase()]!);
+}
+
+export class OutputLimit {
+  /** Raw bytes seen so far */
+  bytes = 0;
+  private exceeded = false;
+
+  /**
+   * @param maxBytes - Cap on the total output size
+   * @param onExceeded - Called once, when the cap is first exceeded
+   */
+  constructor(
+    private maxBytes: number,
+    private onExceeded: (bytes: number) => void,
+  ) {}
+
+  /** Count an output chunk */
+  add(chunk: string) {
+    this.bytes += Buffer.byteLength(chunk, "utf8");
+    if (this.exceeded || this.bytes <= this.maxBytes) return;
+    this.exceeded = true;
+    this.onExceeded(this.bytes);
+  }
+}
@@ -0,0 +1,45 @@
+import { describe, expect, it } from "vitest";
+import { OutputStream } from "./outputStream";
+
+const collect = async (stream: OutputStream) => {
+  const chunks: string[] = [];
+  for await (const chunk of stream) chunks.push(chunk);
+  return chunks;
+};
+
+describe("OutputStream", () => {
+  it("should yield buffered chunks and finish on end", async () => {
+    const stream = new OutputStream();
+    stream.push("a");
+    stream.push("b");
+    stream.end();
+    expect(await collect(stream)).toEqual(["a", "b"]);
+  });
+
+  it("should hand chunks to a waiting consumer", async () => {
+    const stream = new OutputStream();
+    const chunks = collect(stream);
+    await Promise.resolve();
+    stream.push("hello");
+    setTimeout(() => {
+      stream.push("world");
+      stream.end();
+    }, 5);
+    expect(await chunks).toEqual(["hello", "world"]);
+  });
+
+  it("should drop the oldest chunks when the consumer falls behind", async () => {
+    const stream = new OutputStream(2);
+    ["1", "2", "3", "4"].forEach((chunk) => stream.push(chunk));
+    stream.end();
+    expect(await collect(stream)).toEqual(["3", "4"]);
+    expect(stream.dropped).toBe(2);
+  });
+
+  it("should ignore chunks after the end", async () => {
+    const stream = new OutputStream();
+    stream.end();
+    stream.push("late");
+    expect(await collect(stream)).toEqual([]);
+  });
+});
@@ -0,0 +1,63 @@
+/**
+ * Pull-based access to the session output, for embedding agent-yes as a library
+ *
+ * Pass an `OutputStream` as the `outputStream` option and consume it with
+ * `for await`, it gets the same chunks as stdout (after output filters and
+ * prefixes) and ends when the session ends.
+ *
+ * Backpressure: the session never waits for the consumer. Chunks are
+ * buffered up to `maxBuffered`; beyond that the oldest buffered chunks are
+ * dropped and counted in `dropped`, like a lagging broadcast receiver.
+ *
+ * @example
+ * ```typescript
+ * const output = new OutputStream();
+ * const done = agentYes({ cli: "claude", prompt: "fix the tests", outputStream: output, passthrough: false });
+ * for await (const chunk of output) process(chunk);
+ * const { exitCode } = await done;
+ * ```
+ */
+export class OutputStream implements AsyncIterable<string> {
+  private buffer: string[] = [];
+  private waiting: ((result: IteratorResult<string>) => void) | null = null;
+  private ended = false;
+  dropped = 0; // chunks dropped because the consumer fell behind
+
+  constructor(private maxBuffered = 1000) {}
+
+  /** Add a chunk, handing it straight to a waiting consumer if any */
+  push(chunk: string) {
+    if (this.ended) return;
+    if (this.waiting) {
+      const resolve = this.waiting;
+      this.waiting = null;
+      return resolve({ value: chunk, done: false });
+    }
+    this.buffer.push(chunk);
+    if (this.buffer.length > this.maxBuffered) {
+      this.buffer.shift();
+      this.dropped++;
+    }
+  }
+
+  /** End the stream, consumers get the buffered chunks and then finish */
+  end() {
+    this.ended = true;
+    this.waiting?.({ value: undefined, done: true });
+    this.waiting = null;
+  }
+
+  async *[Symbol.asyncIterator](): AsyncIterator<string> {
+    while (true) {
+      const chunk = this.buffer.shift();
+      if (chunk !== undefined) {
+        yield chunk;
+        continue;
+      }
+      if (this.ended) return;
+      const next = await new Promise<IteratorResult<string>>((resolve) => (this.waiting = resolve));
+      if (next.done) return;
+      yield next.value;
+    }
+  }
+}
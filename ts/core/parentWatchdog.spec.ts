@@ -0,0 +1,40 @@
+import { describe, expect, it, vi } from "vitest";
+import { isProcessAlive, watchParent } from "./parentWatchdog";
+
+const tick = (ms = 0) => new Promise((resolve) => setTimeout(resolve, ms));
+
+describe("isProcessAlive", () => {
+  it("should tell running processes from gone ones", () => {
+    expect(isProcessAlive(process.pid)).toBe(true);
+    expect(isProcessAlive(2 ** 22 + 12345)).toBe(false); // above pid_max
+  });
+});
+
+describe("watchParent", () => {
+  it("should not fire while the parent is alive", async () => {
+    const onExit = vi.fn();
+    const stop = watchParent(onExit, { intervalMs: 5, getPpid: () => 42, isAlive: () => true });
+    await tick(30);
+    stop();
+    expect(onExit).not.toHaveBeenCalled();
+  });
+
+  it("should fire once when reparented", async () => {
+    let ppid = 42;
+    const onExit = vi.fn();
+    watchParent(onExit, { intervalMs: 5, getPpid: () => ppid, isAlive: () => true });
+    ppid = 1; // orphaned, adopted by init
+    await tick(30);
+    expect(onExit).toHaveBeenCalledTimes(1);
+    expect(onExit).toHaveBeenCalledWith(42);
+  });
+
+  it("should fire when the parent pid is gone but kept as ppid", async () => {
+    const onExit = vi.fn();
+    let alive = true;
+    watchParent(onExit, { intervalMs: 5, getPpid: () => 42, isAlive: () => alive });
+    alive = false;
+    await tick(30);
+    expect(onExit).toHaveBeenCalledWith(42);
+  });
+});
@@ -0,0 +1,48 @@
+/**
+ * `--kill-on-parent-exit`: stop the session when whoever started it is gone
+ *
+ * A CI job that is cancelled or an SSH session that drops can kill our
+ * parent without signalling us, leaving agent-yes and the agent running as
+ * orphans. Node has no `prctl(PR_SET_PDEATHSIG)`, so the parent is polled on
+ * every platform: it is gone when our parent pid changed (on Unix orphans
+ * are reparented, to init or a subreaper), or when the original parent pid
+ * no longer exists (Windows keeps the stale ppid). On Windows a reused pid
+ * can hide the parent's exit, so there it's best effort.
+ */
+
+/** Exit code when the session was ended by `--kill-on-parent-exit`, 128 + SIGHUP like a hung-up terminal */
+export const EXIT_CODE_PARENT_EXIT = 129;
+
+export interface ParentWatchdogOptions {
+  intervalMs?: number; // how often to check, 1s by default
+  getPpid?: () => number; // for tests
+  isAlive?: (pid: number) => boolean; // for tests
+}
+
+/** Whether a process exists, without signalling it */
+export function isProcessAlive(pid: number): boolean {
+  try {
+    process.kill(pid, 0);
+    return true;
+  } catch (error) {
+    return (error as NodeJS.ErrnoException).code === "EPERM"; // exists, owned by someone else
+  }
+}
+
+/**
+ * Call `onParentExit` once, when the parent process is gone
+ * @returns A function stopping the watchdog
+ */
+export function watchParent(
+  onParentExit: (parentPid: number) => void,
+  { intervalMs = 1000, getPpid = () => process.ppid, isAlive = isProcessAlive }: ParentWatchdogOptions = {},
+): () => void {
+  const parentPid = getPpid();
+  const timer = setInterval(() => {
+    if (getPpid() === parentPid && isAlive(parentPid)) return;
+    clearInterval(timer);
+    onParentExit(parentPid);
+  }, intervalMs);
+  timer.unref?.(); // never keeps agent-yes alive on its own
+  return () => clearInterval(timer);
+}
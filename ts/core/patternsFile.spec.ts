@@ -0,0 +1,112 @@
+import { mkdtempSync, writeFileSync } from "fs";
+import { tmpdir } from "os";
+import path from "path";
+import { describe, expect, it, vi } from "vitest";
+import type { AgentCliConfig } from "../index";
+import { loadPatternsFile, parsePatternsFile, withUserPatterns } from "./patternsFile";
+import { detectPrompt } from "./responders";
+import { SESSION_DIR_ENV } from "./stateDir";
+
+const conf: AgentCliConfig = { enter: [/❯ 1\. Yes/], typingRespond: { "y\n": [/\(y\/n\)/] } };
+
+describe("parsePatternsFile", () => {
+  it("should compile substrings literally and regexes as given", () => {
+    const { patterns, builtins } = parsePatternsFile(
+      JSON.stringify({
+        patterns: [
+          { match: "Proceed? (yes)", response: "\r" },
+          { match: "^Deploy to (staging|prod)\\?", is_regex: true, response: "y" },
+        ],
+      }),
+    );
+    expect(builtins).toBe(true);
+    expect(patterns[0]!.match.test("Proceed? (yes)")).toBe(true);
+    expect(patterns[0]!.match.test("Proceed! yes")).toBe(false);
+    expect(patterns[1]!.match.test("Deploy to prod?")).toBe(true);
+  });
+
+  it("should read TOML", () => {
+    const { patterns, builtins } = parsePatternsFile(
+      [
+        "# only answer what's listed here",
+        "builtins = false",
+        "[[patterns]]",
+        "match = '^\\s*Continue\\? \\(y/n\\)'",
+        "is_regex = true",
+        'response = "y"',
+        "[[patterns]]",
+        'match = "Allow network access?" # the sandbox prompt',
+        'response = "\\r"',
+      ].join("\n"),
+      "toml",
+    );
+    expect(builtins).toBe(false);
+    expect(patterns).toEqual([
+      { match: /^\s*Continue\? \(y\/n\)/, response: "y" },
+      { match: /Allow network access\?/, response: "\r" },
+    ]);
+  });
+
+  it("should accept a bare list and reject incomplete entries", () => {
+    expect(parsePatternsFile('[{ "match": "a", "response": "b" }]').patterns).toHaveLength(1);
+    expect(() => parsePatternsFile('[{ "match": "a" }]')).toThrow(/Pattern 1 needs/);
+    expect(() => parsePatternsFile('[{ "match": "(", "is_regex": true, "response": "b" }]')).toThrow();
+  });
+});
+
+describe("loadPatternsFile", () => {
+  it("should pick the format by extension and name the file in errors", async () => {
+    const dir = mkdtempSync(path.join(tmpdir(), "agent-yes-patterns-"));
+    writeFileSync(path.join(dir, "p.toml"), '[[patterns]]\nmatch = "Go?"\nresponse = "y"\n');
+    writeFileSync(path.join(dir, "bad.json"), "{");
+    expect((await loadPatternsFile(path.join(dir, "p.toml")))?.patterns).toEqual([{ match: /Go\?/, response: "y" }]);
+    await expect(loadPatternsFile(path.join(dir, "bad.json"))).rejects.toThrow(/Invalid patterns file .*bad\.json/);
+  });
+
+  it("should refetch a URL once its cache is older than ttlMs", async () => {
+    process.env[SESSION_DIR_ENV] = mkdtempSync(path.join(tmpdir(), "agent-yes-patterns-cache-"));
+    const fetch = vi.fn(async () => new Response('[{ "match": "Go?", "response": "y" }]'));
+    const url = "https://example.com/patterns.json";
+    try {
+      await loadPatternsFile(url, { fetch });
+      await loadPatternsFile(url, { fetch }); // cached for an hour by default
+      expect(fetch).toHaveBeenCalledTimes(1);
+      await loadPatternsFile(url, { ttlMs: 0, fetch });
+      expect(fetch).toHaveBeenCalledTimes(2);
+    } finally {
+      delete process.env[SESSION_DIR_ENV];
+    }
+  });
+
+  it("should fall back to the built-ins when a URL is down and not cached", async () => {
+    process.env[SESSION_DIR_ENV] = mkdtempSync(path.join(tmpdir(), "agent-yes-patterns-cache-"));
+    const fetch = vi.fn(async () => new Response("<html>Bad gateway</html>")); // a 200 error page, not valid JSON
+    try {
+      expect(await loadPatternsFile("https://example.com/patterns.json", { fetch })).toBeNull();
+    } finally {
+      delete process.env[SESSION_DIR_ENV];
+    }
+  });
+});
+
+describe("user patterns in detectPrompt", () => {
+  const file = parsePatternsFile('[{ "match": "Proceed?", "response": "\\r" }, { "match": "(y/n)", "response": "yes" }]');
+
+  it("should win over the built-ins, first match first", () => {
+    const withUser = withUserPatterns(conf, file);
+    expect(detectPrompt("Proceed? (y/n)", withUser)).toEqual({
+      rule: "patterns[0]",
+      pattern: "Proceed\\?",
+      action: "enter",
+      response: "\r",
+    });
+    expect(detectPrompt("Overwrite? (y/n)", withUser)).toMatchObject({ rule: "patterns[1]", action: "type", response: "yes" });
+    expect(detectPrompt("❯ 1. Yes", withUser)?.rule).toBe("enter[0]");
+  });
+
+  it("should drop the built-in answers without builtins", () => {
+    const only = withUserPatterns(conf, { ...file, builtins: false });
+    expect(detectPrompt("❯ 1. Yes", only)).toBeNull();
+    expect(detectPrompt("Proceed?", only)?.rule).toBe("patterns[0]");
+  });
+});
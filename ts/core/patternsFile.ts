@@ -0,0 +1,133 @@
+import { readFile } from "fs/promises";
+import type { AgentCliConfig } from "../index.ts";
+import { logger } from "../logger.ts";
+import { fetchCached, isRemotePath } from "./remoteFile.ts";
+import { getCacheDir } from "./stateDir.ts";
+
+/**
+ * Auto-response patterns from a file, for `--patterns-file`
+ *
+ * Lets users answer prompts the built-in patterns don't know, without
+ * touching the config. JSON:
+ *
+ *     { "builtins": true, "patterns": [{ "match": "Allow network access?", "response": "\r" }] }
+ *
+ * or TOML:
+ *
+ *     builtins = true
+ *     [[patterns]]
+ *     match = '^\s*Continue\? \(y/n\)'
+ *     is_regex = true
+ *     response = "y"
+ *
+ * `match` is a plain substring unless `is_regex` is true. A response of
+ * `"\r"` presses Enter, anything else is typed followed by Enter, like the
+ * built-in `typingRespond` answers. The patterns are checked before the
+ * built-ins and the first match in file order wins; `builtins = false`
+ * drops the built-in enter/typingRespond patterns, so only the file answers
+ * permission prompts. The file may also be an https:// URL, cached like
+ * other remote files for `--patterns-cache-ttl` (an hour by default).
+ */
+
+export interface UserPattern {
+  match: RegExp; // compiled once when loading
+  response: string;
+}
+
+export interface PatternsFile {
+  patterns: UserPattern[];
+  builtins: boolean; // keep the built-in enter/typingRespond patterns
+}
+
+export const DEFAULT_PATTERNS_CACHE_TTL_MS = 60 * 60 * 1000; // refetch remote pattern sets hourly, see --patterns-cache-ttl
+
+const escapeRegExp = (text: string) => text.replace(/[.*+?^${}()|[\]\\]/g, "\\$&");
+
+/**
+ * Read the small TOML subset patterns files use: top-level keys, `[[patterns]]`
+ * tables, strings, booleans and `#` comments
+ */
+function parseToml(content: string): Record<string, unknown> {
+  const root: Record<string, unknown> = {};
+  let table = root;
+  for (const [index, raw] of content.split(/\r?\n/).entries()) {
+    const line = raw.trim();
+    if (!line || line.startsWith("#")) continue;
+    const arrayTable = line.match(/^\[\[\s*([\w-]+)\s*\]\]$/)?.[1];
+    if (arrayTable) {
+      const list = (root[arrayTable] ??= []) as Record<string, unknown>[];
+      list.push((table = {}));
+      continue;
+    }
+    const [, key, value] = line.match(/^([\w-]+)\s*=\s*(.+?)\s*(?:#[^"']*)?$/) ?? [];
+    if (!key || value === undefined) throw new Error(`Invalid TOML on line ${index + 1}: ${raw}`);
+    if (value === "true" || value === "false") table[key] = value === "true";
+    else if (/^'[^']*'$/.test(value)) table[key] = value.slice(1, -1); // literal string, no escapes
+    else if (/^".*"$/.test(value)) table[key] = JSON.parse(value);
+    else throw new Error(`Unsupported TOML value on line ${index + 1}: ${value}`);
+  }
+  return root;
+}
+
+/**
+ * Parse and compile a patterns file
+ *
+ * @param content - File content
+ * @param format - "toml", or "json" for everything else
+ * @throws Error on an invalid file, an entry without `match` or `response`, or an invalid regex
+ *
+ * @example
+ * ```typescript
+ * parsePatternsFile('{ "patterns": [{ "match": "Proceed?", "response": "\\r" }] }');
+ * // { builtins: true, patterns: [{ match: /Proceed\?/, response: "\r" }] }
+ * ```
+ */
+export function parsePatternsFile(content: string, format: "json" | "toml" = "json"): PatternsFile {
+  const data = (format === "toml" ? parseToml(content) : JSON.parse(content)) as Record<string, unknown>;
+  const entries = Array.isArray(data) ? data : (data.patterns ?? []);
+  if (!Array.isArray(entries)) throw new Error(`"patterns" must be a list`);
+  const patterns = entries.map((entry: Record<string, unknown>, i): UserPattern => {
+    if (typeof entry?.match !== "string" || typeof entry.response !== "string")
+      throw new Error(`Pattern ${i + 1} needs a "match" and a "response" string`);
+    const source = entry.is_regex ? entry.match : escapeRegExp(entry.match);
+    return { match: new RegExp(source), response: entry.response };
+  });
+  return { patterns, builtins: Array.isArray(data) || data.builtins !== false };
+}
+
+/**
+ * Load a patterns file from a path or an https:// URL
+ * @param options.ttlMs - How long a fetched URL is used from the cache, see --patterns-cache-ttl
+ * @returns The patterns, or null when a URL can't be fetched and isn't cached, to go on with the built-ins
+ * @throws Error when the file can't be read or parsed
+ */
+export async function loadPatternsFile(
+  pathOrUrl: string,
+  { ttlMs = DEFAULT_PATTERNS_CACHE_TTL_MS, fetch }: { ttlMs?: number; fetch?: typeof globalThis.fetch } = {},
+): Promise<PatternsFile | null> {
+  const format = /\.toml([?#].*)?$/i.test(pathOrUrl) ? "toml" : "json";
+  const content = isRemotePath(pathOrUrl)
+    ? await fetchCached(pathOrUrl, {
+        cacheDir: getCacheDir(),
+        ttlMs,
+        fetch,
+        validate: (text) => void parsePatternsFile(text, format), // an error page must not replace a good cache
+      })
+    : await readFile(pathOrUrl, "utf8");
+  if (content === null) {
+    logger.warn(`Patterns file ${pathOrUrl} is unavailable, using the built-in patterns only`);
+    return null;
+  }
+  try {
+    return parsePatternsFile(content, format);
+  } catch (error) {
+    throw new Error(`Invalid patterns file ${pathOrUrl}: ${error instanceof Error ? error.message : String(error)}`);
+  }
+}
+
+/**
+ * Add the file's patterns to a cli config, dropping its built-in answers when the file asks to
+ */
+export function withUserPatterns(conf: AgentCliConfig, { patterns, builtins }: PatternsFile): AgentCliConfig {
+  return { ...conf, patterns, ...(!builtins && { enter: [], typingRespond: {} }) };
+}
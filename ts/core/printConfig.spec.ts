@@ -0,0 +1,45 @@
+import { describe, expect, it } from "vitest";
+import { formatResolvedConfig, toPrintableConfig } from "./printConfig";
+
+describe("toPrintableConfig", () => {
+  it("should print regexes as strings", () => {
+    expect(toPrintableConfig({ ready: [/\? for shortcuts/], fatal: [/error/i] })).toEqual({
+      ready: ["\\? for shortcuts"],
+      fatal: ["/error/i"],
+    });
+  });
+
+  it("should redact secret keys and secret-looking values", () => {
+    expect(
+      toPrintableConfig({
+        apiKey: "abc",
+        githubToken: "def",
+        prompt: "use sk-ant-REDACTED to call it",
+        verbose: true,
+      }),
+    ).toEqual({
+      apiKey: "<redacted>",
+      githubToken: "<redacted>",
+      prompt: "use <redacted> to call it",
+      verbose: true,
+    });
+  });
+
+  it("should drop undefined values and functions", () => {
+    expect(toPrintableConfig({ logFile: undefined, outputFilter: (s: string) => s, exitOnIdle: 0 })).toEqual({
+      exitOnIdle: 0,
+    });
+  });
+});
+
+describe("formatResolvedConfig", () => {
+  it("should print options with the cli config, without the process env", () => {
+    const printed = JSON.parse(
+      formatResolvedConfig(
+        { cli: "claude", exitOnIdle: 30000, env: { HOME: "/root" }, printConfig: true },
+        { ready: [/\? for shortcuts/] },
+      ),
+    );
+    expect(printed).toEqual({ cli: "claude", exitOnIdle: 30000, cliConfig: { ready: ["\\? for shortcuts"] } });
+  });
+});
@@ -0,0 +1,51 @@
+/**
+ * `--print-config`: show the effective configuration and exit
+ *
+ * Flags, `--profile`, `--prompt-file` directives, env vars and the config
+ * file all merge into the options a session runs with; printing the result
+ * makes it easy to see which one won. Regex patterns are printed as strings,
+ * as in config files, and secret-looking values are redacted.
+ */
+
+const REDACTED = "<redacted>";
+
+/** Keys whose values are always hidden */
+const SECRET_KEY = /token|secret|passw(or)?d|api[-_]?key|credential|auth/i;
+
+/** Secret-looking substrings of any value, e.g. an API key pasted into a prompt */
+const SECRET_VALUE = /\b(?:sk-[\w-]{16,}|gh[pousr]_\w{20,}|github_pat_\w{20,}|xox[abpr]-[\w-]{10,}|AKIA[0-9A-Z]{16})/g;
+
+/**
+ * Turn a resolved config into plain JSON-able data, redacting secrets
+ *
+ * @example
+ * ```typescript
+ * toPrintableConfig({ ready: [/\? for shortcuts/], apiKey: "abc" });
+ * // { ready: ["\\? for shortcuts"], apiKey: "<redacted>" }
+ * ```
+ */
+export function toPrintableConfig(value: unknown, key = ""): unknown {
+  if (value === undefined || typeof value === "function") return undefined;
+  if (key && SECRET_KEY.test(key) && value !== null && value !== "" && typeof value !== "boolean") return REDACTED;
+  if (value instanceof RegExp) return value.flags ? `/${value.source}/${value.flags}` : value.source;
+  if (typeof value === "string") return value.replace(SECRET_VALUE, REDACTED);
+  if (Array.isArray(value)) return value.map((item) => toPrintableConfig(item) ?? null);
+  if (value && typeof value === "object")
+    return Object.fromEntries(
+      Object.entries(value)
+        .map(([k, v]) => [k, toPrintableConfig(v, k)] as const)
+        .filter(([, v]) => v !== undefined),
+    );
+  return value;
+}
+
+/**
+ * Format the effective config for `--print-config`
+ *
+ * @param options - Parsed session options, see parseCliArgs
+ * @param cliConfig - Patterns and settings of the chosen cli, from the config file
+ */
+export function formatResolvedConfig(options: Record<string, unknown>, cliConfig: unknown): string {
+  const { env: _env, printConfig: _printConfig, ...rest } = options; // env is the whole process environment, not config
+  return JSON.stringify(toPrintableConfig({ ...rest, cliConfig }), null, 2);
+}
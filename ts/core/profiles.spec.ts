@@ -0,0 +1,40 @@
+import { describe, expect, it } from "vitest";
+import { BUILTIN_PROFILES, resolveProfile } from "./profiles";
+
+describe("BUILTIN_PROFILES", () => {
+  it("should run ci quiet and unattended, with JSON events and one restart", () => {
+    expect(BUILTIN_PROFILES.ci).toMatchObject({
+      quiet: true,
+      "json-log": ".agent-yes/events.jsonl",
+      "max-restarts": 1,
+      tty: false,
+    });
+  });
+
+  it("should log verbosely with a status endpoint in dev", () => {
+    expect(BUILTIN_PROFILES.dev).toMatchObject({ verbose: true, "status-port": 0 });
+  });
+
+  it("should only answer trust and edit prompts in safe", () => {
+    const onlyAnswer = (BUILTIN_PROFILES.safe!["only-answer"] as string[]).map((source) => new RegExp(source));
+    const answered = (screen: string) => onlyAnswer.some((rx) => rx.test(screen));
+    expect(answered("Do you trust the files in this folder?")).toBe(true);
+    expect(answered("Do you want to make this edit to README.md?")).toBe(true);
+    expect(answered("Bash command\n  rm -rf dist\nDo you want to proceed?")).toBe(false);
+  });
+});
+
+describe("resolveProfile", () => {
+  it("should return built-in profiles", () => {
+    expect(resolveProfile("ci")).toBe(BUILTIN_PROFILES.ci);
+  });
+
+  it("should prefer user profiles over built-in ones", () => {
+    expect(resolveProfile("ci", { ci: { idle: "5s" } })).toEqual({ idle: "5s" });
+    expect(resolveProfile("mine", { mine: { verbose: true } })).toEqual({ verbose: true });
+  });
+
+  it("should list available profiles for an unknown name", () => {
+    expect(() => resolveProfile("nope", { mine: {} })).toThrow(/Unknown profile "nope".*ci, dev, safe, mine/);
+  });
+});
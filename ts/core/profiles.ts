@@ -0,0 +1,59 @@
+/**
+ * Named flag presets for `--profile <name>`
+ *
+ * A profile is a set of flag defaults keyed by option name as written on the
+ * command line (e.g. "exit-on-idle"). Flags given explicitly always win over
+ * the profile. User profiles come from `profiles` in the config file and
+ * replace a built-in profile of the same name.
+ */
+
+export type Profile = Record<string, string | number | boolean | string[]>;
+
+export const BUILTIN_PROFILES: Record<string, Profile> = {
+  // unattended runs: only the agent's output, events as JSON, exit soon after it goes quiet, restart a crash once
+  ci: {
+    quiet: true,
+    "json-log": ".agent-yes/events.jsonl",
+    "exit-on-idle": "30s",
+    "max-restarts": 1,
+    tty: false,
+    "pager-action": "quit",
+    "trust-mcp": "no",
+  },
+  // watching the agent work: log everything, a status endpoint on a free port, never exit on idle
+  dev: {
+    verbose: true,
+    "status-port": 0,
+    "exit-on-idle": "0",
+  },
+  // only answer trust and edit prompts, decline anything that deletes, runs commands or hits the network
+  safe: {
+    "trust-mcp": "no",
+    "only-answer": [
+      "Do you trust the files in this folder|trust this (folder|project|workspace)",
+      "make this edit|\\bEdit file\\b|create (this|a) file",
+    ],
+    answer: ["delete=no", "shell=no", "network=no"],
+  },
+};
+
+/**
+ * Look up a profile by name, user profiles first
+ *
+ * @throws Error naming the available profiles if there is no such profile
+ *
+ * @example
+ * ```typescript
+ * resolveProfile("ci"); // { "exit-on-idle": "30s", ... }
+ * resolveProfile("mine", { mine: { idle: "10s" } }); // { idle: "10s" }
+ * ```
+ */
+export function resolveProfile(name: string, userProfiles: Record<string, Profile> = {}): Profile {
+  const profiles = { ...BUILTIN_PROFILES, ...userProfiles };
+  const profile = profiles[name];
+  if (!profile)
+    throw new Error(
+      `Unknown profile "${name}", expected one of: ${Object.keys(profiles).join(", ")}`,
+    );
+  return profile;
+}
@@ -0,0 +1,79 @@
+import { describe, expect, it } from "vitest";
+import { classifyPrompt, parseAnswerMap, resolveAnswer } from "./promptCategories";
+
+describe("classifyPrompt", () => {
+  it("should classify folder trust prompts", () => {
+    expect(classifyPrompt("Do you trust the files in this folder?\n❯ 1. Yes, proceed")).toBe("trust");
+  });
+
+  const rule = "─".repeat(40); // the edge above claude's permission box
+
+  it("should classify destructive shell commands as delete", () => {
+    const screen = `${rule}\n Bash command\n\n  rm -rf dist\n  Remove build output\n\n Do you want to proceed?\n ❯ 1. Yes`;
+    expect(classifyPrompt(screen, " Do you want to proceed?")).toBe("delete");
+  });
+
+  it("should classify other shell commands as shell", () => {
+    const screen = `${rule}\n Bash command\n\n  npm test\n  Run tests\n\n Do you want to proceed?\n ❯ 1. Yes`;
+    expect(classifyPrompt(screen, " Do you want to proceed?")).toBe("shell");
+  });
+
+  it("should classify file edits", () => {
+    expect(classifyPrompt("Edit file\n src/app.ts\nDo you want to make this edit to app.ts?")).toBe(
+      "edit",
+    );
+  });
+
+  it("should classify web fetches as network", () => {
+    const screen = "Fetch(https://example.com)\nDo you want to allow Claude to fetch this content?";
+    expect(classifyPrompt(screen)).toBe("network");
+  });
+
+  it("should classify onboarding screens as setup", () => {
+    const screen = "Choose the text style that looks best with your terminal\n❯ 1. Dark mode ✔";
+    expect(classifyPrompt(screen)).toBe("setup");
+  });
+
+  it("should return undefined for unknown prompts", () => {
+    expect(classifyPrompt("Do you want to proceed?\n❯ 1. Yes")).toBeUndefined();
+  });
+
+  it("should only look at the prompt's own box, not earlier output", () => {
+    const earlier = "⏺ I'll remove the stale cache, see https://example.com/docs\n  ⎿  Deleted 3 files";
+    const box = `${rule}\n Bash command\n\n  npm test\n\n Do you want to proceed?\n ❯ 1. Yes\n   2. No`;
+    expect(classifyPrompt(`${earlier}\n${box}`, " Do you want to proceed?")).toBe("shell");
+    expect(classifyPrompt(`${earlier}\n\nDo you want to proceed?\n❯ 1. Yes`, "Do you want to proceed?")).toBeUndefined();
+  });
+});
+
+describe("resolveAnswer", () => {
+  it("should answer yes unless the category says no", () => {
+    expect(resolveAnswer("delete", {})).toBe("yes");
+    expect(resolveAnswer("delete", { delete: "no" })).toBe("no");
+    expect(resolveAnswer(undefined, { delete: "no" })).toBe("yes");
+  });
+
+  it("should answer no by default with --default-answer no, except categories answered yes", () => {
+    expect(resolveAnswer("shell", {}, "no")).toBe("no");
+    expect(resolveAnswer("edit", { edit: "yes" }, "no")).toBe("yes");
+    expect(resolveAnswer(undefined, { edit: "yes" }, "no")).toBe("no");
+  });
+});
+
+describe("parseAnswerMap", () => {
+  it("should parse category answers", () => {
+    expect(parseAnswerMap(["trust=yes", "delete=no", "Edit=YES"])).toEqual({
+      trust: "yes",
+      delete: "no",
+      edit: "yes",
+    });
+  });
+
+  it("should reject unknown categories", () => {
+    expect(() => parseAnswerMap(["deploy=yes"])).toThrow(/Unknown prompt category/);
+  });
+
+  it("should reject invalid answers", () => {
+    expect(() => parseAnswerMap(["delete=maybe"])).toThrow(/expected yes or no/);
+  });
+});
@@ -0,0 +1,104 @@
+/**
+ * Prompt classification for per-category answers (`--answer delete=no`)
+ *
+ * Classifies a permission prompt by what it asks for, using the text of
+ * its box on screen (e.g. "Bash command ... rm -rf dist ... Do you want to
+ * proceed?" is a `delete` prompt), so users can say yes to some kinds of
+ * actions and no to others instead of a blanket yes. Only the prompt's own
+ * box counts, see promptBox: a URL or "remove" in earlier output doesn't
+ * change what the prompt asks for.
+ */
+
+import { promptBox } from "./riskyPrompts.ts";
+
+export const PROMPT_CATEGORIES = ["trust", "delete", "edit", "shell", "network", "setup"] as const;
+export type PromptCategory = (typeof PROMPT_CATEGORIES)[number];
+export type Answer = "yes" | "no";
+export type AnswerMap = Partial<Record<PromptCategory, Answer>>;
+
+/**
+ * Default answer per category, everything is answered yes, same as before categories existed
+ */
+export const DEFAULT_ANSWERS: Record<PromptCategory, Answer> = {
+  trust: "yes",
+  delete: "yes",
+  edit: "yes",
+  shell: "yes",
+  network: "yes",
+  setup: "yes",
+};
+
+// checked in order, first match wins: delete must come before shell/edit since `rm` runs through Bash
+const CATEGORY_PATTERNS: [PromptCategory, RegExp][] = [
+  ["trust", /Do you trust the files in this folder|trust this (folder|project|workspace)/i],
+  ["delete", /\brm\s+-\w*[rf]|\brm\s|\bdelete\b|\bremove\b|\bunlink\b|\brmdir\b|reset --hard/i],
+  ["network", /\bWeb(Fetch|Search)\b|\bFetch\(|\bcurl\b|\bwget\b|https?:\/\//i],
+  ["edit", /make this edit|\bEdit file\b|\bWrite\(|\bUpdate\(|create (this|a) file|overwrite/i],
+  ["shell", /Bash command|\bBash\(|run (this|the) command|Approve and run/i],
+  ["setup", /Dark mode|text style|API key|Press Enter to continue|terms of service/i],
+];
+
+/**
+ * Classify a prompt by the text of its box on screen
+ *
+ * @param screen - Rendered screen text containing the prompt
+ * @param line - The matched prompt line, the box is found around it, see promptBox
+ * @returns The prompt category, or undefined when unknown
+ *
+ * @example
+ * ```typescript
+ * classifyPrompt("───\nBash command\n  rm -rf dist\nDo you want to proceed?\n❯ 1. Yes", "Do you want to proceed?"); // "delete"
+ * classifyPrompt("curl https://example.com\n───\nBash command\n  npm test\nDo you want to proceed?"); // "shell"
+ * ```
+ */
+export function classifyPrompt(screen: string, line = ""): PromptCategory | undefined {
+  const box = promptBox(screen, line);
+  return CATEGORY_PATTERNS.find(([, rx]) => rx.test(box))?.[0];
+}
+
+/**
+ * The answer for a prompt: its category's `--answer`, else `--default-answer`
+ *
+ * @param category - The prompt's category, undefined when it couldn't be classified
+ * @param defaultAnswer - `--default-answer`, "no" declines everything not answered yes per category
+ *
+ * @example
+ * ```typescript
+ * resolveAnswer("delete", { delete: "no" }); // "no"
+ * resolveAnswer("edit", { edit: "yes" }, "no"); // "yes", an allow-list
+ * resolveAnswer(undefined, {}, "no"); // "no"
+ * ```
+ */
+export function resolveAnswer(
+  category: PromptCategory | undefined,
+  answers: AnswerMap = {},
+  defaultAnswer: Answer = "yes",
+): Answer {
+  if (!category) return defaultAnswer;
+  return answers[category] ?? (defaultAnswer === "yes" ? DEFAULT_ANSWERS[category] : "no");
+}
+
+/**
+ * Parse `--answer category=yes|no` flags into an answer map
+ *
+ * @throws Error on unknown categories or answers
+ *
+ * @example
+ * ```typescript
+ * parseAnswerMap(["trust=yes", "delete=no"]); // { trust: "yes", delete: "no" }
+ * ```
+ */
+export function parseAnswerMap(entries: string[]): AnswerMap {
+  const answers: AnswerMap = {};
+  for (const entry of entries) {
+    const [category, answer] = entry.split("=").map((e) => e.trim().toLowerCase());
+    if (!PROMPT_CATEGORIES.includes(category as PromptCategory))
+      throw new Error(
+        `Unknown prompt category in --answer ${entry}, expected one of: ${PROMPT_CATEGORIES.join(", ")}`,
+      );
+    if (answer !== "yes" && answer !== "no")
+      throw new Error(`Invalid answer in --answer ${entry}, expected yes or no`);
+    answers[category as PromptCategory] = answer;
+  }
+  return answers;
+}
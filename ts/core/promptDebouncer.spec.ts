@@ -0,0 +1,72 @@
+import { afterEach, beforeEach, describe, expect, it, vi } from "vitest";
+import { PromptDebouncer } from "./promptDebouncer";
+
+describe("PromptDebouncer", () => {
+  beforeEach(() => {
+    vi.useFakeTimers();
+  });
+
+  afterEach(() => {
+    vi.useRealTimers();
+  });
+
+  it("should wait for the output to settle before detecting", async () => {
+    let screen = "";
+    const detected: string[] = [];
+    const debouncer = new PromptDebouncer<string>(100, (line) => {
+      if (screen.includes(line)) detected.push(line);
+    });
+
+    // partial render: the prompt line shows up, more output follows quickly
+    screen = "Do you want to proceed?\n❯ 1. Yes";
+    debouncer.propose("❯ 1. Yes");
+    await vi.advanceTimersByTimeAsync(60);
+    screen += "\n  2. No";
+    debouncer.poke();
+    await vi.advanceTimersByTimeAsync(60);
+    expect(detected).toEqual([]);
+
+    // complete render, quiet afterwards
+    await vi.advanceTimersByTimeAsync(100);
+    expect(detected).toEqual(["❯ 1. Yes"]);
+  });
+
+  it("should drop a candidate overwritten before the screen settled", async () => {
+    let screen = "";
+    const detected: string[] = [];
+    const debouncer = new PromptDebouncer<string>(100, (line) => {
+      if (screen.includes(line)) detected.push(line);
+    });
+
+    screen = "❯ 1. Yes";
+    debouncer.propose("❯ 1. Yes");
+    await vi.advanceTimersByTimeAsync(50);
+    screen = "⠋ Thinking…"; // the line was a transient mid-paint frame
+    debouncer.poke();
+    await vi.advanceTimersByTimeAsync(200);
+
+    expect(detected).toEqual([]);
+  });
+
+  it("should only handle the latest candidate", async () => {
+    const onStable = vi.fn();
+    const debouncer = new PromptDebouncer<string>(100, onStable);
+
+    debouncer.propose("first");
+    debouncer.propose("second");
+    await vi.advanceTimersByTimeAsync(150);
+
+    expect(onStable).toHaveBeenCalledTimes(1);
+    expect(onStable).toHaveBeenCalledWith("second");
+  });
+
+  it("should ignore pokes without a candidate", async () => {
+    const onStable = vi.fn();
+    const debouncer = new PromptDebouncer<string>(100, onStable);
+
+    debouncer.poke();
+    await vi.advanceTimersByTimeAsync(150);
+
+    expect(onStable).not.toHaveBeenCalled();
+  });
+});
@@ -0,0 +1,51 @@
+/**
+ * Render-stability debounce for prompt detection, behind `--prompt-debounce`
+ *
+ * A matched prompt line isn't answered right away: it's held until the
+ * output has been quiet for `delayMs`, i.e. the agent finished painting,
+ * and then handed to `onStable` which re-checks it against the settled
+ * screen. Lines matched mid-paint and overwritten afterwards are dropped
+ * this way instead of getting a spurious Enter.
+ */
+export class PromptDebouncer<T> {
+  private pending: T | null = null;
+  private timer: ReturnType<typeof setTimeout> | null = null;
+  private running: Promise<unknown> = Promise.resolve();
+
+  /**
+   * @param delayMs - How long the output must be quiet before detection runs
+   * @param onStable - Called with the latest candidate once stable, calls never overlap
+   */
+  constructor(
+    private delayMs: number,
+    private onStable: (candidate: T) => unknown,
+  ) {}
+
+  /** Queue a matched prompt, replacing any earlier candidate */
+  propose(candidate: T) {
+    this.pending = candidate;
+    this.poke();
+  }
+
+  /** New output arrived, restart the countdown */
+  poke() {
+    if (this.pending === null) return;
+    if (this.timer) clearTimeout(this.timer);
+    this.timer = setTimeout(() => this.fire(), this.delayMs);
+  }
+
+  /** Drop the pending candidate, e.g. on exit */
+  cancel() {
+    if (this.timer) clearTimeout(this.timer);
+    this.timer = null;
+    this.pending = null;
+  }
+
+  private fire() {
+    const candidate = this.pending;
+    this.timer = null;
+    this.pending = null;
+    if (candidate === null) return;
+    this.running = this.running.then(() => this.onStable(candidate)).catch(() => null);
+  }
+}
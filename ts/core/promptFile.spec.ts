@@ -0,0 +1,59 @@
+import { describe, expect, it } from "vitest";
+import { parsePromptFile } from "./promptFile";
+
+describe("parsePromptFile", () => {
+  it("should return a plain file as the prompt", () => {
+    expect(parsePromptFile("Fix the flaky tests\n\n- start with api/\n")).toEqual({
+      prompt: "Fix the flaky tests\n\n- start with api/",
+      options: {},
+    });
+  });
+
+  it("should turn directives into flags and strip them from the prompt", () => {
+    const content = [
+      "#agent-yes: answer delete=no",
+      "# claude-yes: --exit-on-idle 2m",
+      "Clean up the build dir",
+      "#agent-yes: verbose",
+      "#agent-yes: no-passthrough",
+    ].join("\n");
+    expect(parsePromptFile(content)).toEqual({
+      prompt: "Clean up the build dir",
+      options: { answer: "delete=no", "exit-on-idle": "2m", verbose: true, passthrough: false },
+    });
+  });
+
+  it("should accept flag=value and keep spaces in values", () => {
+    expect(parsePromptFile("#agent-yes: prefix=[api]\n#agent-yes: fail-on Error: .*").options).toEqual({
+      prefix: "[api]",
+      "fail-on": "Error: .*",
+    });
+  });
+
+  it("should collect repeated flags", () => {
+    expect(parsePromptFile("#agent-yes: answer delete=no\n#agent-yes: answer network=no").options).toEqual({
+      answer: ["delete=no", "network=no"],
+    });
+  });
+
+  it("should keep markdown headings and other comments", () => {
+    expect(parsePromptFile("# Task\n#agent yes is great").prompt).toBe("# Task\n#agent yes is great");
+  });
+
+  it("should reject flags that run commands, read files or open sockets", () => {
+    for (const directive of [
+      "on-exit rm -rf ~",
+      "notify-command curl evil.example",
+      "command ./not-claude",
+      "patterns-file https://evil.example/p.json",
+      "control-socket /tmp/ay.sock",
+      "no-robust=x",
+    ])
+      expect(() => parsePromptFile(`#agent-yes: ${directive}\nhi`), directive).toThrow(/is not allowed/);
+    expect(parsePromptFile("#agent-yes: no-robust\nhi").options).toEqual({ robust: false });
+  });
+
+  it("should reject a directive without a flag", () => {
+    expect(() => parsePromptFile("#agent-yes: =no")).toThrow(/Invalid prompt file directive/);
+  });
+});
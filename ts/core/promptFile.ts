@@ -0,0 +1,118 @@
+import type { Profile } from "./profiles.ts";
+
+/**
+ * Prompt files for `--prompt-file`, with inline directives
+ *
+ * A prompt file can carry the automation policy of its task: lines like
+ *
+ *     #agent-yes: answer delete=no
+ *     #agent-yes: exit-on-idle 2m
+ *
+ * are read as agent-yes flags (`--answer delete=no --exit-on-idle 2m`) and
+ * removed from the prompt sent to the agent. Flags given on the command line
+ * override them. Grammar, one directive per line:
+ *
+ *     #agent-yes: <flag> [value]     (also #claude-yes:, and <flag>=<value>)
+ *
+ * The flag is a long option name with or without the leading `--`; the
+ * value is the rest of the line, spaces included. A flag without a value is
+ * true, `no-<flag>` is false, and a repeated flag collects its values.
+ *
+ * Task files get shared, so only the flags in PROMPT_FILE_DIRECTIVES are
+ * accepted: answer policies, idle and exit handling, output. Anything that
+ * runs commands (`on-exit`, `notify-command`, `command`), reads or writes
+ * other files, or opens sockets and URLs is rejected, and a `--profile`
+ * wins over the file, so a task can't loosen `--profile safe`.
+ */
+
+const DIRECTIVE = /^#\s*(?:agent|claude)-yes:\s*(.*)$/;
+
+/** Flags a prompt file may set, see the module comment */
+export const PROMPT_FILE_DIRECTIVES: readonly string[] = [
+  // answer policies
+  "answer",
+  "default-answer",
+  "only-answer",
+  "confirm-dangerous-once",
+  "confirm-destructive",
+  "pager-action",
+  "pick-session",
+  "menu-response",
+  "select-label",
+  "yn-response",
+  "dry-run",
+  // idle, restarts and exit
+  "exit-on-idle",
+  "idle",
+  "idle-mode",
+  "idle-ignore-spinners",
+  "nudge-on-idle",
+  "busy-marker",
+  "wait-on-rate-limit",
+  "max-runtime",
+  "max-restarts",
+  "restart-backoff",
+  "graceful-idle-exit",
+  "robust",
+  "once",
+  "expect",
+  "fail-on",
+  // messages and timing
+  "guardrail",
+  "type-prompt",
+  "response-delay",
+  "prompt-debounce",
+  "max-prompt-age",
+  "simulate-typing",
+  "answer-delay-jitter",
+  // output
+  "verbose",
+  "quiet",
+  "passthrough",
+  "prefix",
+  "color",
+  "compact-logs",
+  "strip-banner",
+  "log-timestamps",
+];
+
+export interface PromptFile {
+  prompt: string; // the file without directive lines
+  options: Profile; // directives as flag values, applied like a --profile, e.g. { "exit-on-idle": "2m" }
+}
+
+/**
+ * Split a prompt file into the prompt and its directives
+ *
+ * @throws Error on a directive without a flag name, or with a flag not in PROMPT_FILE_DIRECTIVES
+ *
+ * @example
+ * ```typescript
+ * parsePromptFile("#agent-yes: answer delete=no\nClean up the build dir");
+ * // { prompt: "Clean up the build dir", options: { answer: "delete=no" } }
+ * ```
+ */
+export function parsePromptFile(content: string): PromptFile {
+  const options: Profile = {};
+  const promptLines: string[] = [];
+  for (const line of content.split(/\r?\n/)) {
+    const directive = line.trim().match(DIRECTIVE)?.[1]?.trim();
+    if (directive === undefined) {
+      promptLines.push(line);
+      continue;
+    }
+    const [, name, value] = directive.match(/^(?:--)?([\w-]+)(?:\s*=\s*|\s+|$)(.*)$/) ?? [];
+    if (!name) throw new Error(`Invalid prompt file directive "${line.trim()}", expected "#agent-yes: <flag> [value]"`);
+    const flag = value?.trim() ? name : name.replace(/^no-/, "");
+    if (!PROMPT_FILE_DIRECTIVES.includes(flag))
+      throw new Error(
+        `Prompt file directive "${flag}" is not allowed, pass it on the command line instead; allowed: ${PROMPT_FILE_DIRECTIVES.join(", ")}`,
+      );
+    if (!value?.trim()) options[name.replace(/^no-/, "")] = !name.startsWith("no-");
+    else {
+      const previous = options[name];
+      options[name] = previous === undefined ? value.trim() : [previous, value.trim()].flat().map(String);
+    }
+  }
+  return { prompt: promptLines.join("\n").trim(), options };
+}
@@ -0,0 +1,64 @@
+import { mkdtemp, readdir, readFile, rm } from "fs/promises";
+import os from "os";
+import path from "path";
+import { afterEach, beforeEach, describe, expect, it } from "vitest";
+import { fixtureName, PromptRecorder } from "./promptRecorder";
+
+const match = { rule: "enter[3]", pattern: "❯ 1\\. Yes", action: "enter", response: "\r" } as const;
+
+describe("fixtureName", () => {
+  it("should build a file name from cli, rule and time", () => {
+    const date = new Date("2026-01-02T03:04:05.678Z");
+    expect(fixtureName("claude", "enter[3]", date)).toBe("claude-enter-3-20260102T030405678Z.raw.txt");
+  });
+
+  it("should slugify typingRespond rules", () => {
+    const date = new Date("2026-01-02T03:04:05.678Z");
+    expect(fixtureName("claude", 'typingRespond["1\\n"][0]', date)).toBe(
+      "claude-typingRespond-1-n-0-20260102T030405678Z.raw.txt",
+    );
+  });
+});
+
+describe("PromptRecorder", () => {
+  let dir: string;
+
+  beforeEach(async () => {
+    dir = await mkdtemp(path.join(os.tmpdir(), "agent-yes-fixtures-"));
+  });
+
+  afterEach(async () => {
+    await rm(dir, { recursive: true, force: true });
+  });
+
+  it("should save the recent raw output when a prompt is recorded", async () => {
+    const recorder = new PromptRecorder(dir, "claude");
+    recorder.feed("\u001b[1mDo you want to proceed?\u001b[0m\r\n");
+    recorder.feed("❯ 1. Yes\r\n");
+
+    const filePath = await recorder.record(match);
+
+    expect(await readFile(filePath!, "utf8")).toBe(
+      "\u001b[1mDo you want to proceed?\u001b[0m\r\n❯ 1. Yes\r\n",
+    );
+  });
+
+  it("should only keep the tail of the output", async () => {
+    const recorder = new PromptRecorder(dir, "claude", 20, 4);
+    recorder.feed("abcdef");
+
+    const filePath = await recorder.record(match);
+
+    expect(await readFile(filePath!, "utf8")).toBe("cdef");
+  });
+
+  it("should stop recording after the cap", async () => {
+    const recorder = new PromptRecorder(dir, "claude", 2);
+
+    expect(await recorder.record(match)).not.toBeNull();
+    await new Promise((resolve) => setTimeout(resolve, 2)); // distinct timestamps
+    expect(await recorder.record(match)).not.toBeNull();
+    expect(await recorder.record(match)).toBeNull();
+    expect(await readdir(dir)).toHaveLength(2);
+  });
+});
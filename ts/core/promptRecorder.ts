@@ -0,0 +1,65 @@
+import path from "path";
+import { mkdir, writeFile } from "fs/promises";
+import { logger } from "../logger.ts";
+import type { PromptMatch } from "./responders.ts";
+
+/**
+ * Prompt fixture recorder, behind `--record-prompts <dir>`
+ *
+ * Keeps a tail of the raw agent output, and whenever a prompt is detected
+ * saves it to `<dir>` as one fixture file per prompt, ready to be used in
+ * detection tests without running the real cli.
+ */
+
+/**
+ * Build a fixture file name from the cli, matched rule and time
+ *
+ * @example
+ * ```typescript
+ * fixtureName("claude", "enter[3]", new Date("2026-01-02T03:04:05.678Z"));
+ * // "claude-enter-3-20260102T030405678Z.raw.txt"
+ * ```
+ */
+export function fixtureName(cli: string, rule: string, date = new Date()): string {
+  const slug = rule.replace(/[^a-zA-Z0-9]+/g, "-").replace(/^-|-$/g, "");
+  const stamp = date.toISOString().replace(/[-:.]/g, "");
+  return `${cli}-${slug}-${stamp}.raw.txt`;
+}
+
+export class PromptRecorder {
+  private tail = "";
+  private recorded = 0;
+
+  /**
+   * @param dir - Directory to write fixtures to
+   * @param cli - Cli name, used in file names
+   * @param max - Max fixtures to record per run
+   * @param tailSize - How many chars of recent raw output to keep
+   */
+  constructor(
+    private dir: string,
+    private cli: string,
+    private max = 20,
+    private tailSize = 8192,
+  ) {}
+
+  /** Feed a raw output chunk */
+  feed(chunk: string) {
+    this.tail = (this.tail + chunk).slice(-this.tailSize);
+  }
+
+  /**
+   * Save the recent raw output as a fixture for the matched prompt
+   * @returns Path of the written fixture, or null when the cap is reached
+   */
+  async record(match: PromptMatch): Promise<string | null> {
+    if (this.recorded >= this.max) return null;
+    this.recorded++;
+
+    const filePath = path.resolve(this.dir, fixtureName(this.cli, match.rule));
+    await mkdir(this.dir, { recursive: true });
+    await writeFile(filePath, this.tail);
+    logger.debug(`record|saved prompt fixture ${filePath} (${this.recorded}/${this.max})`);
+    return filePath;
+  }
+}
@@ -0,0 +1,29 @@
+import { Readable } from "stream";
+import { describe, expect, it } from "vitest";
+import { readPromptFromStdin, shouldReadPromptFromStdin } from "./promptStdin";
+
+describe("readPromptFromStdin", () => {
+  it("should read a multi-line prompt until EOF", async () => {
+    const stdin = Readable.from([Buffer.from("Fix the tests.\n\n"), Buffer.from("Then commit.\n")]);
+    expect(await readPromptFromStdin(stdin)).toBe("Fix the tests.\n\nThen commit.");
+  });
+
+  it("should not split multi-byte characters across chunks", async () => {
+    const bytes = Buffer.from("修复测试");
+    const stdin = Readable.from([bytes.subarray(0, 4), bytes.subarray(4)]);
+    expect(await readPromptFromStdin(stdin)).toBe("修复测试");
+  });
+});
+
+describe("shouldReadPromptFromStdin", () => {
+  it("should read piped stdin as the prompt when none is given", () => {
+    expect(shouldReadPromptFromStdin(undefined, false, false)).toBe(true);
+    expect(shouldReadPromptFromStdin(undefined, false, true)).toBe(false); // forwarded as input, as before
+    expect(shouldReadPromptFromStdin(undefined, true, false)).toBe(false);
+  });
+
+  it("should follow --prompt-stdin and --no-prompt-stdin", () => {
+    expect(shouldReadPromptFromStdin(true, false, true)).toBe(true);
+    expect(shouldReadPromptFromStdin(false, false, false)).toBe(false);
+  });
+});
@@ -0,0 +1,60 @@
+import { openSync } from "fs";
+import tty from "tty";
+
+/**
+ * Read the initial prompt from stdin, behind `--prompt-stdin` or piped stdin
+ *
+ * Sequencing: the whole prompt is read from piped stdin until EOF first,
+ * then the session's interactive input switches to the controlling
+ * terminal (/dev/tty), so a here-doc prompt and a live session don't
+ * fight over stdin. Without a terminal (e.g. in CI) the session simply
+ * runs without interactive input.
+ *
+ * Piped stdin is read as the prompt by default when no prompt is given
+ * otherwise, e.g. `cat task.md | claude-yes`; `--no-prompt-stdin` forwards
+ * it to the agent as input instead.
+ */
+
+/**
+ * Whether to read the prompt from stdin
+ *
+ * @param flag - `--prompt-stdin` / `--no-prompt-stdin`, undefined when not given
+ * @param stdinIsTTY - Whether stdin is a terminal, which is never read as a prompt unless asked
+ * @param hasPrompt - Whether a prompt was given on the command line or with `--prompt-file`
+ *
+ * @example
+ * ```typescript
+ * shouldReadPromptFromStdin(undefined, false, false); // true, `cat task.md | claude-yes`
+ * shouldReadPromptFromStdin(undefined, false, true); // false, `yes | claude-yes -- fix it` forwards stdin
+ * ```
+ */
+export function shouldReadPromptFromStdin(flag: boolean | undefined, stdinIsTTY: boolean, hasPrompt: boolean): boolean {
+  return flag ?? (!stdinIsTTY && !hasPrompt);
+}
+
+/**
+ * Read a stream to EOF as a prompt, dropping the trailing newline
+ *
+ * @example
+ * ```typescript
+ * await readPromptFromStdin(Readable.from(["fix\n", "the tests\n"])); // "fix\nthe tests"
+ * ```
+ */
+export async function readPromptFromStdin(stream: AsyncIterable<Buffer | string>): Promise<string> {
+  const chunks: Buffer[] = [];
+  for await (const chunk of stream) chunks.push(Buffer.isBuffer(chunk) ? chunk : Buffer.from(chunk));
+  return Buffer.concat(chunks).toString("utf8").replace(/\r?\n$/, "");
+}
+
+/**
+ * Open the controlling terminal for interactive input once stdin is used up
+ * @returns A tty stream, or undefined when there's no terminal
+ */
+export function openTerminalStdin(): tty.ReadStream | undefined {
+  if (process.platform === "win32") return undefined; // no /dev/tty, CONIN$ isn't a tty.ReadStream
+  try {
+    return new tty.ReadStream(openSync("/dev/tty", "r"));
+  } catch {
+    return undefined;
+  }
+}
@@ -0,0 +1,63 @@
+import { EventEmitter } from "events";
+import { describe, expect, it, vi } from "vitest";
+import { DEFAULT_PTY_SIZE, followTerminalResize, normalizePtySize, parsePtySize } from "./ptySize";
+
+describe("parsePtySize", () => {
+  it("should parse COLSxROWS", () => {
+    expect(parsePtySize("120x40")).toEqual({ cols: 120, rows: 40 });
+    expect(parsePtySize(" 200X50 ")).toEqual({ cols: 200, rows: 50 });
+  });
+
+  it("should reject invalid sizes", () => {
+    expect(() => parsePtySize("120")).toThrow(/Invalid pty size/);
+    expect(() => parsePtySize("0x40")).toThrow(/Invalid pty size/);
+    expect(() => parsePtySize("wide x tall")).toThrow(/Invalid pty size/);
+  });
+});
+
+describe("normalizePtySize", () => {
+  it("should keep a usable size", () => {
+    expect(normalizePtySize({ cols: 120, rows: 40 })).toEqual({ cols: 120, rows: 40 });
+  });
+
+  it("should replace a 0x0 size with the default", () => {
+    expect(normalizePtySize({ cols: 0, rows: 0 })).toEqual(DEFAULT_PTY_SIZE);
+    expect(normalizePtySize({})).toEqual(DEFAULT_PTY_SIZE);
+  });
+
+  it("should replace only the degenerate dimension", () => {
+    expect(normalizePtySize({ cols: 100, rows: 0 })).toEqual({ cols: 100, rows: 24 });
+    expect(normalizePtySize({ cols: NaN, rows: 30 })).toEqual({ cols: 80, rows: 30 });
+    expect(normalizePtySize({ cols: 65535, rows: 30 })).toEqual({ cols: 80, rows: 30 });
+  });
+});
+
+describe("followTerminalResize", () => {
+  it("should apply changed sizes until stopped", () => {
+    const stdout = new EventEmitter();
+    let size = { cols: 80, rows: 24 };
+    const resize = vi.fn();
+    const stop = followTerminalResize(resize, () => size, stdout);
+
+    stdout.emit("resize"); // same size, nothing to do
+    size = { cols: 60, rows: 20 };
+    stdout.emit("resize");
+    expect(resize.mock.calls).toEqual([[{ cols: 60, rows: 20 }]]);
+
+    stop();
+    size = { cols: 40, rows: 10 };
+    stdout.emit("resize");
+    expect(resize).toHaveBeenCalledTimes(1);
+  });
+
+  it("should survive resizing a pty that already exited", () => {
+    const stdout = new EventEmitter();
+    let size = { cols: 80, rows: 24 };
+    const resize = vi.fn(() => {
+      throw new Error("Cannot resize a pty that has already exited");
+    });
+    followTerminalResize(resize, () => size, stdout);
+    size = { cols: 60, rows: 20 };
+    expect(() => stdout.emit("resize")).not.toThrow();
+  });
+});
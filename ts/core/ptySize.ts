@@ -0,0 +1,97 @@
+/**
+ * Pty size override, behind `--pty-size COLSxROWS`
+ *
+ * Kept free of pty imports so the cli arg parser can use it.
+ */
+
+export interface PtySize {
+  cols: number;
+  rows: number;
+}
+
+/**
+ * Parse a `--pty-size` value like "120x40" into dimensions
+ *
+ * @throws Error if the value isn't COLSxROWS with positive integers
+ *
+ * @example
+ * ```typescript
+ * parsePtySize("120x40"); // { cols: 120, rows: 40 }
+ * ```
+ */
+export function parsePtySize(size: string): PtySize {
+  const match = size.trim().match(/^(\d+)\s*[xX×]\s*(\d+)$/);
+  const cols = Number(match?.[1]);
+  const rows = Number(match?.[2]);
+  if (!match || !cols || !rows)
+    throw new Error(`Invalid pty size "${size}", expected COLSxROWS, e.g. 120x40`);
+  return { cols, rows };
+}
+
+/** Size used when the terminal reports no usable size */
+export const DEFAULT_PTY_SIZE: PtySize = { cols: 80, rows: 24 };
+
+const MAX_PTY_DIMENSION = 10000;
+
+/**
+ * Replace a degenerate terminal size with a usable one
+ *
+ * Some CI ptys and nested shells report 0x0 (or garbage) as their size,
+ * which makes the agent render into a zero-width pty. Each dimension that
+ * is missing, zero or absurdly large falls back to the given default.
+ *
+ * @example
+ * ```typescript
+ * normalizePtySize({ cols: 0, rows: 0 }); // { cols: 80, rows: 24 }
+ * normalizePtySize({ cols: 120, rows: 0 }); // { cols: 120, rows: 24 }
+ * ```
+ */
+export function normalizePtySize(
+  size: { cols?: number; rows?: number },
+  fallback: PtySize = DEFAULT_PTY_SIZE,
+): PtySize {
+  const valid = (n?: number): n is number =>
+    Number.isInteger(n) && n! > 0 && n! <= MAX_PTY_DIMENSION;
+  return {
+    cols: valid(size.cols) ? size.cols : fallback.cols,
+    rows: valid(size.rows) ? size.rows : fallback.rows,
+  };
+}
+
+/**
+ * Resize the pty whenever the host terminal is resized (SIGWINCH)
+ *
+ * Sizes are read fresh on each event and only applied when they changed.
+ * `resize` should target the current pty, so sizes keep applying after a
+ * crash restart spawned a new one; errors from a pty that already exited
+ * are ignored.
+ *
+ * @param resize - Apply a size to the current pty
+ * @param getSize - Current size to use, e.g. `() => getTerminalDimensions()`
+ * @param stdout - Terminal emitting "resize" events
+ * @returns A function to stop following, e.g. when the session ended
+ *
+ * @example
+ * ```typescript
+ * const stop = followTerminalResize(({ cols, rows }) => shell.resize(cols, rows), () => getTerminalDimensions());
+ * ```
+ */
+export function followTerminalResize(
+  resize: (size: PtySize) => void,
+  getSize: () => PtySize,
+  stdout: Pick<NodeJS.EventEmitter, "on" | "off"> = process.stdout,
+): () => void {
+  let last = getSize();
+  const onResize = () => {
+    const size = getSize();
+    if (size.cols === last.cols && size.rows === last.rows) return;
+    last = size;
+    try {
+      resize(size);
+    } catch {
+      // the pty exited in between, a restarted one gets the current size when spawned
+    }
+  };
+  stdout.on("resize", onResize);
+  return () => stdout.off("resize", onResize);
+}
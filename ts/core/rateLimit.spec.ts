@@ -0,0 +1,48 @@
+import { describe, expect, it } from "vitest";
+import { parseResetTime, RateLimitWatcher } from "./rateLimit";
+
+describe("parseResetTime", () => {
+  const now = new Date(2025, 5, 14, 10, 0); // local time
+
+  it("should parse epoch seconds and milliseconds", () => {
+    expect(parseResetTime("Claude AI usage limit reached|1749924000")?.toISOString()).toBe("2025-06-14T18:00:00.000Z");
+    expect(parseResetTime("rate limit, retry at 1749924000000")?.toISOString()).toBe("2025-06-14T18:00:00.000Z");
+  });
+
+  it("should parse ISO timestamps", () => {
+    expect(parseResetTime("Rate limit exceeded, resets at 2025-06-14T18:00:00Z")?.toISOString()).toBe(
+      "2025-06-14T18:00:00.000Z",
+    );
+  });
+
+  it("should take clock times as the next local occurrence", () => {
+    expect(parseResetTime("5-hour limit reached ∙ resets 3pm", now)).toEqual(new Date(2025, 5, 14, 15, 0));
+    expect(parseResetTime("Your limit will reset at 3:30pm (Europe/Berlin)", now)).toEqual(new Date(2025, 5, 14, 15, 30));
+    expect(parseResetTime("usage limit reached, resets 9am", now)).toEqual(new Date(2025, 5, 15, 9, 0)); // tomorrow
+    expect(parseResetTime("usage limit reached, resets 12am", now)).toEqual(new Date(2025, 5, 15, 0, 0));
+    expect(parseResetTime("usage limit reached, resets at 16:45", now)).toEqual(new Date(2025, 5, 14, 16, 45));
+  });
+
+  it("should return null without a reset time", () => {
+    expect(parseResetTime("Claude usage limit reached.", now)).toBeNull();
+    expect(parseResetTime("resets 3", now)).toBeNull();
+    expect(parseResetTime("resets 13pm", now)).toBeNull();
+  });
+});
+
+describe("RateLimitWatcher", () => {
+  const now = new Date(2025, 5, 14, 10, 0);
+
+  it("should report each message once, and redraws as seen", () => {
+    const watcher = new RateLimitWatcher([/usage limit reached/i]);
+    expect(watcher.check("  5-hour usage limit reached ∙ resets 3pm", now)).toEqual(new Date(2025, 5, 14, 15, 0));
+    expect(watcher.check("5-hour usage limit reached ∙ resets 3pm  ", now)).toBe("seen");
+    expect(watcher.check("usage limit reached ∙ resets 4pm", now)).toEqual(new Date(2025, 5, 14, 16, 0));
+  });
+
+  it("should ignore other lines and messages without a reset time", () => {
+    const watcher = new RateLimitWatcher([/usage limit reached/i]);
+    expect(watcher.check("Reading files, resets 3pm", now)).toBeNull();
+    expect(watcher.check("⎿  Claude usage limit reached.", now)).toBeNull();
+  });
+});
@@ -0,0 +1,89 @@
+/**
+ * `--wait-on-rate-limit`: sleep through a usage limit instead of exiting on it
+ *
+ * When the agent hits its usage limit it prints when the limit resets, e.g.
+ * `Claude AI usage limit reached|1749924000` or `5-hour limit reached ∙ resets 3pm`.
+ * Without the flag that's a fatal error; with it agent-yes waits until the
+ * reset time plus a small buffer and then tells the agent to continue, so
+ * long unattended runs survive the limit.
+ */
+
+/** Extra wait after the announced reset time, the limit doesn't always lift on the dot */
+export const RATE_LIMIT_BUFFER_MS = 60 * 1000;
+
+const EPOCH = /\b(\d{13}|\d{10})\b/; // seconds or milliseconds
+const ISO = /\b\d{4}-\d{2}-\d{2}[T ]\d{2}:\d{2}(?::\d{2}(?:\.\d+)?)?(?:Z|[+-]\d{2}:?\d{2})?/;
+const CLOCK = /\bresets?\s+(?:at\s+)?(\d{1,2})(?::(\d{2}))?\s*(am|pm)?\b/i;
+
+/**
+ * Find the reset time in a usage/rate-limit message
+ *
+ * Understands epoch seconds or milliseconds, ISO 8601 timestamps, and clock
+ * times after "reset(s) (at)", e.g. "resets 3pm" or "reset at 15:30", which
+ * are taken as the next occurrence in local time.
+ *
+ * @param text - The message
+ * @param now - The current time, for clock times
+ * @returns The reset time, or null when the message has none
+ *
+ * @example
+ * ```typescript
+ * parseResetTime("Claude AI usage limit reached|1749924000"); // 2025-06-14T18:00:00.000Z
+ * parseResetTime("5-hour limit reached ∙ resets 3pm"); // today or tomorrow at 15:00 local time
+ * ```
+ */
+export function parseResetTime(text: string, now = new Date()): Date | null {
+  const iso = text.match(ISO)?.[0];
+  if (iso) {
+    const date = new Date(iso);
+    if (!Number.isNaN(date.getTime())) return date;
+  }
+
+  const digits = text.match(EPOCH)?.[1];
+  if (digits) return new Date(digits.length === 13 ? Number(digits) : Number(digits) * 1000);
+
+  const clock = text.match(CLOCK);
+  if (clock) {
+    const [, hourText, minuteText, meridiem] = clock;
+    if (!minuteText && !meridiem) return null; // "resets 3" is too vague
+    let hour = Number(hourText);
+    const minute = Number(minuteText ?? 0);
+    if (meridiem) {
+      if (hour < 1 || hour > 12) return null;
+      hour = (hour % 12) + (meridiem.toLowerCase() === "pm" ? 12 : 0);
+    }
+    if (hour > 23 || minute > 59) return null;
+    const reset = new Date(now);
+    reset.setHours(hour, minute, 0, 0);
+    if (reset.getTime() <= now.getTime()) reset.setDate(reset.getDate() + 1);
+    return reset;
+  }
+  return null;
+}
+
+/**
+ * Recognize rate-limit messages, each one once
+ *
+ * The cli redraws its screen, so the same message is printed again while
+ * waiting and after continuing; a clock time like "resets 3pm" would then
+ * point at tomorrow. Messages already handled are reported as "seen".
+ */
+export class RateLimitWatcher {
+  private handled = new Set<string>();
+
+  constructor(private patterns: RegExp[]) {}
+
+  /**
+   * @returns The reset time for a new rate-limit message, "seen" for one already handled,
+   *   or null for other lines and messages without a reset time
+   */
+  check(line: string, now = new Date()): Date | "seen" | null {
+    const message = line.trim();
+    if (!this.patterns.some((rx) => rx.test(message))) return null;
+    if (this.handled.has(message)) return "seen";
+    const reset = parseResetTime(message, now);
+    if (!reset) return null;
+    this.handled.add(message);
+    return reset;
+  }
+}
@@ -0,0 +1,85 @@
+import { mkdtemp, readFile, rm, utimes, writeFile } from "fs/promises";
+import os from "os";
+import path from "path";
+import { afterEach, beforeEach, describe, expect, it, vi } from "vitest";
+import { cachePathFor, fetchCached, isRemotePath } from "./remoteFile";
+
+const url = "https://example.com/patterns.json";
+
+describe("fetchCached", () => {
+  let cacheDir: string;
+
+  beforeEach(async () => {
+    cacheDir = await mkdtemp(path.join(os.tmpdir(), "agent-yes-remote-"));
+  });
+
+  afterEach(async () => {
+    await rm(cacheDir, { recursive: true, force: true });
+  });
+
+  it("should fetch and cache", async () => {
+    const fetch = vi.fn(async () => new Response("fresh"));
+    expect(await fetchCached(url, { cacheDir, ttlMs: 60_000, fetch })).toBe("fresh");
+    expect(await fetchCached(url, { cacheDir, ttlMs: 60_000, fetch })).toBe("fresh");
+    expect(fetch).toHaveBeenCalledTimes(1);
+  });
+
+  it("should refetch once the cache is older than the ttl", async () => {
+    await writeFile(cachePathFor(url, cacheDir), "old");
+    const hourAgo = new Date(Date.now() - 3600_000);
+    await utimes(cachePathFor(url, cacheDir), hourAgo, hourAgo);
+
+    const fetch = vi.fn(async () => new Response("new"));
+    expect(await fetchCached(url, { cacheDir, ttlMs: 60_000, fetch })).toBe("new");
+  });
+
+  it("should fall back to a stale cache when the fetch fails", async () => {
+    await writeFile(cachePathFor(url, cacheDir), "stale");
+    const hourAgo = new Date(Date.now() - 3600_000);
+    await utimes(cachePathFor(url, cacheDir), hourAgo, hourAgo);
+
+    const fetch = vi.fn(async () => new Response("down", { status: 503 }));
+    expect(await fetchCached(url, { cacheDir, ttlMs: 60_000, fetch })).toBe("stale");
+  });
+
+  it("should keep the cache when the fetched text doesn't validate", async () => {
+    await writeFile(cachePathFor(url, cacheDir), "good");
+    const hourAgo = new Date(Date.now() - 3600_000);
+    await utimes(cachePathFor(url, cacheDir), hourAgo, hourAgo);
+
+    const fetch = vi.fn(async () => new Response("<html>Sign in to Wi-Fi</html>"));
+    const validate = (text: string) => {
+      if (text.startsWith("<")) throw new Error("not JSON");
+    };
+    expect(await fetchCached(url, { cacheDir, ttlMs: 60_000, fetch, validate })).toBe("good");
+    expect(await readFile(cachePathFor(url, cacheDir), "utf8")).toBe("good");
+  });
+
+  it("should time out a hanging fetch", async () => {
+    const fetch = vi.fn(
+      (_url: string | URL | Request, init?: RequestInit) =>
+        new Promise<Response>((_, reject) => init?.signal?.addEventListener("abort", () => reject(init.signal!.reason))),
+    );
+    expect(await fetchCached(url, { cacheDir, ttlMs: 60_000, timeoutMs: 20, fetch })).toBeNull();
+  });
+
+  it("should return null without a cache when the fetch fails", async () => {
+    const fetch = vi.fn(async () => Promise.reject(new Error("offline")));
+    expect(await fetchCached(url, { cacheDir, ttlMs: 60_000, fetch })).toBeNull();
+  });
+
+  it("should refuse plain http unless allowed", async () => {
+    const fetch = vi.fn(async () => new Response("x"));
+    await expect(fetchCached("http://example.com/p.json", { cacheDir, ttlMs: 0, fetch })).rejects.toThrow(
+      /only https:\/\/ URLs/,
+    );
+    expect(await fetchCached("http://example.com/p.json", { cacheDir, ttlMs: 0, fetch, allowInsecure: true })).toBe("x");
+  });
+});
+
+describe("isRemotePath", () => {
+  it("should tell URLs from file paths", () => {
+    expect(isRemotePath(url)).toBe(true);
+    expect(isRemotePath("./patterns.json")).toBe(false);
+  });
+});
@@ -0,0 +1,71 @@
+import path from "path";
+import { createHash } from "crypto";
+import { mkdir, readFile, stat, writeFile } from "fs/promises";
+import { logger } from "../logger.ts";
+
+/**
+ * Remote text files with a local cache, for shared pattern sets
+ *
+ * Fetched once at startup and cached under the config dir. A fresh cache
+ * (younger than `ttlMs`) is used without fetching; when the fetch fails a
+ * stale cache is used with a warning, and without any cache the caller
+ * falls back to its built-ins. Only HTTPS unless explicitly allowed.
+ * A response that fails `validate` (e.g. a 200 captive portal page) counts
+ * as a failed fetch, so it never replaces a good cache.
+ */
+
+const FETCH_TIMEOUT_MS = 10_000; // don't hang the startup on a slow server
+
+export interface FetchCachedOptions {
+  cacheDir: string;
+  ttlMs: number; // how long a cached copy is used without refetching
+  allowInsecure?: boolean; // allow plain http:// URLs
+  validate?: (text: string) => void; // throws when the fetched text is unusable, before it's cached
+  timeoutMs?: number; // default FETCH_TIMEOUT_MS
+  fetch?: typeof globalThis.fetch; // for tests
+}
+
+/** Whether a path given on the command line is a URL rather than a local file */
+export function isRemotePath(value: string): boolean {
+  return /^https?:\/\//i.test(value);
+}
+
+/**
+ * Path of the cached copy of `url`
+ */
+export function cachePathFor(url: string, cacheDir: string): string {
+  const hash = createHash("sha256").update(url).digest("hex").slice(0, 16);
+  return path.resolve(cacheDir, `${hash}.cache`);
+}
+
+/**
+ * Get the text at `url`, from the cache when fresh
+ * @throws Error for non-HTTPS URLs unless `allowInsecure`
+ * @returns The text, or null when it can't be fetched and nothing is cached
+ */
+export async function fetchCached(url: string, options: FetchCachedOptions): Promise<string | null> {
+  const { cacheDir, ttlMs, allowInsecure = false, validate, timeoutMs = FETCH_TIMEOUT_MS, fetch = globalThis.fetch } = options;
+  if (!/^https:\/\//i.test(url) && !allowInsecure)
+    throw new Error(`Refusing to fetch ${url}: only https:// URLs are allowed`);
+
+  const cachePath = cachePathFor(url, cacheDir);
+  const cachedAt = await stat(cachePath).then((s) => s.mtimeMs, () => null);
+  if (cachedAt !== null && Date.now() - cachedAt < ttlMs) return await readFile(cachePath, "utf8");
+
+  try {
+    const response = await fetch(url, { signal: AbortSignal.timeout(timeoutMs) });
+    if (!response.ok) throw new Error(`HTTP ${response.status}`);
+    const text = await response.text();
+    validate?.(text);
+    await mkdir(cacheDir, { recursive: true });
+    await writeFile(cachePath, text);
+    return text;
+  } catch (error) {
+    if (cachedAt !== null) {
+      logger.warn(`Failed to fetch ${url} (${String(error)}), using the cached copy`);
+      return await readFile(cachePath, "utf8");
+    }
+    logger.warn(`Failed to fetch ${url} (${String(error)}), and no cached copy`);
+    return null;
+  }
+}
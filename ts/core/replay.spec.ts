@@ -0,0 +1,65 @@
+import { readFileSync } from "fs";
+import path from "path";
+import { describe, expect, it } from "vitest";
+import { replayOutput } from "./replay";
+import type { AgentCliConfig } from "../index";
+
+const fixture = (name: string) => readFileSync(path.resolve(__dirname, "../tests/fixtures", name), "utf8");
+
+const conf: AgentCliConfig = {
+  ready: [/\? for shortcuts/],
+  enter: [/❯ 1\. Yes/],
+  notices: [/Press Enter to dismiss/],
+  mcpTrust: { detect: [/New MCP servers? found in \.mcp\.json/], yes: "2", no: "3" },
+};
+
+describe("replayOutput", () => {
+  it("should answer the MCP trust prompt of a fixture", () => {
+    const { responses, render } = replayOutput(fixture("claude-mcp-trust.txt"), conf);
+    expect(responses).toEqual([
+      expect.objectContaining({ rule: "mcpTrust[0]", response: "3", line: expect.stringContaining("github") }),
+    ]);
+    expect(render).toContain("Continue without using this MCP server");
+  });
+
+  it("should pass answer policies on to detection", () => {
+    expect(replayOutput(fixture("claude-mcp-trust.txt"), conf, { trustMcp: "ask" }).responses).toEqual([]);
+    expect(replayOutput(fixture("claude-whats-new.txt"), conf).responses.map((r) => r.rule)).toEqual(["notices[0]"]);
+    expect(replayOutput(fixture("claude-whats-new.txt"), conf, { autoDismissNotices: false }).responses).toEqual([]);
+  });
+
+  it("should detect a prompt split across colored chunks", () => {
+    const chunks = ["Do you want to make this edit?\n\u001b[36m❯ 1.", " Yes\u001b[0m\n", "  2. No\n"];
+    const { responses } = replayOutput(chunks, conf);
+    expect(responses).toEqual([
+      {
+        rule: "enter[0]",
+        pattern: "❯ 1\\. Yes",
+        action: "enter",
+        response: "\r",
+        category: "edit",
+        line: "❯ 1. Yes",
+        offset: chunks[0]!.length + chunks[1]!.length,
+      },
+    ]);
+  });
+
+  it("should decode multi-byte chars split across byte chunks", () => {
+    const bytes = Buffer.from("❯ 1. Yes\n");
+    const { responses, render } = replayOutput([bytes.subarray(0, 1), bytes.subarray(1)], conf);
+    expect(responses.map((r) => r.rule)).toEqual(["enter[0]"]);
+    expect(render).toContain("❯ 1. Yes");
+  });
+
+  it("should render a char cut off at the end of the output as a replacement char", () => {
+    const bytes = Buffer.from("Done 😀");
+    const { render } = replayOutput([bytes.subarray(0, 5), bytes.subarray(5, -1)], conf);
+    expect(render.trimEnd()).toBe("Done �");
+  });
+
+  it("should track the agent state", () => {
+    expect(replayOutput("Loading…\n", conf).state).toBe("starting");
+    expect(replayOutput("> \n? for shortcuts\n", conf).state).toBe("awaiting-input");
+    expect(replayOutput("✻ Working… (esc to interrupt)\n? for shortcuts\n", conf).state).toBe("busy");
+  });
+});
@@ -0,0 +1,86 @@
+import { TerminalTextRender } from "terminal-render";
+import type { AgentCliConfig } from "../index.ts";
+import { removeControlCharacters } from "../removeControlCharacters.ts";
+import { Utf8StreamDecoder } from "../utf8Decoder.ts";
+import { AgentStateTracker, type AgentState } from "./agentState.ts";
+import { detectPrompt, type DetectOptions, type PromptMatch } from "./responders.ts";
+
+/**
+ * Replay recorded agent output through the detection pipeline, for tests
+ *
+ * Runs chunks of raw pty output (ANSI codes and all, e.g. a fixture saved by
+ * `--record-prompts`) through the same steps as a live session: the virtual
+ * screen, control character removal, line splitting and prompt detection.
+ * Nothing is spawned or written, so tests are fast and deterministic, and
+ * don't need the real cli installed.
+ */
+
+export interface ReplayResponse extends PromptMatch {
+  line: string; // output line that triggered the response
+  offset: number; // end of the chunk that completed the line, in chars (or bytes, for byte chunks) of the output
+}
+
+export interface ReplayResult {
+  responses: ReplayResponse[]; // what agent-yes would have answered, in order
+  render: string; // final rendered screen text
+  state: AgentState; // agent state after the last chunk, see agentState.ts
+}
+
+/**
+ * Feed output chunks through rendering and prompt detection
+ *
+ * Chunks are processed one by one as if read from the pty, so a prompt split
+ * across chunks is detected once its line completes, and each line sees the
+ * screen as rendered up to its chunk. Byte chunks are decoded as UTF-8 across
+ * chunk boundaries.
+ *
+ * @param output - Raw output, as one string or a list of chunks
+ * @param conf - CLI configuration holding the patterns, e.g. `CLIS_CONFIG.claude`
+ * @param options - Answer policies, as for a live session
+ *
+ * @example
+ * ```typescript
+ * const { responses, render } = replayOutput(readFileSync("claude-trust.raw.txt", "utf8"), CLIS_CONFIG.claude);
+ * expect(responses.map((r) => r.rule)).toEqual(["enter[0]"]);
+ * ```
+ */
+export function replayOutput(
+  output: string | Uint8Array | (string | Uint8Array)[],
+  conf: AgentCliConfig,
+  options: Omit<DetectOptions, "screen"> = {},
+): ReplayResult {
+  const terminalRender = new TerminalTextRender();
+  const agentState = new AgentStateTracker(conf.busy);
+  const decoder = new Utf8StreamDecoder();
+  const responses: ReplayResponse[] = [];
+  let pending = "";
+  let offset = 0;
+
+  const processLine = (line: string) => {
+    const screen = terminalRender.render().split("\n").slice(-12).join("\n"); // the prompt box is at the bottom
+    agentState.observe(conf.ready?.some((rx) => line.match(rx)) ?? false, screen);
+    const match = detectPrompt(line, conf, { ...options, screen });
+    if (match) responses.push({ ...match, line, offset });
+  };
+
+  for (const chunk of [output].flat()) {
+    const text = typeof chunk === "string" ? chunk : decoder.write(chunk);
+    offset += chunk.length;
+    terminalRender.write(text);
+    if (conf.noEOL) {
+      processLine(removeControlCharacters(text)); // no \n in the output, each chunk is a line
+      continue;
+    }
+    const lines = (pending + removeControlCharacters(text)).split("\n");
+    pending = lines.pop()!;
+    lines.forEach(processLine);
+  }
+  const tail = decoder.end(); // e.g. a log cut off mid-char
+  if (tail) {
+    terminalRender.write(tail);
+    pending += removeControlCharacters(tail);
+  }
+  if (pending) processLine(pending);
+
+  return { responses, render: terminalRender.render(), state: agentState.state };
+}
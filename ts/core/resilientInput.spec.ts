@@ -0,0 +1,50 @@
+import { PassThrough } from "stream";
+import { describe, expect, it, vi } from "vitest";
+import { resilientInput } from "./resilientInput";
+
+const readAll = async (stream: ReadableStream<Buffer>) => {
+  const chunks: string[] = [];
+  for await (const chunk of stream) chunks.push(chunk.toString());
+  return chunks;
+};
+
+/** A stdin whose fd was closed under it: reading fails right away */
+const closedStdin = () => {
+  const stdin = new PassThrough();
+  setTimeout(() => stdin.destroy(Object.assign(new Error("EBADF: bad file descriptor, read"), { code: "EBADF" })));
+  return stdin;
+};
+
+describe("resilientInput", () => {
+  it("should pass chunks through and end with the input", async () => {
+    const stdin = new PassThrough();
+    const chunks = readAll(resilientInput(stdin, { fallback: () => undefined }));
+    stdin.write("hello");
+    stdin.end("world");
+    expect(await chunks).toEqual(["hello", "world"]);
+  });
+
+  it("should end cleanly and warn once when a closed stdin has no fallback", async () => {
+    const warn = vi.fn();
+    expect(await readAll(resilientInput(closedStdin(), { fallback: () => undefined, warn }))).toEqual([]);
+    expect(warn).toHaveBeenCalledTimes(1);
+    expect(warn.mock.calls[0]![0]).toMatch(/stdin read failed \(EBADF.*continuing without input/);
+  });
+
+  it("should switch to the fallback input after a read error", async () => {
+    const warn = vi.fn();
+    const terminal = new PassThrough();
+    const chunks = readAll(resilientInput(closedStdin(), { fallback: () => terminal, warn }));
+    setTimeout(() => terminal.end("typed"), 10);
+    expect(await chunks).toEqual(["typed"]);
+    expect(warn.mock.calls[0]![0]).toMatch(/switched to the terminal/);
+  });
+
+  it("should fall back only once", async () => {
+    const warn = vi.fn();
+    const fallback = vi.fn(closedStdin);
+    expect(await readAll(resilientInput(closedStdin(), { fallback, warn }))).toEqual([]);
+    expect(fallback).toHaveBeenCalledTimes(1);
+    expect(warn).toHaveBeenCalledTimes(1);
+  });
+});
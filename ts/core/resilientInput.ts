@@ -0,0 +1,85 @@
+import { logger } from "../logger.ts";
+import { openTerminalStdin } from "./promptStdin.ts";
+
+/**
+ * Keep interactive input alive when reading stdin fails
+ *
+ * A read error on stdin (EIO after the terminal hung up, EBADF on a closed
+ * fd, a terminal quirk) errors the input stream, and with it the whole
+ * session pipeline, or worse, leaves a session where typing does nothing.
+ * Instead, the first error is logged once and input switches to a fresh
+ * read of the controlling terminal; when that isn't possible or fails too,
+ * input simply ends, as if stdin was closed, and the agent keeps running.
+ */
+
+export interface ResilientInputOptions {
+  fallback?: () => NodeJS.ReadableStream | undefined; // input to switch to after an error, the terminal by default
+  warn?: (message: string) => void;
+}
+
+/**
+ * Read a stream of input bytes, falling back once on read errors
+ *
+ * @param primary - Input to read, usually stdin
+ * @returns A stream of its chunks, then the fallback's chunks after an error; it never errors
+ *
+ * @example
+ * ```typescript
+ * sflow(resilientInput(process.stdin)).forEach((buffer) => shell.write(buffer.toString()));
+ * ```
+ */
+export function resilientInput(
+  primary: NodeJS.ReadableStream,
+  { fallback = openTerminalStdin, warn = (message) => logger.warn(message) }: ResilientInputOptions = {},
+): ReadableStream<Buffer> {
+  let current: NodeJS.ReadableStream = primary;
+  let fellBack = false;
+  let closed = false;
+
+  const detach = (stream: NodeJS.ReadableStream) => {
+    stream.removeListener("data", onData);
+    stream.removeListener("end", onEnd);
+    stream.removeListener("error", onError);
+    stream.on("error", () => null); // late errors of a dropped stream must not crash the process
+  };
+  const attach = (stream: NodeJS.ReadableStream) => {
+    current = stream;
+    stream.on("data", onData);
+    stream.on("end", onEnd);
+    stream.on("error", onError);
+  };
+
+  let controller!: ReadableStreamDefaultController<Buffer>;
+  const close = () => {
+    if (closed) return;
+    closed = true;
+    detach(current);
+    controller.close();
+  };
+  function onData(chunk: Buffer | string) {
+    if (!closed) controller.enqueue(Buffer.isBuffer(chunk) ? chunk : Buffer.from(chunk));
+  }
+  function onEnd() {
+    close();
+  }
+  function onError(error: Error) {
+    const next = fellBack ? undefined : fallback();
+    if (!fellBack)
+      warn(`stdin read failed (${error.message}), ${next ? "switched to the terminal" : "continuing without input"}`);
+    fellBack = true;
+    if (!next) return close();
+    detach(current);
+    attach(next);
+  }
+
+  return new ReadableStream<Buffer>({
+    start(c) {
+      controller = c;
+      attach(primary);
+    },
+    cancel() {
+      closed = true;
+      detach(current);
+    },
+  });
+}
@@ -0,0 +1,577 @@
+import { readFileSync } from "fs";
+import path from "path";
+import { describe, expect, it, vi } from "vitest";
+import {
+  continueFallback,
+  createAutoResponseHandler,
+  detectPrompt,
+  extractConfirmationWord,
+  isPromptOnScreen,
+  respondToPrompt,
+  type DetectOptions,
+} from "./responders";
+import type { AgentCliConfig } from "../index";
+import type { AgentContext } from "./context";
+import { AgentStateTracker } from "./agentState";
+import { ActivityTracker } from "./activity";
+import { ManualConfirmation } from "./riskyPrompts";
+import type { ConfirmGate } from "./confirmGate";
+
+const fixture = (name: string) =>
+  readFileSync(path.resolve(__dirname, "../tests/fixtures", name), "utf8").split("\n");
+
+describe("detectPrompt", () => {
+  const conf: AgentCliConfig = {
+    enter: [/^.{0,4} 1\. Yes/m, /❯ 1\. Yes/m, /Press Enter to continue…/m],
+    typingRespond: {
+      "1\n": [/│ Do you want to use this API key\?/],
+    },
+  };
+
+  it("should return null when nothing matches", () => {
+    expect(detectPrompt("just some output", conf)).toBeNull();
+  });
+
+  it("should report the index of the matched enter pattern", () => {
+    const match = detectPrompt("Press Enter to continue…", conf);
+    expect(match).toEqual({
+      rule: "enter[2]",
+      pattern: "Press Enter to continue…",
+      action: "enter",
+      response: "\r",
+    });
+  });
+
+  it("should report the first matching enter pattern", () => {
+    expect(detectPrompt(" ❯ 1. Yes", conf)?.rule).toBe("enter[0]");
+    expect(detectPrompt("      ❯ 1. Yes", conf)?.rule).toBe("enter[1]");
+  });
+
+  it("should report typingRespond rules with their response", () => {
+    const match = detectPrompt("│ Do you want to use this API key?", conf);
+    expect(match?.rule).toBe('typingRespond["1\\n"][0]');
+    expect(match?.action).toBe("type");
+    expect(match?.response).toBe("1\n");
+  });
+
+  it("should answer only the first match, enter before typingRespond and in key order", () => {
+    const overlapping: AgentCliConfig = {
+      enter: [/Continue\?/],
+      typingRespond: { "y\n": [/\[y\/n\]/], "yes\n": [/Continue\? \[y\/n\]/] },
+    };
+    expect(detectPrompt("Continue? [y/n]", overlapping)).toEqual({
+      rule: "enter[0]",
+      pattern: "Continue\\?",
+      action: "enter",
+      response: "\r",
+    });
+    expect(detectPrompt("Continue? [y/n]", { typingRespond: overlapping.typingRespond })?.rule).toBe(
+      'typingRespond["y\\n"][0]',
+    );
+  });
+});
+
+describe("detectPrompt MCP trust", () => {
+  const conf: AgentCliConfig = {
+    enter: [/❯ 1\. Yes/m],
+    mcpTrust: {
+      detect: [/New MCP servers? found in \.mcp\.json/, /Do you trust this MCP server/i],
+      yes: "2",
+      no: "3",
+    },
+  };
+  const matches = (trustMcp?: "yes" | "no" | "ask") =>
+    fixture("claude-mcp-trust.txt")
+      .map((line) => detectPrompt(line, conf, { trustMcp }))
+      .filter((match) => match !== null);
+
+  it("should decline MCP servers by default", () => {
+    expect(matches()).toEqual([
+      {
+        rule: "mcpTrust[0]",
+        pattern: "New MCP servers? found in \\.mcp\\.json",
+        action: "type",
+        response: "3",
+      },
+    ]);
+  });
+
+  it("should trust the server when opted in", () => {
+    expect(matches("yes").map((m) => m.response)).toEqual(["2"]);
+  });
+
+  it("should leave the prompt to the user with ask", () => {
+    expect(matches("ask")).toEqual([]);
+  });
+});
+
+describe("detectPrompt notices", () => {
+  const conf: AgentCliConfig = {
+    enter: [/❯ 1\. Yes/m],
+    notices: [/^\s*Press Enter to dismiss/],
+  };
+  const matches = (autoDismissNotices?: boolean) =>
+    fixture("claude-whats-new.txt")
+      .map((line) => detectPrompt(line, conf, { autoDismissNotices }))
+      .filter((match) => match !== null);
+
+  it("should dismiss the what's new notice with Enter by default", () => {
+    expect(matches()).toEqual([
+      {
+        rule: "notices[0]",
+        pattern: "^\\s*Press Enter to dismiss",
+        action: "enter",
+        response: "\r",
+      },
+    ]);
+  });
+
+  it("should leave the notice to the user with --no-auto-dismiss-notices", () => {
+    expect(matches(false)).toEqual([]);
+  });
+});
+
+describe("detectPrompt type to confirm", () => {
+  const conf: AgentCliConfig = { enter: [/❯ 1\. Yes/m] };
+  const lines = fixture("type-to-confirm.txt");
+  const screen = lines.join("\n");
+  const matches = (options: DetectOptions = {}) =>
+    lines.map((line) => detectPrompt(line, conf, { screen, ...options })).filter((match) => match !== null);
+
+  it("should extract the confirmation word", () => {
+    expect(extractConfirmationWord('Type "DELETE" to confirm: ')).toBe("DELETE");
+    expect(extractConfirmationWord("type `my-app` to continue")).toBe("my-app");
+    expect(extractConfirmationWord("Type PROD and press Enter to proceed")).toBe("PROD");
+    expect(extractConfirmationWord("Type the repository name to confirm")).toBeNull();
+    expect(extractConfirmationWord("Type a message to continue the conversation")).toBeNull();
+  });
+
+  it("should leave the prompt to the user by default", () => {
+    expect(matches()).toEqual([]);
+  });
+
+  it("should type the word with autoTypeConfirmations", () => {
+    expect(matches({ autoTypeConfirmations: true })).toEqual([
+      expect.objectContaining({ rule: "typeToConfirm", action: "type", response: "DELETE", category: "delete" }),
+    ]);
+  });
+
+  it("should reject it when the category is answered no", () => {
+    expect(matches({ autoTypeConfirmations: true, answers: { delete: "no" } })).toEqual([
+      expect.objectContaining({ rule: "typeToConfirm", action: "key", response: "\u001b" }),
+    ]);
+  });
+});
+
+describe("detectPrompt session picker", () => {
+  const conf: AgentCliConfig = { enter: [/❯ 1\. Yes/m], sessionPicker: [/Modified\s+Created\s+# Messages/] };
+  const lines = fixture("claude-resume-picker.txt");
+  const screen = lines.join("\n");
+  const matches = (options: DetectOptions = {}) =>
+    lines.map((line) => detectPrompt(line, conf, { screen, ...options })).filter((match) => match !== null);
+
+  it("should leave the picker to the user by default", () => {
+    expect(matches()).toEqual([]);
+  });
+
+  it("should pick the entry once, on the last entry line", () => {
+    expect(matches({ pickSession: 2 })).toEqual([
+      expect.objectContaining({ rule: "sessionPicker", action: "key", response: "\u001b[B\r" }),
+    ]);
+    expect(matches({ pickSession: "first" }).map((m) => m.response)).toEqual(["\r"]);
+  });
+});
+
+describe("detectPrompt answers", () => {
+  const conf: AgentCliConfig = { enter: [/❯ 1\. Yes/m] };
+  const screen = `${"─".repeat(40)}\nBash command\n\n  rm -rf dist\n\nDo you want to proceed?\n❯ 1. Yes`;
+
+  it("should answer yes and report the category by default", () => {
+    const match = detectPrompt("❯ 1. Yes", conf, { screen });
+    expect(match).toMatchObject({ action: "enter", response: "\r", category: "delete" });
+  });
+
+  it("should decline with Esc when the category is answered no", () => {
+    const match = detectPrompt("❯ 1. Yes", conf, { screen, answers: { delete: "no" } });
+    expect(match).toMatchObject({ action: "key", response: "\u001b", category: "delete" });
+  });
+
+  it("should not apply answers for other categories", () => {
+    const match = detectPrompt("❯ 1. Yes", conf, { screen, answers: { network: "no" } });
+    expect(match?.action).toBe("enter");
+  });
+
+  it("should classify by the prompt's box, not by earlier output", () => {
+    const earlier = "⏺ Fetched https://example.com/docs, I'll remove the old build next";
+    const match = detectPrompt("❯ 1. Yes", conf, {
+      screen: `${earlier}\n${"─".repeat(40)}\nBash command\n\n  npm test\n\nDo you want to proceed?\n❯ 1. Yes`,
+      answers: { delete: "no", network: "no" },
+    });
+    expect(match).toMatchObject({ action: "enter", category: "shell" });
+  });
+});
+
+describe("detectPrompt custom responses", () => {
+  const conf: AgentCliConfig = {
+    enter: [/❯ 1\. Yes/m],
+    typingRespond: { "y\n": [/\[Y\] Enable indexing/], "1\n": [/Do you want to use this API key\?/] },
+  };
+
+  it("should keep the defaults", () => {
+    expect(detectPrompt("❯ 1. Yes", conf)?.response).toBe("\r");
+    expect(detectPrompt("[Y] Enable indexing", conf)?.response).toBe("y\n");
+  });
+
+  it("should send the configured menu response as raw keys", () => {
+    const match = detectPrompt("❯ 1. Yes", conf, { menuResponse: "\u001b[B\r" });
+    expect(match).toMatchObject({ action: "key", response: "\u001b[B\r" });
+  });
+
+  it("should pick the --select-label option, and fall back without one on screen", () => {
+    const screen = "Do you want to proceed?\n❯ 1. Yes\n  2. No";
+    expect(detectPrompt("❯ 1. Yes", conf, { screen, selectLabel: "No" })).toMatchObject({
+      action: "key",
+      response: "\u001b[B\r",
+    });
+    expect(detectPrompt("❯ 1. Yes", conf, { screen, selectLabel: "Always" })?.response).toBe("\r");
+    expect(detectPrompt("❯ 1. Yes", conf, { selectLabel: "No" })?.response).toBe("\r"); // no screen to parse
+  });
+
+  it("should decline everything but allow-listed categories with defaultAnswer no", () => {
+    const screen = "Do you want to make this edit to README.md?\n❯ 1. Yes";
+    expect(detectPrompt("❯ 1. Yes", conf, { screen, defaultAnswer: "no" })?.response).toBe("\u001b");
+    expect(detectPrompt("❯ 1. Yes", conf, { screen, defaultAnswer: "no", answers: { edit: "yes" } })?.response).toBe("\r");
+    expect(detectPrompt("[Y] Enable indexing", conf, { defaultAnswer: "no" })?.response).toBe("n\n");
+    expect(detectPrompt("Do you want to use this API key?", conf, { defaultAnswer: "no" })?.response).toBe("1\n"); // not y/n
+  });
+
+  it("should classify y/n prompts", () => {
+    const screen = "Delete 3 files in dist/?\n[Y] Enable indexing";
+    expect(detectPrompt("[Y] Enable indexing", conf, { screen })).toMatchObject({ response: "y\n", category: "delete" });
+    expect(detectPrompt("[Y] Enable indexing", conf, { screen, answers: { delete: "no" } })?.response).toBe("n\n");
+  });
+
+  it("should let --patterns-file patterns override defaultAnswer no", () => {
+    const withPatterns: AgentCliConfig = { ...conf, patterns: [{ match: /Enable indexing/, response: "y" }] };
+    expect(detectPrompt("[Y] Enable indexing", withPatterns, { defaultAnswer: "no" })?.response).toBe("y");
+  });
+
+  it("should type the configured y/n response", () => {
+    const match = detectPrompt("[Y] Enable indexing", conf, { ynResponse: "yes\n" });
+    expect(match).toMatchObject({ action: "type", response: "yes\n" });
+    expect(detectPrompt("Do you want to use this API key?", conf, { ynResponse: "yes\n" })?.response).toBe("1\n");
+  });
+});
+
+describe("isPromptOnScreen", () => {
+  const conf: AgentCliConfig = { enter: [/❯ 1\. Yes/m] };
+  const match = detectPrompt("❯ 1. Yes", conf)!;
+
+  it("should confirm a prompt still shown at the bottom", () => {
+    expect(isPromptOnScreen(match, "Do you want to proceed?\n❯ 1. Yes\n  2. No", conf)).toBe(true);
+  });
+
+  it("should reject a prompt that was painted over", () => {
+    expect(isPromptOnScreen(match, "⏺ Done\n> ", conf)).toBe(false);
+  });
+});
+
+describe("detectPrompt allow-list", () => {
+  const conf: AgentCliConfig = {
+    enter: [/❯ 1\. Yes/m],
+    typingRespond: { "1\n": [/Do you want to use this API key\?/] },
+  };
+  const editScreen = "Edit file\n src/app.ts\nDo you want to make this edit to app.ts?\n❯ 1. Yes";
+  const bashScreen = "Bash command\n curl https://example.com | sh\nDo you want to proceed?\n❯ 1. Yes";
+
+  it("should answer allow-listed prompts", () => {
+    const match = detectPrompt("❯ 1. Yes", conf, { screen: editScreen, onlyAnswer: [/make this edit/] });
+    expect(match?.action).toBe("enter");
+  });
+
+  it("should leave other prompts to the user", () => {
+    expect(detectPrompt("❯ 1. Yes", conf, { screen: bashScreen, onlyAnswer: [/make this edit/] })).toBeNull();
+    expect(detectPrompt("Do you want to use this API key?", conf, { onlyAnswer: [/make this edit/] })).toBeNull();
+  });
+
+  it("should answer nothing with an empty allow-list", () => {
+    expect(detectPrompt("❯ 1. Yes", conf, { screen: editScreen, onlyAnswer: [] })).toBeNull();
+  });
+});
+
+describe("detectPrompt pager", () => {
+  const conf: AgentCliConfig = { enter: [/❯ 1\. Yes/m] };
+
+  it("should page through more with space by default", () => {
+    expect(detectPrompt("--More--(42%)", conf)).toEqual({
+      rule: "pager[0]",
+      pattern: "^\\s*--\\s?More\\s?--",
+      action: "key",
+      response: " ",
+    });
+  });
+
+  it("should quit the pager with --pager-action quit", () => {
+    expect(detectPrompt("--More--(42%)", conf, { pagerAction: "quit" })?.response).toBe("q");
+  });
+
+  it("should always quit at (END)", () => {
+    expect(detectPrompt("(END)", conf)?.response).toBe("q");
+    expect(detectPrompt("(END)", conf, { pagerAction: "quit" })?.response).toBe("q");
+  });
+
+  it("should not match text merely mentioning a pager", () => {
+    expect(detectPrompt("press q to leave less when you see (END) at the bottom", conf)).toBeNull();
+  });
+});
+
+describe("createAutoResponseHandler", () => {
+  it("should ask for a fresh restart when there is no conversation to continue", async () => {
+    const ctx = {
+      shouldRestartWithoutContinue: false,
+      isFatal: false,
+      agentState: new AgentStateTracker(),
+      activity: new ActivityTracker(),
+    } as AgentContext;
+    const exitAgent = vi.fn(async () => {});
+    const conf: AgentCliConfig = { restartWithoutContinueArg: [/No conversation found to continue/] };
+
+    await createAutoResponseHandler("No conversation found to continue", 3, {
+      ctx,
+      conf,
+      cli: "claude",
+      workingDir: "/tmp",
+      exitAgent,
+    });
+
+    expect(ctx.shouldRestartWithoutContinue).toBe(true);
+    expect(exitAgent).toHaveBeenCalled();
+  });
+
+  it("should start fresh once, then exit when there is still nothing to continue", async () => {
+    const ctx = {
+      shouldRestartWithoutContinue: false,
+      isFatal: false,
+      agentState: new AgentStateTracker(),
+      activity: new ActivityTracker(),
+    } as AgentContext;
+    const noConversation = () =>
+      createAutoResponseHandler("No conversation found to continue", 0, {
+        ctx,
+        conf: { restartWithoutContinueArg: [/No conversation found to continue/] },
+        cli: "claude",
+        workingDir: "/tmp",
+        exitAgent: async () => {},
+      });
+    let fallbackFresh = true; // --continue-fallback-fresh, the default
+
+    expect(continueFallback(ctx, fallbackFresh)).toBeNull(); // exited for another reason
+
+    await noConversation();
+    expect(continueFallback(ctx, fallbackFresh)).toBe("fresh");
+    expect(ctx).toMatchObject({ shouldRestartWithoutContinue: false, isFatal: false }); // restarted, not fatal
+    fallbackFresh = false; // only once, as agentYes does
+
+    await noConversation(); // the fresh session says it again
+    expect(continueFallback(ctx, fallbackFresh)).toBe("exit");
+    expect(ctx.isFatal).toBe(true);
+  });
+
+  it("should exit instead of starting fresh with --no-continue-fallback-fresh", () => {
+    const ctx = { shouldRestartWithoutContinue: true, isFatal: true } as AgentContext;
+    expect(continueFallback(ctx, false)).toBe("exit");
+    expect(ctx).toMatchObject({ shouldRestartWithoutContinue: false, isFatal: true });
+  });
+
+  it("should pass the answer policies on to prompt detection", async () => {
+    const write = vi.fn();
+    const ctx = {
+      shell: { write },
+      events: { emit: vi.fn() },
+      agentState: new AgentStateTracker(),
+      activity: new ActivityTracker(),
+    } as unknown as AgentContext;
+    const options = { ctx, conf: {}, cli: "claude" as const, workingDir: "/tmp", exitAgent: async () => {} };
+
+    await createAutoResponseHandler("--More--(42%)", 0, { ...options, pagerAction: "quit" });
+    expect(write).toHaveBeenLastCalledWith("q");
+
+    write.mockClear();
+    await createAutoResponseHandler("--More--(42%)", 1, { ...options, onlyAnswer: [] });
+    expect(write).not.toHaveBeenCalled();
+  });
+
+  it("should detect prompt text soft-wrapped on a narrow terminal", async () => {
+    const write = vi.fn();
+    const ctx = {
+      shell: { write },
+      events: { emit: vi.fn() },
+      agentState: new AgentStateTracker(),
+      activity: new ActivityTracker(),
+    } as unknown as AgentContext;
+    const conf: AgentCliConfig = { enter: [/Do you want to proceed\?/] };
+    const screen = `${"─".repeat(16)}\nBash command\n  rm -rf dist\n\nDo you want to\nproceed?`;
+    const options = {
+      ctx,
+      conf,
+      cli: "claude" as const,
+      workingDir: "/tmp",
+      exitAgent: async () => {},
+      getScreen: () => screen,
+      answers: { delete: "no" as const }, // answered with Esc, written right away
+    };
+
+    await createAutoResponseHandler("proceed?", 4, options);
+    expect(write).not.toHaveBeenCalled(); // width unknown, the lines are taken as they are
+
+    await createAutoResponseHandler("proceed?", 4, { ...options, wrapWidth: 16 });
+    expect(write).toHaveBeenLastCalledWith("\u001b");
+    expect(isPromptOnScreen(detectPrompt("Do you want to proceed?", conf)!, screen, conf, { wrapWidth: 16 })).toBe(true);
+  });
+
+  it("should classify a prompt box taller than the bottom 12 lines by its command", async () => {
+    const write = vi.fn();
+    const ctx = {
+      shell: { write },
+      events: { emit: vi.fn() },
+      agentState: new AgentStateTracker(),
+      activity: new ActivityTracker(),
+    } as unknown as AgentContext;
+    const commandLines = "  && echo step\n".repeat(10);
+    const screen = `${"─".repeat(40)}\nBash command\n\n  rm -rf build\n${commandLines}\nDo you want to proceed?\n❯ 1. Yes\n  2. No`;
+    await createAutoResponseHandler("❯ 1. Yes", 0, {
+      ctx,
+      conf: { enter: [/❯ 1\. Yes/] },
+      cli: "claude" as const,
+      workingDir: "/tmp",
+      exitAgent: async () => {},
+      getScreen: () => screen,
+      answers: { delete: "no" as const }, // answered with Esc, written right away
+    });
+    expect(write).toHaveBeenLastCalledWith("\u001b");
+  });
+
+  it("should leave risky prompts to the user with manualConfirmation", async () => {
+    const write = vi.fn();
+    const notices: string[] = [];
+    const ctx = {
+      shell: { write },
+      events: { emit: vi.fn() },
+      agentState: new AgentStateTracker(),
+      activity: new ActivityTracker(),
+    } as unknown as AgentContext;
+    const manualConfirmation = new ManualConfirmation((text) => notices.push(text), () => {});
+    const options = {
+      ctx,
+      conf: { enter: [/Do you want to proceed\?/] },
+      cli: "claude" as const,
+      workingDir: "/tmp",
+      exitAgent: async () => {},
+      manualConfirmation,
+    };
+
+    const risky = "Bash command\n  git push --force origin main\nDo you want to proceed?";
+    await createAutoResponseHandler("Do you want to proceed?", 0, { ...options, getScreen: () => risky });
+    await createAutoResponseHandler("Do you want to proceed?", 1, { ...options, getScreen: () => risky }); // redraw
+    expect(write).not.toHaveBeenCalled();
+    expect(notices).toHaveLength(1);
+    expect(notices[0]).toContain("manual confirmation required");
+    manualConfirmation.release();
+
+    // "delete" in earlier output, above the prompt's box, doesn't make a safe prompt risky
+    const safe = `⏺ I'll delete the stale cache after the tests\n${"─".repeat(20)}\nBash command\n  npm test\nDo you want to proceed?`;
+    const answered = { ...options, getScreen: () => safe, defaultAnswer: "no" as const }; // answered with Esc, written right away
+    await createAutoResponseHandler("Do you want to proceed?", 2, answered);
+    expect(notices).toHaveLength(1);
+    expect(write).toHaveBeenLastCalledWith("\u001b");
+
+    // a long command puts the risky part more than 12 lines above the prompt line, still in its box
+    const tall = `${"─".repeat(20)}\nBash command\n  rm -rf build\n${"  && echo step\n".repeat(12)}Do you want to proceed?`;
+    await createAutoResponseHandler("Do you want to proceed?", 3, { ...options, getScreen: () => tall });
+    expect(notices).toHaveLength(2);
+    manualConfirmation.release();
+  });
+
+  it("should only record prompts with dryRun", async () => {
+    const write = vi.fn();
+    const emit = vi.fn();
+    const ctx = {
+      shell: { write },
+      events: { emit },
+      agentState: new AgentStateTracker(),
+      activity: new ActivityTracker(),
+      dryRun: true,
+    } as unknown as AgentContext;
+    const options = { ctx, conf: {}, cli: "claude" as const, workingDir: "/tmp", exitAgent: async () => {} };
+
+    await createAutoResponseHandler("--More--(42%)", 0, options);
+    expect(write).not.toHaveBeenCalled();
+    expect(emit).toHaveBeenCalledWith(expect.objectContaining({ type: "prompt", response: " ", dryRun: true }));
+  });
+
+  it("should ask the confirm gate before typing y, and type n when refused", async () => {
+    const write = vi.fn();
+    const ctx = {
+      shell: { write },
+      events: { emit: vi.fn() },
+      agentState: new AgentStateTracker(),
+      activity: new ActivityTracker(),
+      idleWaiter: { ping: vi.fn() },
+      messageContext: { shell: { write }, idleWaiter: { ping: vi.fn() }, nextStdout: { unready: vi.fn() }, inputGating: false },
+    } as unknown as AgentContext;
+    const confirmGate = { guards: (category?: string) => category === "delete", confirm: vi.fn(async () => "no") };
+    const conf: AgentCliConfig = { typingRespond: { "y\n": [/Delete them\? \[y\/n\]/] } };
+
+    const match = detectPrompt("Delete them? [y/n]", conf, { screen: "rm -rf dist\nDelete them? [y/n]" })!;
+    await respondToPrompt(match, "Delete them? [y/n]", { ctx, confirmGate: confirmGate as unknown as ConfirmGate });
+    expect(confirmGate.confirm).toHaveBeenCalledWith("delete", "Delete them? [y/n]");
+    expect(write).toHaveBeenCalledWith("n\n");
+  });
+
+  it("should record the keys sent to --cast-file", async () => {
+    const input = vi.fn();
+    const ctx = {
+      shell: { write: vi.fn() },
+      events: { emit: vi.fn() },
+      agentState: new AgentStateTracker(),
+      activity: new ActivityTracker(),
+      cast: { input },
+    } as unknown as AgentContext;
+    const options = { ctx, conf: {}, cli: "claude" as const, workingDir: "/tmp", exitAgent: async () => {} };
+
+    await createAutoResponseHandler("--More--(42%)", 0, options);
+    expect(input).toHaveBeenCalledWith(" ");
+
+    input.mockClear();
+    ctx.dryRun = true; // nothing sent, nothing recorded
+    await createAutoResponseHandler("--More--(42%)", 1, options);
+    expect(input).not.toHaveBeenCalled();
+  });
+
+  it("should not answer prompt text redrawn from scrollback with maxPromptAge", async () => {
+    const write = vi.fn();
+    const ctx = {
+      shell: { write },
+      events: { emit: vi.fn() },
+      agentState: new AgentStateTracker(),
+      activity: new ActivityTracker(),
+    } as unknown as AgentContext;
+    const options = {
+      ctx,
+      conf: {},
+      cli: "claude" as const,
+      workingDir: "/tmp",
+      exitAgent: async () => {},
+      maxPromptAge: 10,
+    };
+    const output = Array.from({ length: 30 }, (_, i) => `line ${i}`);
+
+    const scrollback = ["--More--(42%)", ...output, "> "].join("\n");
+    await createAutoResponseHandler("--More--(42%)", 0, { ...options, getScreen: () => scrollback });
+    expect(write).not.toHaveBeenCalled();
+
+    const fresh = [...output, "--More--(42%)", "", ""].join("\n");
+    await createAutoResponseHandler("--More--(42%)", 1, { ...options, getScreen: () => fresh });
+    expect(write).toHaveBeenLastCalledWith(" ");
+  });
+});
@@ -1,10 +1,26 @@
-import sflow from "sflow";
 import { logger } from "../logger.ts";
+import { sleepms } from "../utils.ts";
 import { sendEnter, sendMessage } from "./messaging.ts";
 import type { AgentContext } from "./context.ts";
 import type { AgentCliConfig } from "../index.ts";
 import type { SUPPORTED_CLIS } from "../SUPPORTED_CLIS.ts";
 import { extractSessionId, storeSessionForCwd } from "../resume/codexSessionManager.ts";
+import { extractClaudeSessionId, storeClaudeSessionForCwd } from "../resume/claudeSessionManager.ts";
+import {
+  classifyPrompt,
+  resolveAnswer,
+  type Answer,
+  type AnswerMap,
+  type PromptCategory,
+} from "./promptCategories.ts";
+import type { PromptRecorder } from "./promptRecorder.ts";
+import type { PromptDebouncer } from "./promptDebouncer.ts";
+import type { ConfirmGate } from "./confirmGate.ts";
+import { parseSessionPicker, pickSessionKeys, type PickSessionPolicy } from "./sessionPicker.ts";
+import { selectLabelKeys } from "./menuSelect.ts";
+import { dewrapLines, logicalLineEndingWith } from "./dewrap.ts";
+import { RATE_LIMIT_BUFFER_MS, type RateLimitWatcher } from "./rateLimit.ts";
+import { isRisky, promptBox, type ManualConfirmation } from "./riskyPrompts.ts";
 
 /**
  * Auto-response handlers for CLI-specific patterns
@@ -14,12 +30,316 @@ import { extractSessionId, storeSessionForCwd } from "../resume/codexSessionMana
  * signals, fatal errors, enter key automation, and session management.
  */
 
-export interface AutoResponderOptions {
+/**
+ * A matched auto-response rule
+ *
+ * `rule` identifies which configured pattern fired, so false positives can
+ * be traced back to the exact regex, e.g. `enter[3]` is `conf.enter[3]`.
+ */
+export interface PromptMatch {
+  rule: string;
+  pattern: string;
+  action: "enter" | "type" | "key"; // key: write the response as-is, without a trailing Enter
+  response: string;
+  category?: PromptCategory;
+}
+
+/**
+ * How to answer MCP server trust prompts
+ * - `no`: continue without the server (default, external servers can run arbitrary code)
+ * - `yes`: trust the server
+ * - `ask`: leave the prompt for the user
+ */
+export type TrustMcpPolicy = "yes" | "no" | "ask";
+
+/**
+ * How to get past pager prompts of commands the agent runs, e.g. `git log` in less
+ * - `space`: show the next page (default), `(END)` is still quit
+ * - `quit`: quit the pager right away
+ */
+export type PagerAction = "space" | "quit";
+
+/** Pager prompts, the same for every cli since they come from its subprocesses */
+export const PAGER_PATTERNS: { pattern: RegExp; atEnd: boolean }[] = [
+  { pattern: /^\s*--\s?More\s?--/, atEnd: false }, // more
+  { pattern: /^\s*\(END\)\s*$/, atEnd: true }, // less, last page
+];
+
+/**
+ * "Type X to confirm" prompts, e.g. `Type "DELETE" to confirm:`; the word is
+ * quoted, or all caps so "type the repo name to confirm" doesn't count
+ */
+export const TYPE_TO_CONFIRM =
+  /\b[Tt]ype\s+(?:(["'`])([^"'`\s]+)\1|([A-Z][A-Z0-9_-]*[A-Z0-9]))\s+(?:and press [Ee]nter\s+)?to\s+(?:confirm|continue|proceed)\b/;
+
+/**
+ * Get the word a "type X to confirm" prompt asks for
+ *
+ * @example
+ * ```typescript
+ * extractConfirmationWord('Type "DELETE" to confirm:'); // "DELETE"
+ * extractConfirmationWord("Type the repository name to confirm:"); // null
+ * ```
+ */
+export function extractConfirmationWord(line: string): string | null {
+  const match = line.match(TYPE_TO_CONFIRM);
+  return match ? (match[2] ?? match[3] ?? null) : null;
+}
+
+export interface DetectOptions {
+  trustMcp?: TrustMcpPolicy;
+  autoDismissNotices?: boolean; // press Enter on update/changelog notices (conf.notices), true by default
+  autoTypeConfirmations?: boolean; // type the word of "type X to confirm" prompts, false by default: they are high-friction on purpose
+  answers?: AnswerMap; // per-category answers for enter prompts, see promptCategories.ts
+  defaultAnswer?: Answer; // for prompts without a category answer, "no" declines menus and answers y/n prompts "n"
+  screen?: string; // rendered text around the prompt, used to classify it
+  menuResponse?: string; // sent to accept enter prompts, "\r" (Enter) by default
+  selectLabel?: string; // pick the menu option with this label on the screen instead, see menuSelect.ts
+  ynResponse?: string; // typed instead of the "y\n" typingRespond answer, for prompts wanting e.g. "yes\n"
+  onlyAnswer?: RegExp[]; // allow-list: only answer prompts whose screen matches one of these, [] answers nothing
+  pagerAction?: PagerAction;
+  pickSession?: PickSessionPolicy; // entry to pick in a --resume session picker (conf.sessionPicker), left to the user when unset
+  wrapWidth?: number; // terminal columns, to also match prompt text soft-wrapped across lines, see dewrap.ts
+}
+
+/**
+ * Find the auto-response rule matching an output line, if any
+ *
+ * User patterns from `--patterns-file` are checked first, in file order.
+ * MCP trust prompts come next and are answered per `trustMcp` policy,
+ * then pager prompts per `pagerAction`, then update/changelog notices
+ * (dismissed with Enter unless `autoDismissNotices` is false), then the
+ * `--resume` session picker (answered per `pickSession`), then "type
+ * X to confirm" prompts (left to the user unless `autoTypeConfirmations`),
+ * then enter patterns, then typingRespond patterns, in key order. The
+ * first matching regex wins and is the only one answered, so a prompt
+ * never gets two responses; its rule id is what the prompt event reports,
+ * patterns further down that would also match are ignored. Enter prompts are classified by the `screen` text, and
+ * declined with Esc when `answers` says no for their category, or
+ * `defaultAnswer` is no without one; such y/n prompts get `n\n`. Accepted
+ * enter prompts pick the `selectLabel` option when it's on the screen,
+ * else get `menuResponse`, and `y\n` answers become `ynResponse`.
+ * With an `onlyAnswer` allow-list, prompts whose screen (or line, without
+ * a screen) matches none of its regexes are left for the user.
+ *
+ * @param line - Output line to analyze
+ * @param conf - CLI configuration holding the patterns
+ * @param options - Answer policies
+ * @returns The matched rule, or null when no auto-response applies
+ *
+ * @example
+ * ```typescript
+ * detectPrompt("❯ 1. Yes", { enter: [/❯ 1\. Yes/] });
+ * // { rule: "enter[0]", pattern: "❯ 1\\. Yes", action: "enter", response: "\r" }
+ * ```
+ */
+export function detectPrompt(
+  line: string,
+  conf: AgentCliConfig,
+  {
+    trustMcp = "no",
+    autoDismissNotices = true,
+    autoTypeConfirmations = false,
+    answers = {},
+    defaultAnswer = "yes",
+    screen,
+    menuResponse = "\r",
+    selectLabel,
+    ynResponse = "y\n",
+    onlyAnswer,
+    pagerAction = "space",
+    pickSession,
+  }: DetectOptions = {},
+): PromptMatch | null {
+  if (onlyAnswer && !onlyAnswer.some((rx) => rx.test(screen ?? line))) return null; // not allow-listed
+  const userIndex = conf.patterns?.findIndex(({ match }) => line.match(match)) ?? -1;
+  if (userIndex >= 0) {
+    const { match, response } = conf.patterns![userIndex]!; // --patterns-file, before the built-ins
+    const action = response === "\r" ? "enter" : "type";
+    return { rule: `patterns[${userIndex}]`, pattern: match.source, action, response };
+  }
+  const mcpIndex = conf.mcpTrust?.detect.findIndex((rx: RegExp) => line.match(rx)) ?? -1;
+  if (mcpIndex >= 0) {
+    if (trustMcp === "ask") return null; // leave it to the user
+    const rx = conf.mcpTrust!.detect[mcpIndex]!;
+    const response = conf.mcpTrust![trustMcp];
+    return { rule: `mcpTrust[${mcpIndex}]`, pattern: rx.source, action: "type", response };
+  }
+
+  const pagerIndex = PAGER_PATTERNS.findIndex(({ pattern }) => pattern.test(line));
+  if (pagerIndex >= 0) {
+    const { pattern, atEnd } = PAGER_PATTERNS[pagerIndex]!;
+    const response = atEnd || pagerAction === "quit" ? "q" : " ";
+    return { rule: `pager[${pagerIndex}]`, pattern: pattern.source, action: "key", response };
+  }
+
+  const noticeIndex = conf.notices?.findIndex((rx: RegExp) => line.match(rx)) ?? -1;
+  if (noticeIndex >= 0) {
+    if (!autoDismissNotices) return null; // leave it to the user
+    const rx = conf.notices![noticeIndex]!;
+    return { rule: `notices[${noticeIndex}]`, pattern: rx.source, action: "enter", response: "\r" };
+  }
+
+  // answer the session picker once, on its last entry line, when the whole list is on screen
+  const pickerEntries = conf.sessionPicker && screen ? parseSessionPicker(screen, conf.sessionPicker) : null;
+  const lineEntry = line.match(/^\s*(?:❯\s*)?(\d+)\.\s/)?.[1];
+  if (pickerEntries?.length && lineEntry && Number(lineEntry) === pickerEntries.at(-1)!.index) {
+    const keys = pickSession !== undefined ? pickSessionKeys(pickerEntries, pickSession) : null;
+    if (!keys) return null; // leave it to the user
+    const pattern = conf.sessionPicker!.map((rx) => rx.source).join("|");
+    return { rule: "sessionPicker", pattern, action: "key", response: keys };
+  }
+
+  const confirmationWord = extractConfirmationWord(line);
+  if (confirmationWord) {
+    if (!autoTypeConfirmations) return null; // leave it to the user
+    const category = screen ? classifyPrompt(screen, line) : undefined;
+    const answer = resolveAnswer(category, answers, defaultAnswer);
+    const pattern = TYPE_TO_CONFIRM.source;
+    if (answer === "no") return { rule: "typeToConfirm", pattern, action: "key", response: "\u001b", category }; // Esc to reject
+    return { rule: "typeToConfirm", pattern, action: "type", response: confirmationWord, category };
+  }
+
+  const enterIndex = conf.enter?.findIndex((rx: RegExp) => line.match(rx)) ?? -1;
+  if (enterIndex >= 0) {
+    const rx = conf.enter![enterIndex]!;
+    const rule = `enter[${enterIndex}]`;
+    const category = screen ? classifyPrompt(screen, line) : undefined;
+    const answer = resolveAnswer(category, answers, defaultAnswer);
+    if (answer === "no")
+      return { rule, pattern: rx.source, action: "key", response: "\u001b", category }; // Esc to reject
+    const labelKeys = selectLabel && screen ? selectLabelKeys(screen, selectLabel) : null;
+    if (labelKeys) return { rule, pattern: rx.source, action: "key", response: labelKeys, category }; // --select-label
+    if (menuResponse !== "\r")
+      return { rule, pattern: rx.source, action: "key", response: menuResponse, category }; // custom keys, e.g. arrows + Enter
+    return { rule, pattern: rx.source, action: "enter", response: "\r", category };
+  }
+
+  for (const [sendString, onThePatterns] of Object.entries(conf.typingRespond ?? {})) {
+    const index = onThePatterns.findIndex((rx) => line.match(rx));
+    if (index < 0) continue;
+    const yn = sendString === "y\n";
+    const category = yn && screen ? classifyPrompt(screen, line) : undefined;
+    const declined = yn && resolveAnswer(category, answers, defaultAnswer) === "no";
+    return {
+      rule: `typingRespond[${JSON.stringify(sendString)}][${index}]`,
+      pattern: onThePatterns[index]!.source,
+      action: "type",
+      response: declined ? "n\n" : yn ? ynResponse : sendString,
+      category,
+    };
+  }
+
+  return null;
+}
+
+/**
+ * What to do once the agent exited after "No conversation found to continue"
+ *
+ * The first time, with `--continue-fallback-fresh` (the default), the agent
+ * is restarted once without the continue args; otherwise the session ends
+ * as fatal, so there is no restart loop on a missing conversation.
+ *
+ * @param fallbackFresh - Whether a fresh start is still allowed, false after the first one or with `--no-continue-fallback-fresh`
+ * @returns "fresh" to restart without the continue args, "exit" to end the session, or null when the agent exited for another reason
+ */
+export function continueFallback(
+  ctx: Pick<AgentContext, "shouldRestartWithoutContinue" | "isFatal">,
+  fallbackFresh: boolean,
+): "fresh" | "exit" | null {
+  if (!ctx.shouldRestartWithoutContinue) return null;
+  ctx.shouldRestartWithoutContinue = false; // handled
+  if (!fallbackFresh) return "exit"; // still fatal
+  ctx.isFatal = false; // restart instead of exiting
+  return "fresh";
+}
+
+/**
+ * Check that a matched prompt is still shown on the settled screen
+ *
+ * Used after the render-stability debounce, see promptDebouncer.ts: a rule
+ * that no longer matches any of the bottom screen lines was a mid-paint frame.
+ *
+ * @param match - The prompt matched earlier on a streamed line
+ * @param screen - Rendered screen text
+ * @param window - How many lines from the bottom of the screen to look at
+ */
+export function isPromptOnScreen(
+  match: PromptMatch,
+  screen: string,
+  conf: AgentCliConfig,
+  options: DetectOptions = {},
+  window = 12,
+): boolean {
+  const bottom = screen.split("\n").slice(-window); // the prompt box is at the bottom
+  const detect = { ...options, screen: bottom.join("\n") };
+  const lines = options.wrapWidth ? [...bottom, ...dewrapLines(bottom, options.wrapWidth)] : bottom;
+  return lines.some((line) => detectPrompt(line, conf, detect)?.rule === match.rule);
+}
+
+/**
+ * Answer a detected prompt: log it, emit the event, and send the response
+ *
+ * With a `confirmGate`, accepting a dangerous prompt is up to the user the
+ * first time per category, see confirmGate.ts. With `--dry-run` the prompt
+ * is only logged and recorded, the user answers it.
+ */
+export async function respondToPrompt(
+  match: PromptMatch,
+  line: string,
+  { ctx, promptRecorder, confirmGate }: Pick<AutoResponderOptions, "ctx" | "promptRecorder" | "confirmGate">,
+) {
+  if (ctx.dryRun) {
+    logger.info(`[dry-run] would ${match.action} ${JSON.stringify(match.response)} |${match.rule}|${line}`);
+    ctx.events.emit({ type: "prompt", ...match, line, dryRun: true });
+    return void (await promptRecorder?.record(match).catch(() => null));
+  }
+  const yn = match.rule.startsWith('typingRespond["y\\n"]'); // rejected by typing n, not Esc
+  const declining = match.response === "\u001b" || (yn && match.response === "n\n");
+  if (confirmGate?.guards(match.category) && !declining) {
+    const keepAlive = setInterval(() => ctx.idleWaiter.ping(), 1000); // waiting for the user isn't idling
+    const answer = await confirmGate.confirm(match.category, line).finally(() => clearInterval(keepAlive));
+    if (answer === "no") match = yn ? { ...match, response: "n\n" } : { ...match, action: "key", response: "\u001b" }; // Esc to reject
+  }
+  const jitter = ctx.nextAnswerJitter?.() ?? 0;
+  if (jitter) await sleepms(jitter); // --answer-delay-jitter
+  logger.debug(`${match.action} |${match.rule}|${match.category ?? "-"}|${line}`);
+  ctx.events.emit({ type: "prompt", ...match, line });
+  await promptRecorder?.record(match).catch(() => null);
+  ctx.cast?.input(match.action === "enter" ? "\r" : match.action === "key" ? match.response : `${match.response}\r`);
+  if (match.action === "enter") return await sendEnter(ctx.messageContext, ctx.responseDelay ?? 400); // wait for idle for a short while (--response-delay) and then send Enter
+  if (match.action === "key") return ctx.shell.write(match.response);
+  return await sendMessage(ctx.messageContext, match.response, { waitForReady: false });
+}
+
+/**
+ * Sleep until a rate limit resets, then tell the agent to continue
+ *
+ * Pings the idle waiter throughout, waiting out a limit isn't idling.
+ */
+export async function waitForRateLimitReset(resetAt: Date, ctx: AgentContext, now = Date.now()) {
+  const waitMs = Math.max(0, resetAt.getTime() - now) + RATE_LIMIT_BUFFER_MS;
+  logger.warn(`[agent-yes] rate limited until ${resetAt.toISOString()}, continuing in ${Math.ceil(waitMs / 60000)} min`);
+  ctx.events.emit({ type: "rate_limit", resetAt: resetAt.toISOString(), waitMs });
+  const keepAlive = setInterval(() => ctx.idleWaiter.ping(), 1000);
+  await sleepms(waitMs).finally(() => clearInterval(keepAlive));
+  logger.info(`[agent-yes] rate limit reset, continuing`);
+  await sendMessage(ctx.messageContext, "continue");
+}
+
+export interface AutoResponderOptions extends Omit<DetectOptions, "screen"> {
   ctx: AgentContext;
   conf: AgentCliConfig;
   cli: SUPPORTED_CLIS;
   workingDir: string;
   exitAgent: () => Promise<void>;
+  getScreen?: () => string; // rendered terminal text, used to classify prompts
+  promptRecorder?: PromptRecorder; // saves a fixture for each detected prompt
+  promptDebouncer?: PromptDebouncer<{ match: PromptMatch; line: string }>; // defer answers until the screen settles
+  confirmGate?: ConfirmGate; // ask the user before accepting dangerous prompts
+  maxPromptAge?: number; // only answer prompts within this many lines of the bottom of the screen, 0 for no limit
+  rateLimitWatcher?: RateLimitWatcher; // --wait-on-rate-limit: wait for the reset instead of exiting
+  manualConfirmation?: ManualConfirmation; // --confirm-destructive: leave risky prompts to the user
 }
 
 /**
@@ -57,32 +377,71 @@ export async function createAutoResponseHandler(
   lineIndex: number,
   options: AutoResponderOptions,
 ) {
-  const { ctx, conf, cli, workingDir, exitAgent } = options;
+  const {
+    ctx,
+    conf,
+    cli,
+    workingDir,
+    exitAgent,
+    getScreen,
+    promptDebouncer,
+    promptRecorder,
+    confirmGate,
+    maxPromptAge,
+    rateLimitWatcher,
+    manualConfirmation,
+    ...detectOptions
+  } = options;
 
   logger.debug(`stdout|${line}`);
+  const rendered = getScreen?.();
+  // the prompt box is at the bottom: the last 12 lines, more when the box and its top edge are taller
+  const boxHeight = rendered ? promptBox(rendered, line).split("\n").length + 1 : 0;
+  const screen = rendered?.split("\n").slice(-Math.max(12, boxHeight)).join("\n");
 
   // ready matcher: if matched, mark stdin ready
-  if (conf.ready?.some((rx: RegExp) => line.match(rx))) {
+  const isReadyLine = conf.ready?.some((rx: RegExp) => line.match(rx)) ?? false;
+  if (isReadyLine) {
     logger.debug(`ready |${line}`);
     if (cli === "gemini" && lineIndex <= 80) return; // gemini initial noise, only after many lines
+    if (!ctx.stdinReady.isReady) ctx.events.emit({ type: "ready" }); // once per agent start
     ctx.stdinReady.ready();
     ctx.stdinFirstReady.ready();
   }
+  ctx.agentState.observe(isReadyLine, screen ?? line); // busy or awaiting input, see agentState.ts
+  const activity = ctx.activity.observe(line); // thinking, running a tool, ..., see activity.ts
+  if (activity) ctx.events.emit({ type: "activity", ...activity });
 
-  // enter matchers: send Enter when any enter regex matches
-  if (conf.enter?.some((rx: RegExp) => line.match(rx))) {
-    logger.debug(`enter |${line}`);
-    return await sendEnter(ctx.messageContext, 400); // wait for idle for a short while and then send Enter
+  // enter & typingRespond matchers: send Enter or the configured message
+  let match = detectPrompt(line, conf, { ...detectOptions, screen });
+  if (!match && detectOptions.wrapWidth && getScreen) {
+    // on narrow terminals the prompt text may be wrapped, e.g. "Do you want to" + "proceed?"
+    const logicalLine = logicalLineEndingWith(getScreen().split("\n").slice(-12), line, detectOptions.wrapWidth);
+    if (logicalLine) match = detectPrompt(logicalLine, conf, { ...detectOptions, screen });
+    if (match) logger.debug(`dewrap|${logicalLine}`);
+  }
+  // --max-prompt-age: a prompt redrawn from scrollback (e.g. a full repaint on resize) is not a new prompt
+  const stale =
+    match && maxPromptAge && getScreen && !isPromptOnScreen(match, getScreen().trimEnd(), conf, detectOptions, maxPromptAge);
+  if (stale) logger.debug(`stale |${match.rule}|not within the last ${maxPromptAge} lines|${line}`);
+  if (match && !stale) {
+    // --confirm-destructive: the user answers risky prompts on stdin, as without agent-yes
+    if (manualConfirmation && isRisky(rendered ? promptBox(rendered, line) : line)) {
+      if (manualConfirmation.hold(line)) logger.debug(`manual|${match.rule}|${line}`);
+      return;
+    }
+    if (promptDebouncer) return promptDebouncer.propose({ match, line }); // answered once the screen settles
+    return await respondToPrompt(match, line, { ctx, promptRecorder, confirmGate });
   }
 
-  // typingRespond matcher: if matched, send the specified message
-  const typingResponded = await sflow(Object.entries(conf.typingRespond ?? {}))
-    .filter(([_sendString, onThePatterns]) => onThePatterns.some((rx) => line.match(rx)))
-    .map(
-      async ([sendString]) => await sendMessage(ctx.messageContext, sendString, { waitForReady: false }),
-    )
-    .toCount();
-  if (typingResponded) return;
+  // rate-limit matchers: wait for the reset rather than exiting on the usage limit
+  const resetAt = rateLimitWatcher?.check(line);
+  if (resetAt === "seen") return; // redrawn while waiting or after continuing
+  if (resetAt) {
+    // don't hold up the output stream for hours, the screen keeps rendering while waiting
+    waitForRateLimitReset(resetAt, ctx).catch((error) => logger.error(`rate limit wait failed: ${error}`));
+    return;
+  }
 
   // fatal matchers: set isFatal flag when matched
   if (conf.fatal?.some((rx: RegExp) => line.match(rx))) {
@@ -107,4 +466,13 @@ export async function createAutoResponseHandler(
       await storeSessionForCwd(workingDir, sessionId);
     }
   }
+
+  // session ID capture for claude, resumed per directory, see claudeSessionManager.ts
+  if (cli === "claude") {
+    const sessionId = extractClaudeSessionId(line);
+    if (sessionId) {
+      logger.debug(`session|captured claude session ID: ${sessionId}`);
+      await storeClaudeSessionForCwd(workingDir, sessionId);
+    }
+  }
 }
@@ -0,0 +1,28 @@
+import { describe, expect, it } from "vitest";
+import { MAX_RESTART_BACKOFF, RestartPolicy } from "./restartPolicy";
+
+describe("RestartPolicy", () => {
+  it("should restart forever without delay by default", () => {
+    const policy = new RestartPolicy();
+    for (let i = 1; i <= 100; i++) expect(policy.next()).toEqual({ attempt: i, delayMs: 0 });
+  });
+
+  it("should give up after the cap", () => {
+    const policy = new RestartPolicy(2);
+    expect(policy.next()?.attempt).toBe(1);
+    expect(policy.next()?.attempt).toBe(2);
+    expect(policy.next()).toBeNull();
+    expect(policy.next()).toBeNull();
+  });
+
+  it("should not restart at all with a cap of 0", () => {
+    expect(new RestartPolicy(0).next()).toBeNull();
+  });
+
+  it("should back off exponentially up to the max", () => {
+    const policy = new RestartPolicy(Infinity, 1000);
+    expect([1, 2, 3, 4].map(() => policy.next()?.delayMs)).toEqual([1000, 2000, 4000, 8000]);
+    for (let i = 0; i < 20; i++) policy.next();
+    expect(policy.next()?.delayMs).toBe(MAX_RESTART_BACKOFF);
+  });
+});
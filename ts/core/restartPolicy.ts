@@ -0,0 +1,48 @@
+/**
+ * Limits for restarting a crashed agent, behind `--max-restarts` and `--restart-backoff`
+ *
+ * A deterministic crash (a segfault on startup, a broken install) would
+ * otherwise respawn the agent forever. Crashes are counted over the whole
+ * session; each restart waits `backoffMs` doubled per attempt (capped at
+ * MAX_RESTART_BACKOFF), and after `maxRestarts` restarts agent-yes gives up
+ * and exits with the agent's exit code. A clean exit ends the session, so
+ * it needs no reset.
+ */
+
+/** Longest wait between restarts, however many attempts */
+export const MAX_RESTART_BACKOFF = 5 * 60 * 1000;
+
+export interface RestartAttempt {
+  attempt: number; // 1 for the first restart
+  delayMs: number; // wait before respawning
+}
+
+export class RestartPolicy {
+  attempts = 0;
+
+  /**
+   * @param maxRestarts - Restarts allowed per session, Infinity for no limit
+   * @param backoffMs - Wait before the first restart, doubled for each further one, 0 restarts right away
+   */
+  constructor(
+    readonly maxRestarts = Infinity,
+    readonly backoffMs = 0,
+  ) {}
+
+  /**
+   * Count a crash
+   * @returns The restart to do, or null when out of restarts
+   *
+   * @example
+   * ```typescript
+   * const policy = new RestartPolicy(3, 1000);
+   * policy.next(); // { attempt: 1, delayMs: 1000 }
+   * policy.next(); // { attempt: 2, delayMs: 2000 }
+   * ```
+   */
+  next(): RestartAttempt | null {
+    if (this.attempts >= this.maxRestarts) return null;
+    const attempt = ++this.attempts;
+    return { attempt, delayMs: Math.min(this.backoffMs * 2 ** (attempt - 1), MAX_RESTART_BACKOFF) };
+  }
+}
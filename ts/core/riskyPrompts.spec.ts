@@ -0,0 +1,86 @@
+import { afterEach, beforeEach, describe, expect, it, vi } from "vitest";
+import { isRisky, ManualConfirmation, promptBox } from "./riskyPrompts";
+
+describe("isRisky", () => {
+  it.each([
+    "Bash command\n  rm -rf dist\nDo you want to proceed?",
+    "Bash command\n  rm -v -Rf node_modules",
+    "Bash command\n  git push --force origin main",
+    "Bash command\n  git push -f",
+    "Force push to main?",
+    "Bash command\n  git reset --hard HEAD~3",
+    "Bash command\n  git clean -fd",
+    "Delete file src/old.ts?",
+    "Overwrite existing file?",
+    "psql -c 'DROP TABLE users'",
+  ])("should flag %j", (text) => {
+    expect(isRisky(text)).toBe(true);
+  });
+
+  it.each([
+    "Bash command\n  npm test\nDo you want to proceed?",
+    "Bash command\n  rm dist/cache.json",
+    "Bash command\n  git push origin feature-f",
+    "Edit file src/index.ts\nDo you want to make this edit?",
+    "git reset HEAD file.ts",
+  ])("should not flag %j", (text) => {
+    expect(isRisky(text)).toBe(false);
+  });
+});
+
+describe("promptBox", () => {
+  it("should take the lines after the last rule above the prompt, and its options", () => {
+    const screen = [
+      "⏺ Deleted the old fixtures",
+      "─".repeat(40),
+      " Bash command",
+      "",
+      "   npm test",
+      "",
+      " Do you want to proceed?",
+      " ❯ 1. Yes",
+      "   2. No",
+    ].join("\n");
+    const box = promptBox(screen, " Do you want to proceed?");
+    expect(box.split("\n")[0]).toBe(" Bash command");
+    expect(box).toContain("2. No");
+    expect(isRisky(box)).toBe(false);
+  });
+
+  it("should stop at a previous box's bottom edge", () => {
+    const screen = "│ rm -rf dist │\n╰──────────────╯\n│ Edit file a.ts │\n│ Do you want to make this edit? │";
+    expect(promptBox(screen, "Do you want to make this edit?")).toBe("│ Edit file a.ts │\n│ Do you want to make this edit? │");
+  });
+
+  it("should take the non-blank lines ending at the prompt without a box edge", () => {
+    const screen = "Deleted 3 files\n\nBash command\n  git push --force origin main\nDo you want to proceed?";
+    expect(promptBox(screen, "Do you want to proceed?")).toBe("Bash command\n  git push --force origin main\nDo you want to proceed?");
+    expect(promptBox("a\nb", "not on screen")).toBe("a\nb");
+  });
+});
+
+describe("ManualConfirmation", () => {
+  beforeEach(() => vi.useFakeTimers());
+  afterEach(() => vi.useRealTimers());
+
+  it("should show the notice once per prompt and hold the idle timer until released", async () => {
+    const write = vi.fn();
+    const ping = vi.fn();
+    const manual = new ManualConfirmation(write, ping);
+
+    expect(manual.hold("❯ 1. Yes ")).toBe(true);
+    expect(manual.hold("❯ 1. Yes")).toBe(false); // redraw
+    expect(write).toHaveBeenCalledTimes(1);
+    expect(manual.waiting).toBe(true);
+
+    await vi.advanceTimersByTimeAsync(3000);
+    expect(ping).toHaveBeenCalledTimes(3);
+
+    manual.release();
+    expect(manual.waiting).toBe(false);
+    await vi.advanceTimersByTimeAsync(3000);
+    expect(ping).toHaveBeenCalledTimes(3);
+    expect(manual.hold("❯ 1. Yes")).toBe(true); // the next risky prompt is asked again
+    manual.release();
+  });
+});
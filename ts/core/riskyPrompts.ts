@@ -0,0 +1,109 @@
+/**
+ * `--confirm-destructive`: leave high-risk prompts to the user
+ *
+ * Prompts whose screen mentions a destructive action (deleting files, a
+ * force push, a hard reset, ...) are not answered at all. agent-yes prints
+ * a highlighted notice and the user answers the agent's own prompt through
+ * stdin as usual; the idle timer is held until they type something, so the
+ * session doesn't exit on idle while the question is open.
+ *
+ * Unlike `--confirm-dangerous-once` (confirmGate.ts) nothing is remembered:
+ * every risky prompt is answered by hand. Only the prompt's own box is
+ * checked, see promptBox, so "delete" in earlier output doesn't hold back
+ * an unrelated prompt.
+ */
+
+export const RISKY_PATTERNS: RegExp[] = [
+  /\bdelete\b/i,
+  /\brm\s+(-\w+\s+)*-\w*(rf|fr)\w*\b/i, // rm -rf, rm -fr, rm -v -Rf
+  /\bforce[ -]push\b|\bpush\s+(.*\s)?(--force(-with-lease)?|-f)\b/i,
+  /\boverwrite\b/i,
+  /\breset\s+--hard\b/i,
+  /\bclean\s+-\w*f/i, // git clean -fd
+  /\bdrop\s+(table|database|schema)\b/i,
+];
+
+/**
+ * Whether the prompt text asks for a destructive action
+ *
+ * @param cleanText - Prompt text with control characters removed, e.g. the rendered screen around it
+ *
+ * @example
+ * ```typescript
+ * isRisky("Bash command\n  git reset --hard HEAD~3\nDo you want to proceed?"); // true
+ * isRisky("Bash command\n  npm test\nDo you want to proceed?"); // false
+ * ```
+ */
+export function isRisky(cleanText: string): boolean {
+  return RISKY_PATTERNS.some((rx) => rx.test(cleanText));
+}
+
+// a box edge or horizontal rule, e.g. "╭────╮", "╰────╯" or the "─────" line above claude's permission prompts
+const BOX_EDGE = /^\s*[╭╰┌└]?[─━═╌]{3,}[╮╯┐┘]?\s*$/;
+
+/**
+ * The prompt's box on screen: the lines around the prompt line, after the last box edge above it
+ *
+ * Without a box edge, the block of non-blank lines ending at the prompt line
+ * is taken. The lines below the prompt line (e.g. its menu options) are kept.
+ *
+ * @param screen - The whole rendered screen, a box can be taller than the bottom lines
+ * @param line - The matched prompt line, the last line of the screen when not found
+ *
+ * @example
+ * ```typescript
+ * promptBox("rm -rf old\n─────\n Bash command\n   npm test\n Do you want to proceed?", "Do you want to proceed?");
+ * // " Bash command\n   npm test\n Do you want to proceed?"
+ * ```
+ */
+export function promptBox(screen: string, line: string): string {
+  const lines = screen.split("\n");
+  const wanted = line.trim();
+  const found = wanted ? lines.findLastIndex((l) => l.includes(wanted)) : -1;
+  const at = found >= 0 ? found : lines.length - 1;
+  const edge = lines.slice(0, at + 1).findLastIndex((l) => BOX_EDGE.test(l));
+  const start = edge >= 0 ? edge + 1 : lines.slice(0, at + 1).findLastIndex((l) => !l.trim()) + 1;
+  return lines.slice(start).join("\n");
+}
+
+export const MANUAL_CONFIRMATION_NOTICE = "\u001b[1;33m⚠ manual confirmation required\u001b[0m";
+
+/**
+ * Tracks the risky prompt waiting for the user
+ */
+export class ManualConfirmation {
+  private pending: string | null = null;
+  private keepAlive?: ReturnType<typeof setInterval>;
+
+  /**
+   * @param write - Shows the notice, e.g. on stderr so it doesn't mix into the agent's screen
+   * @param ping - Marks activity, holding the idle timer while the prompt is open
+   */
+  constructor(
+    private write: (text: string) => void,
+    private ping: () => void,
+  ) {}
+
+  get waiting() {
+    return this.pending !== null;
+  }
+
+  /**
+   * Hold a risky prompt for the user, the notice is shown once per prompt
+   * @returns true when the prompt is new, false for a redraw of the open one
+   */
+  hold(line: string): boolean {
+    if (this.pending === line.trim()) return false;
+    this.pending = line.trim();
+    this.keepAlive ??= setInterval(this.ping, 1000);
+    this.write(`\n${MANUAL_CONFIRMATION_NOTICE}: ${this.pending}\n`);
+    return true;
+  }
+
+  /** The user typed something, e.g. their answer, so the prompt is no longer open */
+  release() {
+    clearInterval(this.keepAlive);
+    this.keepAlive = undefined;
+    this.pending = null;
+  }
+}
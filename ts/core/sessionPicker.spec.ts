@@ -0,0 +1,59 @@
+import { readFileSync } from "fs";
+import path from "path";
+import { describe, expect, it } from "vitest";
+import { parsePickSession, parseSessionPicker, pickSessionKeys } from "./sessionPicker";
+
+const screen = readFileSync(path.resolve(__dirname, "../tests/fixtures/claude-resume-picker.txt"), "utf8");
+const header = [/Modified\s+Created\s+# Messages/];
+const DOWN = "\u001b[B";
+const UP = "\u001b[A";
+
+describe("parsePickSession", () => {
+  it("should accept first, last and entry numbers", () => {
+    expect(parsePickSession("first")).toBe("first");
+    expect(parsePickSession("last")).toBe("last");
+    expect(parsePickSession("3")).toBe(3);
+  });
+
+  it("should reject anything else", () => {
+    expect(() => parsePickSession("0")).toThrow(/Invalid --pick-session 0/);
+    expect(() => parsePickSession("newest")).toThrow(/expected first, last or an entry number/);
+  });
+});
+
+describe("parseSessionPicker", () => {
+  it("should list the entries below the header", () => {
+    const entries = parseSessionPicker(screen, header)!;
+    expect(entries.map((e) => e.index)).toEqual([1, 2, 3, 4]);
+    expect(entries[0]).toMatchObject({ selected: true, text: expect.stringContaining("Fix flaky parser tests") });
+    expect(entries.filter((e) => e.selected)).toHaveLength(1);
+  });
+
+  it("should return null without a picker", () => {
+    expect(parseSessionPicker("❯ 1. Yes\n  2. No", header)).toBeNull();
+    expect(parseSessionPicker("     Modified     Created      # Messages\n", header)).toBeNull();
+  });
+});
+
+describe("pickSessionKeys", () => {
+  const entries = parseSessionPicker(screen, header)!;
+  const withSelected = (index: number) => entries.map((e) => ({ ...e, selected: e.index === index }));
+
+  it("should press Enter right away for the highlighted entry", () => {
+    expect(pickSessionKeys(entries, "first")).toBe("\r");
+  });
+
+  it("should move down to the picked entry", () => {
+    expect(pickSessionKeys(entries, 3)).toBe(DOWN + DOWN + "\r");
+    expect(pickSessionKeys(entries, "last")).toBe(DOWN.repeat(3) + "\r");
+  });
+
+  it("should move up from a lower highlight", () => {
+    expect(pickSessionKeys(withSelected(4), "first")).toBe(UP.repeat(3) + "\r");
+    expect(pickSessionKeys(withSelected(4), 2)).toBe(UP + UP + "\r");
+  });
+
+  it("should give up on entries that aren't shown", () => {
+    expect(pickSessionKeys(entries, 9)).toBeNull();
+  });
+});
@@ -0,0 +1,71 @@
+/**
+ * Session pickers shown by `--resume`, and which entry to pick
+ *
+ * Claude's `--resume` without an id lists past sessions of the directory,
+ * most recent first, with the first one highlighted. The list shows no
+ * session ids, so the entry to pick comes from `--pick-session`: `first`
+ * (most recent), `last`, or a 1-based entry number. Picking navigates with
+ * the arrow keys from the highlighted entry and presses Enter. Without
+ * `--pick-session` the picker is left to the user.
+ */
+
+/** Which entry to pick, a number is the entry shown as `N.` */
+export type PickSessionPolicy = "first" | "last" | number;
+
+export interface SessionPickerEntry {
+  index: number; // the entry number shown, 1-based
+  text: string;
+  selected: boolean; // highlighted with ❯
+}
+
+const ENTRY = /^\s*(❯)?\s*(\d+)\.\s+(.*\S)\s*$/;
+
+/**
+ * Parse `--pick-session first|last|<n>`
+ * @throws Error on anything else
+ */
+export function parsePickSession(value: string): PickSessionPolicy {
+  if (value === "first" || value === "last") return value;
+  const index = Number(value);
+  if (Number.isInteger(index) && index >= 1) return index;
+  throw new Error(`Invalid --pick-session ${value}, expected first, last or an entry number like 2`);
+}
+
+/**
+ * List the picker entries on screen, below the picker header
+ *
+ * @param screen - Rendered screen text
+ * @param header - Patterns of the picker header, `sessionPicker` in the cli config
+ * @returns The entries, or null when no picker is shown
+ */
+export function parseSessionPicker(screen: string, header: RegExp[]): SessionPickerEntry[] | null {
+  const lines = screen.split("\n");
+  const start = lines.findLastIndex((line) => header.some((rx) => rx.test(line)));
+  if (start < 0) return null;
+  const entries = lines
+    .slice(start + 1)
+    .map((line) => line.match(ENTRY))
+    .filter((match) => match !== null)
+    .map(([, marker, index, text]) => ({ index: Number(index), text: text!, selected: Boolean(marker) }));
+  return entries.some((entry) => entry.selected) ? entries : null;
+}
+
+/**
+ * Keys moving the highlight to the entry picked by `policy`, then Enter
+ *
+ * @returns The keys, or null when the entry isn't on screen
+ *
+ * @example
+ * ```typescript
+ * pickSessionKeys(entries, 3); // "\u001b[B\u001b[B\r" with entry 1 highlighted
+ * ```
+ */
+export function pickSessionKeys(entries: SessionPickerEntry[], policy: PickSessionPolicy): string | null {
+  const selected = entries.find((entry) => entry.selected);
+  if (!selected) return null;
+  const target =
+    policy === "first" ? 1 : policy === "last" ? Math.max(...entries.map((entry) => entry.index)) : policy;
+  if (!entries.some((entry) => entry.index === target)) return null;
+  const moves = target - selected.index;
+  return (moves > 0 ? "\u001b[B" : "\u001b[A").repeat(Math.abs(moves)) + "\r";
+}
@@ -0,0 +1,49 @@
+import { describe, expect, it } from "vitest";
+import type { AgentEvent } from "./events";
+import { formatDuration, formatSessionSummary, summarizeSession } from "./sessionSummary";
+
+const prompt = (dryRun?: boolean): AgentEvent => ({
+  type: "prompt",
+  rule: "enter[0]",
+  pattern: "❯ 1\\. Yes",
+  action: "enter",
+  response: "\r",
+  line: "❯ 1. Yes",
+  ...(dryRun && { dryRun }),
+});
+
+describe("summarizeSession", () => {
+  it("should count answered prompts and crash restarts from the events", () => {
+    const events: AgentEvent[] = [
+      { type: "ready" },
+      prompt(),
+      prompt(),
+      { type: "crash_restart", exitCode: 1, attempt: 1, delayMs: 0 },
+      { type: "ready" },
+      prompt(),
+      prompt(true), // --dry-run, not answered
+      { type: "idle_timeout", idleMs: 60000 },
+    ];
+    const startedAt = new Date("2026-10-15T10:00:00Z");
+    expect(
+      summarizeSession({ cli: "claude", exitCode: 0, reason: "idle", startedAt, events, now: startedAt.getTime() + 90_000 }),
+    ).toEqual({ cli: "claude", exitCode: 0, reason: "idle", durationMs: 90_000, responses: 3, restarts: 1 });
+  });
+});
+
+describe("formatSessionSummary", () => {
+  it("should print one line", () => {
+    expect(
+      formatSessionSummary({ cli: "claude", exitCode: 0, reason: "idle", durationMs: 5400000, responses: 42, restarts: 1 }),
+    ).toBe("📊 Summary: 42 prompts auto-answered, 1 crash restart, ran 1h 30m, ended: idle (exit code 0)");
+    expect(
+      formatSessionSummary({ cli: "claude", exitCode: 124, reason: "timeout", durationMs: 125000, responses: 1, restarts: 0 }),
+    ).toBe("📊 Summary: 1 prompt auto-answered, 0 crash restarts, ran 2m 5s, ended: max runtime (exit code 124)");
+  });
+
+  it("should format short and empty durations", () => {
+    expect(formatDuration(0)).toBe("0s");
+    expect(formatDuration(1499)).toBe("1s");
+    expect(formatDuration(3600_000)).toBe("1h");
+  });
+});
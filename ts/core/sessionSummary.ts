@@ -0,0 +1,89 @@
+import type { AgentEvent } from "./events.ts";
+import type { ExitReason } from "./exitHook.ts";
+
+/**
+ * What happened in a session, for the summary line printed on exit and for
+ * `--notify-url` / `--notify-command`
+ *
+ * Counted from the session's event history (events.ts), so the numbers
+ * match the `--json-log`.
+ */
+
+export interface SessionSummary {
+  cli: string;
+  exitCode: number | null;
+  reason: ExitReason; // e.g. "idle", "timeout" (--max-runtime), "exited" (the agent's own exit)
+  durationMs: number; // from start to exit
+  responses: number; // prompts auto-answered
+  restarts: number; // crash restarts
+}
+
+/**
+ * Count the auto-responses and crash restarts of a session
+ */
+export function summarizeSession({
+  cli,
+  exitCode,
+  reason,
+  startedAt,
+  events,
+  now = Date.now(),
+}: {
+  cli: string;
+  exitCode: number | null;
+  reason: ExitReason;
+  startedAt: Date;
+  events: readonly AgentEvent[];
+  now?: number;
+}): SessionSummary {
+  return {
+    cli,
+    exitCode,
+    reason,
+    durationMs: now - startedAt.getTime(),
+    responses: events.filter((event) => event.type === "prompt" && !event.dryRun).length,
+    restarts: events.filter((event) => event.type === "crash_restart").length,
+  };
+}
+
+const REASONS: Record<ExitReason, string> = {
+  exited: "the agent exited",
+  crashed: "the agent crashed",
+  idle: "idle",
+  fatal: "fatal error",
+  interrupted: "interrupted",
+  "output-limit": "output limit",
+  "parent-exit": "parent exited",
+  timeout: "max runtime",
+};
+
+/** e.g. 5400000 -> "1h 30m", 125000 -> "2m 5s" */
+export function formatDuration(ms: number): string {
+  const seconds = Math.round(ms / 1000);
+  const parts = [
+    [Math.floor(seconds / 3600), "h"],
+    [Math.floor((seconds % 3600) / 60), "m"],
+    [seconds % 60, "s"],
+  ] as const;
+  return parts.filter(([n]) => n > 0).map(([n, unit]) => `${n}${unit}`).join(" ") || "0s";
+}
+
+const plural = (n: number, word: string) => `${n} ${word}${n === 1 ? "" : "s"}`;
+
+/**
+ * One line for the terminal
+ *
+ * @example
+ * ```typescript
+ * formatSessionSummary({ cli: "claude", exitCode: 0, reason: "idle", durationMs: 5400000, responses: 42, restarts: 1 });
+ * // "📊 Summary: 42 prompts auto-answered, 1 crash restart, ran 1h 30m, ended: idle (exit code 0)"
+ * ```
+ */
+export function formatSessionSummary(summary: SessionSummary): string {
+  return [
+    `📊 Summary: ${plural(summary.responses, "prompt")} auto-answered`,
+    plural(summary.restarts, "crash restart"),
+    `ran ${formatDuration(summary.durationMs)}`,
+    `ended: ${REASONS[summary.reason]} (exit code ${summary.exitCode ?? "none"})`,
+  ].join(", ");
+}
@@ -0,0 +1,55 @@
+import { spawn } from "child_process";
+import { describe, expect, it } from "vitest";
+import { isProcessAlive, killWithTimeout, processGroup } from "./shutdown";
+
+const spawnSleeper = (script: string) => {
+  const child = spawn("sh", ["-c", script], { stdio: "ignore" });
+  const exited = new Promise<void>((resolve) => child.on("exit", () => resolve()));
+  return {
+    exited,
+    proc: { pid: child.pid!, kill: (signal?: string) => child.kill(signal as NodeJS.Signals) },
+  };
+};
+
+describe("killWithTimeout", () => {
+  it.skipIf(process.platform === "win32")("should stop a process that honors SIGTERM", async () => {
+    const { proc, exited } = spawnSleeper("sleep 30");
+
+    const graceful = await killWithTimeout(proc, { timeout: 2000 });
+    await exited;
+
+    expect(graceful).toBe(true);
+    expect(isProcessAlive(proc.pid)).toBe(false);
+  });
+
+  it.skipIf(process.platform === "win32")(
+    "should escalate to SIGKILL when SIGTERM is ignored",
+    async () => {
+      const { proc, exited } = spawnSleeper("trap '' TERM; while :; do sleep 0.1; done");
+      await new Promise((resolve) => setTimeout(resolve, 200)); // let the trap install
+
+      const start = Date.now();
+      const graceful = await killWithTimeout(proc, { timeout: 300 });
+      await exited;
+
+      expect(graceful).toBe(false);
+      expect(Date.now() - start).toBeGreaterThanOrEqual(300);
+      expect(isProcessAlive(proc.pid)).toBe(false);
+    },
+  );
+});
+
+describe("processGroup", () => {
+  it.skipIf(process.platform === "win32")("should signal the children of a group leader too", async () => {
+    const child = spawn("sh", ["-c", "sleep 30 & echo $!; wait"], { detached: true, stdio: ["ignore", "pipe", "ignore"] });
+    const exited = new Promise<void>((resolve) => child.on("exit", () => resolve()));
+    const grandchild = Number(await new Promise<string>((resolve) => child.stdout!.once("data", (data) => resolve(String(data)))));
+
+    const proc = { pid: child.pid!, kill: (signal?: string) => child.kill(signal as NodeJS.Signals) };
+    expect(await killWithTimeout(processGroup(proc), { timeout: 2000 })).toBe(true);
+    await exited;
+    await new Promise((resolve) => setTimeout(resolve, 100)); // let the grandchild go
+
+    expect(isProcessAlive(grandchild)).toBe(false);
+  });
+});
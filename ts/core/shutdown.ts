@@ -0,0 +1,79 @@
+import { logger } from "../logger.ts";
+import { sleepms } from "../utils.ts";
+
+/**
+ * Bounded process shutdown helpers
+ */
+
+export interface KillableProcess {
+  pid: number;
+  kill: (signal?: string) => void;
+}
+
+/**
+ * Check if a process is still alive (signal 0 only tests for existence)
+ */
+export function isProcessAlive(pid: number): boolean {
+  try {
+    process.kill(pid, 0);
+    return true;
+  } catch {
+    return false;
+  }
+}
+
+/**
+ * The process with its whole process group, for signals
+ *
+ * The pty makes the agent a session leader, so signaling its group also
+ * reaches the tools it started (shells, dev servers), which would otherwise
+ * be orphaned. Falls back to the process alone when it leads no group, and
+ * on Windows.
+ */
+export function processGroup(proc: KillableProcess): KillableProcess {
+  return {
+    pid: proc.pid,
+    kill: (signal = "SIGTERM") => {
+      if (process.platform === "win32") return proc.kill(signal);
+      try {
+        process.kill(-proc.pid, signal);
+      } catch {
+        proc.kill(signal); // not a group leader
+      }
+    },
+  };
+}
+
+/**
+ * Send a signal to the agent, and escalate to SIGKILL if it's still alive after a timeout
+ *
+ * Keeps shutdown bounded when the agent ignores SIGINT/SIGTERM, instead of
+ * leaving agent-yes waiting on it forever.
+ *
+ * @param proc - Process to stop, e.g. the PTY shell
+ * @param options.signal - Signal to send first (default: SIGTERM)
+ * @param options.timeout - Milliseconds to wait before sending SIGKILL (default: 5000)
+ * @returns true if the process exited on its own, false if it had to be SIGKILLed
+ *
+ * @example
+ * ```typescript
+ * await killWithTimeout(shell, { signal: 'SIGINT', timeout: 3000 });
+ * ```
+ */
+export async function killWithTimeout(
+  proc: KillableProcess,
+  { signal = "SIGTERM", timeout = 5000 }: { signal?: string; timeout?: number } = {},
+): Promise<boolean> {
+  proc.kill(signal);
+
+  const deadline = Date.now() + timeout;
+  while (Date.now() < deadline) {
+    if (!isProcessAlive(proc.pid)) return true;
+    await sleepms(50);
+  }
+  if (!isProcessAlive(proc.pid)) return true;
+
+  logger.warn(`Process ${proc.pid} did not exit within ${timeout}ms after ${signal}, sending SIGKILL`);
+  proc.kill("SIGKILL");
+  return false;
+}
@@ -0,0 +1,45 @@
+import { spawnSync } from "child_process";
+import { describe, expect, it } from "vitest";
+import { parsePtySize } from "./ptySize";
+import { describeSpawnError, getTerminalDimensions, withoutContinueArgs } from "./spawner";
+
+describe("describeSpawnError", () => {
+  it("should explain a missing binary", () => {
+    const { error } = spawnSync("agent-yes-nonexistent-binary");
+    expect(describeSpawnError(error, "agent-yes-nonexistent-binary")).toBe(
+      "command 'agent-yes-nonexistent-binary' not found, is it installed and in your PATH?",
+    );
+  });
+
+  it("should explain a binary that is not executable", () => {
+    const error = Object.assign(new Error("spawn ./claude EACCES"), { code: "EACCES" });
+    expect(describeSpawnError(error, "./claude")).toMatch(/permission denied.*chmod \+x/);
+  });
+
+  it("should recognize pty style not found messages", () => {
+    expect(describeSpawnError(new Error("File not found: claude"), "claude")).toMatch(/not found/);
+  });
+
+  it("should fall back to the original message", () => {
+    expect(describeSpawnError(new Error("boom"), "claude")).toBe("failed to spawn 'claude': boom");
+  });
+});
+
+describe("getTerminalDimensions", () => {
+  it("should apply the pty size override", () => {
+    expect(getTerminalDimensions(parsePtySize("132x43"))).toEqual({ cols: 132, rows: 43 });
+  });
+});
+
+describe("withoutContinueArgs", () => {
+  it("should drop the resume flags only", () => {
+    expect(withoutContinueArgs(["--continue", "--model", "opus", "--resume"])).toEqual(["--model", "opus"]);
+  });
+
+  it("should drop a resumed session id with its flag", () => {
+    expect(withoutContinueArgs(["--resume", "3f2b8c1e-9d4a-4e6b-8f1a-2c3d4e5f6a7b", "--model", "opus"])).toEqual([
+      "--model",
+      "opus",
+    ]);
+  });
+});
@@ -1,10 +1,12 @@
 import { execaCommandSync, parseCommandString } from "execa";
 import { logger } from "../logger.ts";
 import { catcher } from "../catcher.ts";
-import pty from "../pty.ts";
+import pty, { ptyPackage } from "../pty.ts";
 import type { AgentCliConfig } from "../index.ts";
 import type { SUPPORTED_CLIS } from "../SUPPORTED_CLIS.ts";
 import type { IPty } from "node-pty";
+import { withStderrLog } from "./splitStderr.ts";
+import { DEFAULT_PTY_SIZE, normalizePtySize, type PtySize } from "./ptySize.ts";
 
 /**
  * Agent spawning utilities
@@ -16,15 +18,27 @@ export interface SpawnOptions {
   cliArgs: string[];
   verbose: boolean;
   install: boolean;
+  stderrLogDir?: string; // send the agent's stderr to <dir>/<pid>.stderr.log instead of the pty, see splitStderr.ts
   ptyOptions: {
     name: string;
     cols: number;
     rows: number;
     cwd: string;
     env: Record<string, string>;
+    encoding?: null; // see RAW_PTY_OUTPUT
   };
 }
 
+/**
+ * Whether the agent's output is read as raw bytes (node-pty's `encoding: null`)
+ *
+ * The bytes are decoded by agent-yes with a Utf8StreamDecoder, so a char
+ * split across reads is reassembled and non-UTF8 output (e.g. Latin-1 from a
+ * subprocess) becomes U+FFFD instead of holding up the output. bun-pty and
+ * Windows ConPTY only deliver strings.
+ */
+export const RAW_PTY_OUTPUT = ptyPackage === "node-pty" && process.platform !== "win32";
+
 /**
  * Get install command based on platform and configuration
  *
@@ -82,6 +96,34 @@ export function getInstallCommand(
   return null;
 }
 
+/**
+ * Describe why spawning failed, with a hint on how to fix it
+ *
+ * @param error - Error thrown while spawning
+ * @param bin - Binary that was spawned
+ * @returns Human readable reason, e.g. "command 'claude' not found, ..."
+ *
+ * @example
+ * ```typescript
+ * describeSpawnError(Object.assign(new Error("spawn foo ENOENT"), { code: "ENOENT" }), "foo");
+ * // "command 'foo' not found, is it installed and in your PATH?"
+ * ```
+ */
+export function describeSpawnError(error: unknown, bin: string): string {
+  const code = (error as NodeJS.ErrnoException | undefined)?.code;
+  const message = error instanceof Error ? error.message : String(error);
+
+  if (code === "EACCES" || /EACCES|permission denied/i.test(message))
+    return `permission denied running '${bin}', check that it is executable (chmod +x)`;
+  if (code === "ENOENT" || /ENOENT|command not found|no such file|file not found/i.test(message))
+    return `command '${bin}' not found, is it installed and in your PATH?`;
+  if (code === "ENOEXEC" || /ENOEXEC|exec format error/i.test(message))
+    return `'${bin}' is not a valid executable for this platform (exec format error)`;
+  if (code === "EMFILE" || code === "ENFILE")
+    return `too many open files while spawning '${bin}', try raising the limit (ulimit -n)`;
+  return `failed to spawn '${bin}': ${message}`;
+}
+
 /**
  * Check if error is a command not found error
  */
@@ -126,11 +168,12 @@ function isCommandNotFoundError(e: unknown): boolean {
  * ```
  */
 export function spawnAgent(options: SpawnOptions): IPty {
-  const { cli, cliConf, cliArgs, verbose, install, ptyOptions } = options;
+  const { cli, cliConf, cliArgs, verbose, install, stderrLogDir, ptyOptions } = options;
 
+  const cliCommand = cliConf?.binary || cli;
   const spawn = () => {
-    const cliCommand = cliConf?.binary || cli;
     let [bin, ...args] = [...parseCommandString(cliCommand), ...cliArgs];
+    [bin, args] = withStderrLog(bin!, args, stderrLogDir);
     if (verbose) logger.info(`Spawning ${bin} with args: ${JSON.stringify(args)}`);
     logger.info(`Spawning ${bin} with args: ${JSON.stringify(args)}`);
     const spawned = pty.spawn(bin!, args, ptyOptions);
@@ -141,7 +184,8 @@ export function spawnAgent(options: SpawnOptions): IPty {
   return catcher(
     // error handler
     (error: unknown, _fn, ..._args) => {
-      logger.error(`Fatal: Failed to start ${cli}.`);
+      const [bin = cliCommand] = parseCommandString(cliCommand);
+      logger.error(`Fatal: Failed to start ${cli}: ${describeSpawnError(error, bin)}`);
 
       const isNotFound = isCommandNotFoundError(error);
       if (cliConf?.install && isNotFound) {
@@ -176,15 +220,47 @@ export function spawnAgent(options: SpawnOptions): IPty {
   )();
 }
 
+let warnedDegenerateSize = false; // warn once, resize events would repeat it
+
 /**
  * Get terminal dimensions with defaults for non-TTY environments
+ *
+ * A 0x0 or otherwise degenerate size reported by the terminal is replaced
+ * by a default, see normalizePtySize.
+ * @param override - Fixed size from `--pty-size`, takes precedence over the real terminal
  */
-export function getTerminalDimensions(): { cols: number; rows: number } {
+export function getTerminalDimensions(override?: PtySize): PtySize {
+  if (override) return override;
   if (!process.stdout.isTTY) return { cols: 80, rows: 30 }; // default size when not tty
+  const { columns, rows } = process.stdout;
+  const size = normalizePtySize({ cols: columns, rows });
+  if ((size.cols !== columns || size.rows !== rows) && !warnedDegenerateSize) {
+    warnedDegenerateSize = true;
+    logger.warn(
+      `[pty] Terminal reported size ${columns}x${rows}, using ${size.cols}x${size.rows} instead (set --pty-size to override, default ${DEFAULT_PTY_SIZE.cols}x${DEFAULT_PTY_SIZE.rows})`,
+    );
+  }
   return {
     // TODO: enforce minimum cols/rows to avoid layout issues
     // cols: Math.max(process.stdout.columns, 80),
-    cols: Math.min(Math.max(20, process.stdout.columns), 80),
-    rows: process.stdout.rows,
+    cols: Math.min(Math.max(20, size.cols), 80),
+    rows: size.rows,
   };
 }
+
+/**
+ * Drop the resume flags from cli args, to start a fresh session when there
+ * is nothing to continue ("No conversation found to continue")
+ *
+ * @example
+ * ```typescript
+ * withoutContinueArgs(["--continue", "--model", "opus"]); // ["--model", "opus"]
+ * withoutContinueArgs(["--resume", "3f2b8c1e-9d4a-4e6b-8f1a-2c3d4e5f6a7b"]); // []
+ * ```
+ */
+export function withoutContinueArgs(args: string[]): string[] {
+  const isSessionId = (arg?: string) => /^[0-9a-f]{8}-[0-9a-f]{4}-[0-9a-f]{4}-[0-9a-f]{4}-[0-9a-f]{12}$/i.test(arg ?? "");
+  return args.filter(
+    (arg, i) => !["--continue", "--resume"].includes(arg) && !(args[i - 1] === "--resume" && isSessionId(arg)),
+  );
+}
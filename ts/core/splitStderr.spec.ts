@@ -0,0 +1,20 @@
+import { execFileSync } from "child_process";
+import { mkdtempSync, readFileSync } from "fs";
+import { tmpdir } from "os";
+import path from "path";
+import { describe, expect, it } from "vitest";
+import { withStderrLog } from "./splitStderr";
+
+describe("withStderrLog", () => {
+  it("should leave the command alone without a log dir", () => {
+    expect(withStderrLog("claude", ["--verbose"])).toEqual(["claude", ["--verbose"]]);
+  });
+
+  it.skipIf(process.platform === "win32")("should send stderr to the log and keep stdout", () => {
+    const logDir = mkdtempSync(path.join(tmpdir(), "agent-yes-stderr-"));
+    const [bin, args] = withStderrLog("sh", ["-c", 'echo out; echo err >&2; echo $$'], logDir);
+    const [out, pid] = execFileSync(bin, args, { encoding: "utf8" }).trim().split("\n");
+    expect(out).toBe("out");
+    expect(readFileSync(path.join(logDir, `${pid}.stderr.log`), "utf8")).toBe("err\n");
+  });
+});
@@ -0,0 +1,37 @@
+import path from "path";
+
+/**
+ * Run the agent with its stderr outside the pty, for `--split-stderr`
+ *
+ * A pty has one output stream, stdout and stderr of the agent arrive merged
+ * and agent-yes can't tell its error output apart. With `--split-stderr` the
+ * agent is started through `sh`, which points fd 2 at a log file of its own
+ * and then execs the agent, so it keeps the pid the logs are named after,
+ * while stdin and stdout stay on the pty.
+ *
+ * Tradeoffs: stderr is no longer a tty, so the agent may notice it doesn't
+ * run in a full terminal (`isatty(2)` is false) and change its behavior,
+ * error output doesn't show on screen or in the rendered logs, and a missing
+ * binary is reported by `sh` in the stderr log (exit code 127) instead of
+ * the usual "command not found" hint. Not available on Windows.
+ */
+
+/**
+ * Wrap a command so its stderr is appended to `<logDir>/<pid>.stderr.log`
+ *
+ * @param bin - Binary to run
+ * @param args - Its arguments
+ * @param logDir - Directory for the log, unset or on Windows the command is returned as is
+ * @returns Binary and arguments to spawn instead
+ *
+ * @example
+ * ```typescript
+ * withStderrLog("claude", ["--verbose"], ".agent-yes/logs");
+ * // ["/bin/sh", ["-c", 'exec "$@" 2>>"$0/$$.stderr.log"', ".agent-yes/logs", "claude", "--verbose"]]
+ * ```
+ */
+export function withStderrLog(bin: string, args: string[], logDir?: string): [string, string[]] {
+  if (!logDir || process.platform === "win32") return [bin, args];
+  // $0 is the log dir, $$ stays the agent's pid after exec
+  return ["/bin/sh", ["-c", 'exec "$@" 2>>"$0/$$.stderr.log"', logDir, bin, ...args]];
+}
@@ -0,0 +1,47 @@
+import { existsSync } from "fs";
+import { mkdtemp, readFile, rm } from "fs/promises";
+import os from "os";
+import path from "path";
+import { afterEach, beforeEach, describe, expect, it } from "vitest";
+import { SESSION_DIR_ENV, getCacheDir, getStateDir } from "./stateDir";
+import { getSessionsFile, loadSessionMap, storeSessionForCwd } from "../resume/codexSessionManager";
+
+describe("getStateDir", () => {
+  const original = { session: process.env[SESSION_DIR_ENV], home: process.env.CLI_YES_TEST_HOME };
+  let dir: string;
+
+  beforeEach(async () => {
+    dir = await mkdtemp(path.join(os.tmpdir(), "agent-yes-state-"));
+    delete process.env[SESSION_DIR_ENV];
+    delete process.env.CLI_YES_TEST_HOME;
+  });
+
+  afterEach(async () => {
+    for (const [key, value] of [[SESSION_DIR_ENV, original.session], ["CLI_YES_TEST_HOME", original.home]] as const)
+      if (value === undefined) delete process.env[key];
+      else process.env[key] = value;
+    await rm(dir, { recursive: true, force: true });
+  });
+
+  it("should default to ~/.config/agent-yes", () => {
+    expect(getStateDir()).toBe(path.join(os.homedir(), ".config", "agent-yes"));
+    expect(getCacheDir()).toBe(path.join(os.homedir(), ".config", "agent-yes", "cache"));
+  });
+
+  it("should be relocated by the session dir override", () => {
+    process.env[SESSION_DIR_ENV] = dir;
+    expect(getStateDir()).toBe(dir);
+    expect(getCacheDir()).toBe(path.join(dir, "cache"));
+    expect(getSessionsFile()).toBe(path.join(dir, "codex-sessions.json"));
+  });
+
+  it("should read and write codex sessions in the overridden dir", async () => {
+    process.env[SESSION_DIR_ENV] = path.join(dir, "nested");
+    await storeSessionForCwd("/work/api", "0199e659-0e5f-7843-8876-5a65c64e77c0");
+
+    const file = path.join(dir, "nested", "codex-sessions.json");
+    expect(existsSync(file)).toBe(true);
+    expect(JSON.parse(await readFile(file, "utf8"))["/work/api"].sessionId).toBe("0199e659-0e5f-7843-8876-5a65c64e77c0");
+    expect((await loadSessionMap())["/work/api"]?.sessionId).toBe("0199e659-0e5f-7843-8876-5a65c64e77c0");
+  });
+});
@@ -0,0 +1,21 @@
+import { homedir } from "os";
+import path from "path";
+
+/**
+ * Where agent-yes keeps its per-user state: codex and claude sessions, caches and the
+ * running lock. `--session-dir` (or `CLAUDE_YES_SESSION_DIR`) moves all of
+ * it, e.g. for sandboxes, tests or several users sharing one home.
+ */
+
+/** Env var holding the `--session-dir` override, inherited by child sessions */
+export const SESSION_DIR_ENV = "CLAUDE_YES_SESSION_DIR";
+
+/** State dir, `~/.config/agent-yes` unless overridden */
+export function getStateDir(): string {
+  const override = process.env[SESSION_DIR_ENV];
+  if (override) return path.resolve(override);
+  return path.join(process.env.CLI_YES_TEST_HOME || homedir(), ".config", "agent-yes");
+}
+
+/** Cache dir for fetched files, e.g. remote pattern sets */
+export const getCacheDir = () => path.join(getStateDir(), "cache");
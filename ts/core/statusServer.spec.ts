@@ -0,0 +1,65 @@
+import { request, type Server } from "http";
+import type { AddressInfo } from "net";
+import { afterEach, describe, expect, it } from "vitest";
+import { startStatusServer, type SessionStatus } from "./statusServer";
+
+const status: SessionStatus = {
+  pid: 100,
+  agentPid: 101,
+  cli: "claude",
+  state: "busy",
+  activity: "running-tool",
+  tool: "Bash",
+  startedAt: "2026-01-02T03:04:05.000Z",
+  uptimeMs: 5000,
+  idleMs: 200,
+  remainingIdleMs: 29800,
+  prompts: 2,
+  lastPrompt: { rule: "enter[0]", category: "edit", line: "❯ 1. Yes" },
+};
+
+describe("startStatusServer", () => {
+  let server: Server | undefined;
+  const url = (pathname: string) => `http://127.0.0.1:${(server!.address() as AddressInfo).port}${pathname}`;
+
+  afterEach(() => {
+    server?.close();
+    server = undefined;
+  });
+
+  it("should serve the current status as JSON on localhost", async () => {
+    let prompts = 2;
+    server = await startStatusServer(0, () => ({ ...status, prompts }));
+    expect((server.address() as AddressInfo).address).toBe("127.0.0.1");
+
+    const response = await fetch(url("/status"));
+    expect(response.headers.get("content-type")).toBe("application/json");
+    expect(await response.json()).toEqual(status);
+
+    prompts = 3;
+    expect(((await (await fetch(url("/"))).json()) as SessionStatus).prompts).toBe(3);
+  });
+
+  it("should refuse requests for another host, against DNS rebinding", async () => {
+    server = await startStatusServer(0, () => status);
+    const { port } = server.address() as AddressInfo;
+    const statusFor = (host: string) =>
+      new Promise<number | undefined>((resolve, reject) =>
+        request({ host: "127.0.0.1", port, path: "/status", headers: { host } }, (res) => {
+          res.resume();
+          resolve(res.statusCode);
+        })
+          .on("error", reject)
+          .end(),
+      );
+    expect(await statusFor(`localhost:${port}`)).toBe(200);
+    expect(await statusFor(`127.0.0.1:${port}`)).toBe(200);
+    expect(await statusFor(`evil.example:${port}`)).toBe(403);
+    expect(await statusFor("localhost")).toBe(403); // wrong port
+  });
+
+  it("should answer 404 for other paths", async () => {
+    server = await startStatusServer(0, () => status);
+    expect((await fetch(url("/nope"))).status).toBe(404);
+  });
+});
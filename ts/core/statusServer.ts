@@ -0,0 +1,65 @@
+import { createServer, type Server } from "http";
+import type { AddressInfo } from "net";
+import { logger } from "../logger.ts";
+import type { AgentState } from "./agentState.ts";
+import type { Activity } from "./activity.ts";
+
+/**
+ * HTTP status endpoint, behind `--status-port <port>`
+ *
+ * For supervising many background sessions without parsing their logs:
+ * `GET /status` (or `/`) answers with a JSON snapshot of the session. Only
+ * listens on localhost, and only when the flag is given; the module is
+ * loaded lazily so sessions without it don't pay for an HTTP server.
+ * Requests must name `localhost` or `127.0.0.1` with the bound port as
+ * their Host, so a web page can't read the status through DNS rebinding.
+ */
+
+export interface SessionStatus {
+  pid: number; // agent-yes process id
+  agentPid?: number; // pid of the agent process
+  cli: string;
+  prefix?: string; // the --prefix label of the instance, if any
+  state: AgentState; // starting, busy or awaiting-input, see agentState.ts
+  activity: Activity; // thinking, running-tool, ..., see activity.ts
+  tool?: string; // the running tool, e.g. "Bash"
+  startedAt: string; // ISO timestamp
+  uptimeMs: number;
+  idleMs: number; // since the last output
+  remainingIdleMs: number | null; // until --exit-on-idle fires, null without it
+  prompts: number; // prompts answered so far
+  lastPrompt: { rule: string; category?: string; line: string } | null;
+}
+
+/**
+ * Serve `getStatus()` as JSON on `host:port`
+ * @param port - Port to listen on, 0 picks a free one
+ * @returns The server, close it when the session ends
+ */
+export async function startStatusServer(
+  port: number,
+  getStatus: () => SessionStatus,
+  host = "127.0.0.1",
+): Promise<Server> {
+  const server = createServer((req, res) => {
+    const { port: boundPort } = server.address() as AddressInfo;
+    if (![`localhost:${boundPort}`, `127.0.0.1:${boundPort}`].includes(req.headers.host?.toLowerCase() ?? "")) {
+      res.writeHead(403, { "content-type": "application/json" }).end(JSON.stringify({ error: "forbidden host" }));
+      return;
+    }
+    const pathname = new URL(req.url ?? "/", "http://localhost").pathname;
+    if (req.method !== "GET" || (pathname !== "/" && pathname !== "/status")) {
+      res.writeHead(404, { "content-type": "application/json" }).end(JSON.stringify({ error: "not found" }));
+      return;
+    }
+    res.writeHead(200, { "content-type": "application/json" }).end(JSON.stringify(getStatus()));
+  });
+
+  await new Promise<void>((resolve, reject) => {
+    server.once("error", reject);
+    server.listen(port, host, () => resolve());
+  });
+  const address = server.address() as AddressInfo;
+  logger.info(`Status endpoint listening at http://${address.address}:${address.port}/status`);
+  return server;
+}
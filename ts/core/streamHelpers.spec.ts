@@ -0,0 +1,155 @@
+import type { IPty } from "node-pty";
+import { TerminalTextRender } from "terminal-render";
+import { describe, expect, it, vi } from "vitest";
+import { createTerminateSignalHandler, createTerminatorStream, handleConsoleControlCodes } from "./streamHelpers";
+
+describe("createTerminateSignalHandler", () => {
+  it("should abort on CTRL+C while the agent is loading", () => {
+    const onAbort = vi.fn();
+    const handler = createTerminateSignalHandler({ isReady: false }, onAbort);
+
+    expect(handler("\u0003")).toBe("\u0003");
+    expect(onAbort).toHaveBeenCalledWith(130);
+  });
+
+  it("should forward a single CTRL+C to a ready agent without aborting", () => {
+    const onAbort = vi.fn();
+    const handler = createTerminateSignalHandler({ isReady: true }, onAbort, { now: () => 0 });
+
+    expect(handler("\u0003")).toBe("\u0003");
+    expect(onAbort).not.toHaveBeenCalled();
+  });
+
+  it("should force quit on a second CTRL+C within the window", () => {
+    let time = 0;
+    const onAbort = vi.fn();
+    const handler = createTerminateSignalHandler({ isReady: true }, onAbort, { now: () => time });
+
+    handler("\u0003");
+    time = 1500;
+    handler("\u0003");
+    expect(onAbort).toHaveBeenCalledWith(130);
+  });
+
+  it("should show the force quit hint with CRLF line endings, once per first CTRL+C", () => {
+    const notice = vi.fn();
+    const handler = createTerminateSignalHandler({ isReady: true }, vi.fn(), { now: () => 0, notice });
+
+    handler("\u0003");
+    expect(notice).toHaveBeenCalledWith("\r\n  Press Ctrl+C again to force quit\r\n");
+    handler("\u0003"); // force quit, no hint
+    expect(notice).toHaveBeenCalledTimes(1);
+  });
+
+  it("should not force quit when CTRL+C presses are far apart", () => {
+    let time = 0;
+    const onAbort = vi.fn();
+    const handler = createTerminateSignalHandler({ isReady: true }, onAbort, { now: () => time });
+
+    handler("\u0003");
+    time = 5000;
+    handler("\u0003");
+    expect(onAbort).not.toHaveBeenCalled();
+  });
+
+  it("should filter out CTRL+Z", () => {
+    const handler = createTerminateSignalHandler({ isReady: true }, vi.fn());
+    expect(handler("\u001A")).toBe("");
+  });
+});
+
+describe("createTerminatorStream", () => {
+  const readAll = async (stream: ReadableStream<string>) => {
+    const chunks: string[] = [];
+    for await (const chunk of stream) chunks.push(chunk);
+    return chunks.join("");
+  };
+
+  it("should drain chunks still queued when the agent exits", async () => {
+    const exit = Promise.withResolvers<void>();
+    let written = 0;
+    const source = new TransformStream<string, string>();
+    const writer = source.writable.getWriter();
+    const slowStage = new TransformStream<string, string>({
+      async transform(chunk, ctrl) {
+        await new Promise((resolve) => setTimeout(resolve, 10));
+        ctrl.enqueue(chunk);
+      },
+    });
+    const output = source.readable
+      .pipeThrough(slowStage)
+      .pipeThrough(createTerminatorStream(exit.promise, { expected: () => written }));
+
+    for (const chunk of ["a", "b", "c"]) {
+      written++;
+      writer.write(chunk);
+    }
+    exit.resolve(); // exits before the slow stage processed anything
+
+    expect(await readAll(output)).toBe("abc");
+  });
+
+  it("should stop waiting for missing chunks after the drain timeout", async () => {
+    const exit = Promise.withResolvers<void>();
+    const source = new TransformStream<string, string>();
+    const output = source.readable.pipeThrough(
+      createTerminatorStream(exit.promise, { expected: () => 5, timeout: 50 }),
+    );
+
+    exit.resolve();
+    expect(await readAll(output)).toBe("");
+  });
+});
+
+describe("handleConsoleControlCodes rendering", () => {
+  const render = (...chunks: string[]) => {
+    const terminalRender = new TerminalTextRender();
+    const shell = { write: vi.fn() } as unknown as IPty;
+    for (const chunk of chunks) handleConsoleControlCodes(chunk, shell, terminalRender, "claude", false);
+    return terminalRender.render();
+  };
+
+  it("should overwrite in place after a carriage return, like a terminal", () => {
+    expect(render("abcdef\rXY")).toBe("XYcdef");
+    expect(render("Progress: 50%\rProgress: 60%")).toBe("Progress: 60%");
+  });
+
+  it("should keep overwriting across chunks", () => {
+    expect(render("Downloading 10%", "\rDownloading 100%", "\n")).toContain("Downloading 100%");
+    expect(render("Downloading 10%", "\rDownloading 100%", "\n")).not.toContain("10%D");
+  });
+
+  it("should render repaints with cursor movement as the final screen", () => {
+    // ink repaints: cursor up, erase the line, write the new frame (a pty sends \r\n for newlines)
+    const frames = [
+      "✻ Thinking…\r\n> ",
+      "\u001b[1A\u001b[2K\r✽ Thinking…\r\n\u001b[2K> ",
+      "\u001b[1A\u001b[2K\r⏺ Done\r\n\u001b[2K> ",
+    ];
+    expect(render(...frames).trimEnd()).toBe("⏺ Done\n>");
+  });
+
+  it("should handle home, absolute positioning and erase in display", () => {
+    const cleared = render("old line 1\r\nold line 2", "\u001b[H\u001b[2J", "new");
+    expect(cleared).toContain("new");
+    expect(cleared).not.toContain("old line");
+    expect(render("aaaa\r\nbbbb", "\u001b[2;2HX").split("\n").slice(0, 2)).toEqual(["aaaa", "bXbb"]);
+    expect(render("abcdef", "\u001b[3D\u001b[K").trimEnd()).toBe("abc");
+  });
+});
+
+describe("handleConsoleControlCodes cursor position", () => {
+  const ask = (ttyStdin: boolean) => {
+    const shell = { write: vi.fn() } as unknown as IPty;
+    handleConsoleControlCodes("> \u001b[6n", shell, new TerminalTextRender(), "claude", false, ttyStdin);
+    return shell.write;
+  };
+
+  it("should answer cursor position requests without a raw terminal, e.g. --no-tty", () => {
+    expect(ask(false)).toHaveBeenCalledWith(expect.stringMatching(/^\u001b\[\d+;\d+R$/));
+  });
+
+  it("should leave them to a raw terminal on stdin", () => {
+    expect(ask(true)).not.toHaveBeenCalled();
+  });
+});
@@ -22,6 +22,7 @@ import type { SUPPORTED_CLIS } from "../SUPPORTED_CLIS.ts";
  * @param terminalRender - Terminal renderer for cursor position tracking
  * @param cli - CLI name for special handling (e.g., codex)
  * @param verbose - Whether to log verbose debug messages
+ * @param ttyStdin - Whether stdin is a terminal in raw mode, which answers cursor position requests itself
  *
  * @example
  * ```typescript
@@ -36,6 +37,7 @@ export function handleConsoleControlCodes(
   terminalRender: TerminalTextRender,
   cli: SUPPORTED_CLIS,
   verbose: boolean,
+  ttyStdin = !!process.stdin.isTTY,
 ) {
   // Render terminal output for log file
   terminalRender.write(text);
@@ -52,7 +54,7 @@ export function handleConsoleControlCodes(
   }
 
   // Only handle cursor position when stdin is not tty, because tty already handled this
-  if (process.stdin.isTTY) return;
+  if (ttyStdin) return;
 
   // Handle cursor position request - ESC[6n
   if (!text.includes("\u001b[6n")) return;
@@ -68,12 +70,17 @@ export function handleConsoleControlCodes(
  * Create a transformer that handles terminate signals (CTRL+C, CTRL+Z)
  *
  * Returns a function that processes input chunks and handles keyboard
- * interrupt signals. CTRL+C (SIGINT) is only processed when stdin is
- * not ready (agent is loading), allowing graceful shutdown. CTRL+Z is
- * currently filtered out as suspend/resume is not yet supported.
+ * interrupt signals. CTRL+C (SIGINT) aborts right away when stdin is
+ * not ready (agent is loading), allowing graceful shutdown. Once the agent
+ * is ready, the first CTRL+C is forwarded to the agent as an interrupt, and
+ * a second CTRL+C within `forceQuitWindowMs` force quits agent-yes itself.
+ * CTRL+Z is currently filtered out as suspend/resume is not yet supported.
  *
  * @param stdinReady - ReadyManager indicating if stdin is ready
  * @param onAbort - Callback invoked when user aborts with SIGINT
+ * @param options.forceQuitWindowMs - Max gap between two CTRL+C to force quit (default: 2000)
+ * @param options.now - Clock source, overridable for tests
+ * @param options.notice - Shows the force quit hint, on stderr only when it's a terminal by default
  * @returns Transformer function that processes chunks and handles signals
  *
  * @example
@@ -88,8 +95,14 @@ export function handleConsoleControlCodes(
 export function createTerminateSignalHandler(
   stdinReady: { isReady: boolean },
   onAbort: (exitCode: number) => void,
+  {
+    forceQuitWindowMs = 2000,
+    now = () => Date.now(),
+    notice = (text: string) => void (process.stderr.isTTY && process.stderr.write(text)),
+  }: { forceQuitWindowMs?: number; now?: () => number; notice?: (text: string) => void } = {},
 ) {
   let aborted = false;
+  let lastInterruptAt = -Infinity;
 
   return (chunk: string): string => {
     // handle CTRL+Z and filter it out (not supported yet)
@@ -105,6 +118,20 @@ export function createTerminateSignalHandler(
       return chunk; // still pass to agent, but they'll probably be killed
     }
 
+    // handle double CTRL+C: first one interrupts the agent, second one quits agent-yes
+    if (!aborted && chunk === "\u0003") {
+      const at = now();
+      if (at - lastInterruptAt <= forceQuitWindowMs) {
+        logger.error("User force quit: SIGINT");
+        onAbort(130); // SIGINT exit code
+        aborted = true;
+        return chunk;
+      }
+      lastInterruptAt = at;
+      notice("\r\n  Press Ctrl+C again to force quit\r\n"); // \r: stdin is in raw mode, so is the terminal
+      return chunk;
+    }
+
     return chunk; // normal inputs
   };
 }
@@ -115,24 +142,52 @@ export function createTerminateSignalHandler(
  * Creates a TransformStream that automatically terminates when the provided
  * promise resolves. Used to stop output processing when the agent exits.
  *
+ * When `drain` is given, termination waits until as many chunks as
+ * `drain.expected()` have passed through, so output still queued in earlier
+ * stages when the agent exits isn't cut off. `drain.timeout` bounds the wait
+ * in case chunks never arrive.
+ *
  * @param exitPromise - Promise that resolves when stream should terminate
+ * @param drain - Optional chunk count to wait for before terminating
  * @returns TransformStream that terminates on promise resolution
  *
  * @example
  * ```typescript
  * const exitPromise = Promise.withResolvers<number>();
- * stream.by(createTerminatorStream(exitPromise.promise));
+ * let written = 0;
+ * shell.onData((data) => { written++; writer.write(data); });
+ * stream.by(createTerminatorStream(exitPromise.promise, { expected: () => written }));
  *
  * // Later, when agent exits:
  * exitPromise.resolve(0);
  * ```
  */
-export function createTerminatorStream(exitPromise: Promise<unknown>): TransformStream<string, string> {
+export function createTerminatorStream(
+  exitPromise: Promise<unknown>,
+  drain?: { expected: () => number; timeout?: number },
+): TransformStream<string, string> {
+  let seen = 0;
+  let exited = false;
+  let terminated = false;
+  const terminate = (ctrl: TransformStreamDefaultController<string>) => {
+    if (terminated) return;
+    terminated = true;
+    ctrl.terminate();
+  };
   return new TransformStream({
     start: function terminator(ctrl) {
-      exitPromise.then(() => ctrl.terminate());
+      exitPromise.then(() => {
+        exited = true;
+        if (!drain || seen >= drain.expected()) return terminate(ctrl);
+        setTimeout(() => terminate(ctrl), drain.timeout ?? 1000); // give up draining eventually
+      });
+    },
+    transform: (e, ctrl) => {
+      if (terminated) return;
+      ctrl.enqueue(e);
+      seen++;
+      if (exited && drain && seen >= drain.expected()) terminate(ctrl);
     },
-    transform: (e, ctrl) => ctrl.enqueue(e),
-    flush: (ctrl) => ctrl.terminate(),
+    flush: (ctrl) => terminate(ctrl),
   });
 }
@@ -0,0 +1,54 @@
+import { mkdtemp, readFile, rm, writeFile } from "fs/promises";
+import os from "os";
+import path from "path";
+import { afterEach, beforeEach, describe, expect, it } from "vitest";
+import { createStreamLogFilter, StreamLog } from "./streamLog";
+
+describe("createStreamLogFilter", () => {
+  it("should strip control sequences and normalize line endings", () => {
+    const filter = createStreamLogFilter();
+    expect(filter("\u001b[1mdone\u001b[0m\r\n50%\r100%\n")).toBe("done\n50%\n100%\n");
+  });
+
+  it("should prefix lines with timestamps", () => {
+    const filter = createStreamLogFilter({ timestamps: true, now: () => new Date("2026-10-15T12:30:00.000Z") });
+    expect(filter("one\ntw")).toBe("2026-10-15T12:30:00.000Z one\n2026-10-15T12:30:00.000Z tw");
+    expect(filter("o\n")).toBe("o\n");
+  });
+});
+
+describe("StreamLog", () => {
+  let dir: string;
+
+  beforeEach(async () => {
+    dir = await mkdtemp(path.join(os.tmpdir(), "agent-yes-stream-log-"));
+  });
+
+  afterEach(async () => {
+    await rm(dir, { recursive: true, force: true });
+  });
+
+  it("should write each chunk as it arrives, truncating the old log", async () => {
+    const filePath = path.join(dir, "logs", "run.log");
+    const first = new StreamLog(filePath);
+    first.write("old\n");
+    await first.flush();
+
+    const log = new StreamLog(filePath, { filter: createStreamLogFilter() });
+    log.write("\u001b[32mhello\u001b[0m ");
+    await log.flush();
+    expect(await readFile(filePath, "utf8")).toBe("hello "); // on disk before the session ends
+    log.write("world\r\n");
+    await log.flush();
+    expect(await readFile(filePath, "utf8")).toBe("hello world\n");
+  });
+
+  it("should keep existing content with append", async () => {
+    const filePath = path.join(dir, "run.log");
+    await writeFile(filePath, "previous run\n");
+    const log = new StreamLog(filePath, { append: true });
+    log.write("next run\n");
+    await log.flush();
+    expect(await readFile(filePath, "utf8")).toBe("previous run\nnext run\n");
+  });
+});
@@ -0,0 +1,72 @@
+import path from "path";
+import { appendFile, mkdir, writeFile } from "fs/promises";
+import { logger } from "../logger.ts";
+import { removeControlCharacters } from "../removeControlCharacters.ts";
+import { composeOutputFilters, createLinePrefixer, type OutputFilter } from "./outputFilters.ts";
+
+/**
+ * `--stream-log`: the agent's output as plain text, appended as it arrives
+ *
+ * The rendered `--logFile` is only written when the session ends, so a
+ * killed run (SIGKILL, OOM, a closed CI job) leaves nothing behind. The
+ * stream log appends every output chunk right away, with control sequences
+ * removed, optionally prefixing each line with an RFC 3339 timestamp
+ * (`--log-timestamps`). It is truncated when the session starts unless
+ * `--log-append` is given.
+ */
+
+/**
+ * Turn output chunks into stream log text: control sequences removed, line endings normalized
+ *
+ * @example
+ * ```typescript
+ * const filter = createStreamLogFilter({ timestamps: true });
+ * filter("\u001b[1mdone\u001b[0m\r\n"); // "2026-10-15T12:30:00.000Z done\n"
+ * ```
+ */
+export function createStreamLogFilter({
+  timestamps = false,
+  now = () => new Date(),
+}: { timestamps?: boolean; now?: () => Date } = {}): OutputFilter {
+  return composeOutputFilters(
+    removeControlCharacters,
+    (chunk) => chunk.replace(/\r+\n/g, "\n").replace(/\r/g, "\n"), // an overwritten line becomes a new line
+    timestamps && createLinePrefixer(() => `${now().toISOString()} `),
+  );
+}
+
+export class StreamLog {
+  private queue: Promise<unknown>;
+
+  /**
+   * @param filePath - Log file, its directory is created
+   * @param options.append - Keep existing content instead of truncating it
+   * @param options.filter - Applied to each chunk before writing, see createStreamLogFilter
+   */
+  constructor(
+    readonly filePath: string,
+    private options: { append?: boolean; filter?: OutputFilter } = {},
+  ) {
+    this.queue = mkdir(path.dirname(filePath), { recursive: true })
+      .then(() => (options.append ? undefined : writeFile(filePath, "")))
+      .catch((error) => logger.warn(`Failed to create stream log ${filePath}: ${String(error)}`));
+  }
+
+  /**
+   * Append an output chunk, writes are serialized to keep the order
+   */
+  write(chunk: string) {
+    const text = this.options.filter ? this.options.filter(chunk) : chunk;
+    if (!text) return;
+    this.queue = this.queue
+      .then(() => appendFile(this.filePath, text))
+      .catch((error) => logger.debug(`streamlog|failed to append: ${String(error)}`));
+  }
+
+  /**
+   * Wait for all pending writes to finish
+   */
+  async flush() {
+    await this.queue;
+  }
+}
@@ -0,0 +1,56 @@
+import { mkdtemp, rm, writeFile } from "fs/promises";
+import os from "os";
+import path from "path";
+import { afterEach, beforeEach, describe, expect, it } from "vitest";
+import { appendSystemPromptArg, readSystemPromptFile } from "./systemPrompt";
+
+describe("readSystemPromptFile", () => {
+  let dir: string;
+  beforeEach(async () => {
+    dir = await mkdtemp(path.join(os.tmpdir(), "agent-yes-system-prompt-"));
+  });
+  afterEach(async () => {
+    await rm(dir, { recursive: true, force: true });
+  });
+
+  it("should read the file, trimmed", async () => {
+    await writeFile(path.join(dir, "team.md"), "\nAlways run the tests.\n");
+    expect(readSystemPromptFile(path.join(dir, "team.md"))).toBe("Always run the tests.");
+  });
+
+  it("should reject missing and empty files", async () => {
+    await writeFile(path.join(dir, "empty.md"), " \n");
+    expect(() => readSystemPromptFile(path.join(dir, "empty.md"))).toThrow(/is empty/);
+    expect(() => readSystemPromptFile(path.join(dir, "nope.md"))).toThrow(/Can't read --system-prompt-file/);
+  });
+});
+
+describe("appendSystemPromptArg", () => {
+  it("should add the flag", () => {
+    expect(appendSystemPromptArg(["--model=opus"], "Be terse.")).toEqual([
+      "--model=opus",
+      "--append-system-prompt",
+      "Be terse.",
+    ]);
+  });
+
+  it("should append to a system prompt the user already set", () => {
+    expect(appendSystemPromptArg(["--append-system-prompt", "Use pnpm.", "-c"], "Be terse.")).toEqual([
+      "--append-system-prompt",
+      "Use pnpm.\n\nBe terse.",
+      "-c",
+    ]);
+    expect(appendSystemPromptArg(["--append-system-prompt=Use pnpm."], "Be terse.")).toEqual([
+      "--append-system-prompt=Use pnpm.\n\nBe terse.",
+    ]);
+  });
+
+  it("should keep a replacing --system-prompt", () => {
+    expect(appendSystemPromptArg(["--system-prompt", "You are a linter."], "Be terse.")).toEqual([
+      "--system-prompt",
+      "You are a linter.",
+      "--append-system-prompt",
+      "Be terse.",
+    ]);
+  });
+});
@@ -0,0 +1,48 @@
+import { readFileSync } from "fs";
+
+/**
+ * `--system-prompt-file`: pass a file to Claude's `--append-system-prompt`
+ *
+ * Long team-wide instructions are easier to keep in a file than on the
+ * command line. The file is appended to Claude's default system prompt; a
+ * `--append-system-prompt` the user passed already is kept, with the file
+ * appended after it, and a `--system-prompt` replacing the default stays.
+ */
+
+const FLAG = "--append-system-prompt";
+
+/**
+ * Read a system prompt file
+ * @throws Error when the file is missing or empty
+ */
+export function readSystemPromptFile(filePath: string): string {
+  let content: string;
+  try {
+    content = readFileSync(filePath, "utf8");
+  } catch (error) {
+    throw new Error(`Can't read --system-prompt-file ${filePath}: ${(error as Error).message}`);
+  }
+  if (!content.trim()) throw new Error(`--system-prompt-file ${filePath} is empty`);
+  return content.trim();
+}
+
+/**
+ * Add `text` to the `--append-system-prompt` of claude's args
+ *
+ * @example
+ * ```typescript
+ * appendSystemPromptArg(["--model=opus"], "Be terse."); // ["--model=opus", "--append-system-prompt", "Be terse."]
+ * appendSystemPromptArg(["--append-system-prompt", "Use pnpm."], "Be terse.");
+ * // ["--append-system-prompt", "Use pnpm.\n\nBe terse."]
+ * ```
+ */
+export function appendSystemPromptArg(args: string[], text: string): string[] {
+  const index = args.findIndex((arg) => arg === FLAG || arg.startsWith(`${FLAG}=`));
+  if (index < 0) return [...args, FLAG, text];
+  const arg = args[index]!;
+  const result = [...args];
+  if (arg.startsWith(`${FLAG}=`)) result[index] = `${arg}\n\n${text}`;
+  else if (index + 1 < args.length) result[index + 1] = `${args[index + 1]}\n\n${text}`;
+  else result.push(text); // flag without its value, give it ours
+  return result;
+}
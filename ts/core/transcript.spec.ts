@@ -0,0 +1,77 @@
+import { mkdtempSync, readFileSync, writeFileSync } from "fs";
+import { tmpdir } from "os";
+import path from "path";
+import { describe, expect, it } from "vitest";
+import { outputToMarkdown, renderTranscript, saveTranscript, type TranscriptInput } from "./transcript";
+
+describe("outputToMarkdown", () => {
+  it("should fence tool output", () => {
+    const output = [
+      "⏺ I'll run the tests.",
+      "",
+      "⏺ Bash(npm test)",
+      "  ⎿  > test",
+      "     12 passed",
+      "",
+      "⏺ All tests pass.",
+    ].join("\n");
+
+    expect(outputToMarkdown(output)).toBe(
+      "I'll run the tests.\n\n**Bash** `npm test`\n\n```\n> test\n12 passed\n```\n\nAll tests pass.",
+    );
+  });
+
+  it("should keep plain output as paragraphs", () => {
+    expect(outputToMarkdown("hello   \n\n\nworld")).toBe("hello\n\nworld");
+  });
+});
+
+describe("renderTranscript", () => {
+  it("should include the prompt and the answered prompts", () => {
+    const markdown = renderTranscript({
+      cli: "claude",
+      prompt: "fix the tests",
+      output: "⏺ Done",
+      prompts: [
+        {
+          type: "prompt",
+          rule: "enter[0]",
+          pattern: "❯ 1\\. Yes",
+          action: "enter",
+          response: "\r",
+          line: " ❯ 1. Yes",
+        },
+      ],
+      exitCode: 0,
+      startedAt: new Date("2026-01-01T00:00:00Z"),
+      endedAt: new Date("2026-01-01T00:01:30Z"),
+    });
+
+    expect(markdown).toBe(
+      [
+        "# claude-yes session",
+        "- Started: 2026-01-01T00:00:00.000Z\n- Duration: 90s\n- Exit code: 0",
+        "## Prompt",
+        "> fix the tests",
+        "## Answered prompts",
+        "- **❯ 1. Yes** → Enter",
+        "## Session",
+        "Done",
+      ].join("\n\n") + "\n",
+    );
+  });
+});
+
+describe("saveTranscript", () => {
+  const input: TranscriptInput = { cli: "claude", output: "⏺ Done", prompts: [], exitCode: 0, startedAt: new Date() };
+
+  it("should save the transcript, or report that it couldn't", async () => {
+    const dir = mkdtempSync(path.join(tmpdir(), "agent-yes-transcript-"));
+    const file = path.join(dir, "sub", "session.md");
+    expect(await saveTranscript(file, input)).toBe(true);
+    expect(readFileSync(file, "utf8")).toContain("Done");
+
+    writeFileSync(path.join(dir, "not-a-dir"), "");
+    expect(await saveTranscript(path.join(dir, "not-a-dir", "session.md"), input)).toBe(false);
+  });
+});
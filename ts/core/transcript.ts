@@ -0,0 +1,114 @@
+import path from "path";
+import { mkdir, writeFile } from "fs/promises";
+import { logger } from "../logger.ts";
+import type { PromptEvent } from "./events.ts";
+
+/**
+ * Markdown session transcript, behind `--transcript <path>`
+ *
+ * A human-readable summary of the session built from the final rendered
+ * output and the prompts agent-yes answered, meant to be pasted into a PR.
+ * Structure is inferred from the output: claude-style tool calls
+ * (`⏺ Bash(npm test)` followed by `⎿` lines) get their output fenced.
+ */
+
+export interface TranscriptInput {
+  cli: string;
+  prompt?: string;
+  output: string; // final rendered terminal text
+  prompts: PromptEvent[]; // prompts answered during the session
+  exitCode: number | null;
+  startedAt: Date;
+  endedAt?: Date;
+}
+
+const TOOL_CALL = /^⏺ (\w[\w-]*)\((.*)\)\s*$/; // e.g. "⏺ Bash(npm test)"
+const TOOL_OUTPUT = /^\s+⎿\s?(.*)$/; // first output line, following ones are indented
+
+/**
+ * Convert rendered agent output into Markdown
+ *
+ * @example
+ * ```typescript
+ * outputToMarkdown("⏺ Bash(npm test)\n  ⎿  ok\n⏺ Done");
+ * // "**Bash** `npm test`\n\n```\nok\n```\n\nDone"
+ * ```
+ */
+export function outputToMarkdown(output: string): string {
+  const blocks: string[] = [];
+  let fence: string[] | null = null;
+  const closeFence = () => {
+    if (fence?.length) blocks.push("```\n" + fence.join("\n") + "\n```");
+    fence = null;
+  };
+
+  for (const raw of output.split("\n")) {
+    const line = raw.trimEnd();
+    const tool = line.match(TOOL_CALL);
+    if (tool) {
+      closeFence();
+      blocks.push(`**${tool[1]}** \`${tool[2]}\``);
+      fence = [];
+      continue;
+    }
+    const toolOutput = line.match(TOOL_OUTPUT);
+    if (fence && toolOutput) {
+      fence.push(toolOutput[1]!.trimStart());
+      continue;
+    }
+    if (fence && /^\s{2,}\S/.test(line)) {
+      fence.push(line.replace(/^\s{5}/, "")); // continuation of the tool output
+      continue;
+    }
+    closeFence();
+    if (!line.trim()) continue;
+    blocks.push(line.replace(/^⏺ /, ""));
+  }
+  closeFence();
+  return blocks.join("\n\n");
+}
+
+/**
+ * Build the Markdown transcript of a session
+ */
+export function renderTranscript(input: TranscriptInput): string {
+  const { cli, prompt, output, prompts, exitCode, startedAt, endedAt = new Date() } = input;
+  const sections = [
+    `# ${cli}-yes session`,
+    [
+      `- Started: ${startedAt.toISOString()}`,
+      `- Duration: ${Math.round((endedAt.getTime() - startedAt.getTime()) / 1000)}s`,
+      `- Exit code: ${exitCode ?? "unknown"}`,
+    ].join("\n"),
+  ];
+  if (prompt) sections.push("## Prompt", prompt.replace(/^/gm, "> "));
+  if (prompts.length)
+    sections.push(
+      "## Answered prompts",
+      prompts
+        .map(({ line, action, response }) => {
+          const answer = action === "enter" ? "Enter" : JSON.stringify(response);
+          return `- **${line.trim()}** → ${answer}`;
+        })
+        .join("\n"),
+    );
+  sections.push("## Session", outputToMarkdown(output));
+  return sections.join("\n\n") + "\n";
+}
+
+/**
+ * Write the Markdown transcript to `filePath`, warning instead of throwing when it can't be written
+ * @returns Whether it was saved
+ */
+export async function saveTranscript(filePath: string | undefined, input: TranscriptInput): Promise<boolean> {
+  if (!filePath) return false;
+  try {
+    await mkdir(path.dirname(filePath), { recursive: true });
+    await writeFile(filePath, renderTranscript(input));
+  } catch (error) {
+    logger.warn(`Failed to save transcript to ${filePath}: ${String(error)}`);
+    return false;
+  }
+  logger.info(`Transcript saved to ${filePath}`);
+  return true;
+}
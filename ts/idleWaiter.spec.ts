@@ -26,6 +26,14 @@ describe("IdleWaiter", () => {
     expect(waiter.ping()).toBe(waiter);
   });
 
+  it("should report the time remaining until idle", () => {
+    const waiter = new IdleWaiter();
+    waiter.lastActivityTime = Date.now() - 300;
+    expect(waiter.remaining(1000)).toBeGreaterThan(600);
+    expect(waiter.remaining(1000)).toBeLessThanOrEqual(700);
+    expect(waiter.remaining(100)).toBe(0);
+  });
+
   it("should resolve wait immediately when already idle", async () => {
     const waiter = new IdleWaiter();
 
@@ -47,6 +55,11 @@ describe("IdleWaiter", () => {
     expect(waiter.checkInterval).toBe(200);
   });
 
+  it("should take the check interval in the constructor", () => {
+    expect(new IdleWaiter().checkInterval).toBe(100);
+    expect(new IdleWaiter(1000).checkInterval).toBe(1000);
+  });
+
   it("should have ping method that chains", () => {
     const waiter = new IdleWaiter();
     const result = waiter.ping().ping().ping();
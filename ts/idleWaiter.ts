@@ -12,18 +12,27 @@
  * console.log('System has been idle for 5 seconds');
  */
 export class IdleWaiter {
+  /** Timestamp (ms) of the last ping */
   lastActivityTime = Date.now();
-  checkInterval = 100; // Default check interval in milliseconds
-
-  constructor() {
+  /**
+   * @param checkInterval - How often wait() re-checks for idleness, in milliseconds, see --idle-poll-interval
+   */
+  constructor(public checkInterval = 100) {
     this.ping();
   }
 
+  /** Record activity now, returns this for chaining */
   ping() {
     this.lastActivityTime = Date.now();
     return this;
   }
 
+  /** Milliseconds left until `ms` without pings is reached, 0 once idle that long */
+  remaining(ms: number) {
+    return Math.max(0, this.lastActivityTime + ms - Date.now());
+  }
+
+  /** Resolve once there has been no ping for at least `ms` milliseconds */
   async wait(ms: number) {
     while (this.lastActivityTime >= Date.now() - ms)
       await new Promise((resolve) => setTimeout(resolve, this.checkInterval));
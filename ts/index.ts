@@ -1,15 +1,17 @@
+import { randomBytes } from "crypto";
 import { execaCommandSync, parseCommandString } from "execa";
-import { fromReadable, fromWritable } from "from-node-stream";
 import { mkdir, readFile, writeFile } from "fs/promises";
 import path from "path";
 import DIE from "phpdie";
 import sflow from "sflow";
 import { TerminalTextRender } from "terminal-render";
 import { getSessionForCwd } from "./resume/codexSessionManager.ts";
+import { claudeResumeArgs, getClaudeSessionForCwd } from "./resume/claudeSessionManager.ts";
 import pty, { ptyPackage } from "./pty.ts";
 import { removeControlCharacters } from "./removeControlCharacters.ts";
+import { Utf8StreamDecoder } from "./utf8Decoder.ts";
 import { acquireLock, releaseLock, shouldUseLock } from "./runningLock.ts";
-import { logger } from "./logger.ts";
+import { applyLogFilter, logger, setLogColors, type LogFilter } from "./logger.ts";
 import { createFifoStream } from "./beta/fifo.ts";
 import { PidStore } from "./pidStore.ts";
 import { SUPPORTED_CLIS } from "./SUPPORTED_CLIS.ts";
@@ -19,10 +21,74 @@ import {
   setupDebugLogging,
   saveLogFile,
   saveDeprecatedLogFile,
+  compactLogLines,
+  expandLogPathTemplate,
 } from "./core/logging.ts";
-import { spawnAgent, getTerminalDimensions } from "./core/spawner.ts";
+import { spawnAgent, getTerminalDimensions, withoutContinueArgs, RAW_PTY_OUTPUT } from "./core/spawner.ts";
 import { AgentContext } from "./core/context.ts";
-import { createAutoResponseHandler } from "./core/responders.ts";
+import {
+  continueFallback,
+  createAutoResponseHandler,
+  detectPrompt,
+  isPromptOnScreen,
+  respondToPrompt,
+  type PagerAction,
+  type PromptMatch,
+  type TrustMcpPolicy,
+} from "./core/responders.ts";
+import { killWithTimeout, processGroup } from "./core/shutdown.ts";
+import { EventLog } from "./core/events.ts";
+import { PromptRecorder } from "./core/promptRecorder.ts";
+import type { Answer, AnswerMap } from "./core/promptCategories.ts";
+import { followTerminalResize, type PtySize } from "./core/ptySize.ts";
+import {
+  checkExpectations,
+  checkFailPatterns,
+  formatExpectationReport,
+} from "./core/expectations.ts";
+import { saveTranscript } from "./core/transcript.ts";
+import { CastRecorder } from "./core/cast.ts";
+import { PromptDebouncer } from "./core/promptDebouncer.ts";
+import { startControlSocket } from "./core/controlSocket.ts";
+import { EXIT_CODE_OUTPUT_LIMIT, OutputLimit } from "./core/outputLimit.ts";
+import { runExitHook, type ExitReason } from "./core/exitHook.ts";
+import type { PromptEvent } from "./core/events.ts";
+import { defaultStdoutControlCharPolicy, type ControlCharPolicy } from "./core/controlChars.ts";
+import type { Profile } from "./core/profiles.ts";
+import type { OutputStream } from "./core/outputStream.ts";
+import { ConfirmGate } from "./core/confirmGate.ts";
+import { BUSY_MARKER, matchBusyMarker } from "./core/agentState.ts";
+import { createJitter } from "./core/jitter.ts";
+import { isPrintMode, runOneShot } from "./core/oneShot.ts";
+import { resolveColorMode, type ColorMode } from "./core/color.ts";
+import { resilientInput } from "./core/resilientInput.ts";
+import { SESSION_DIR_ENV } from "./core/stateDir.ts";
+import { OutputBatcher } from "./core/outputBatcher.ts";
+import { EXIT_CODE_PARENT_EXIT, watchParent } from "./core/parentWatchdog.ts";
+import { EXIT_CODE_MAX_RUNTIME, limitRuntime } from "./core/maxRuntime.ts";
+import { RestartPolicy } from "./core/restartPolicy.ts";
+import type { PickSessionPolicy } from "./core/sessionPicker.ts";
+import { loadPatternsFile, withUserPatterns, type UserPattern } from "./core/patternsFile.ts";
+import { openTerminalStdin } from "./core/promptStdin.ts";
+import { withGuardrail } from "./core/guardrail.ts";
+import { MeaningfulOutputFilter, type IdleMode } from "./core/meaningfulOutput.ts";
+import { withStderrLog } from "./core/splitStderr.ts";
+import { MAX_IDLE_NUDGES, waitIdleWithNudges } from "./core/idleNudge.ts";
+import { RateLimitWatcher } from "./core/rateLimit.ts";
+import { postNotification, runNotifyCommand } from "./core/notify.ts";
+import { summarizeSession, type SessionSummary } from "./core/sessionSummary.ts";
+import { createStreamLogFilter, StreamLog } from "./core/streamLog.ts";
+import { ManualConfirmation } from "./core/riskyPrompts.ts";
+import {
+  composeOutputFilters,
+  createControlCharFilter,
+  createLinePrefixer,
+  stripControlCharactersFilter,
+  stripColorsFilter,
+  stripLeading,
+  createLeadingStripper,
+  type OutputFilter,
+} from "./core/outputFilters.ts";
 import {
   handleConsoleControlCodes,
   createTerminateSignalHandler,
@@ -31,6 +97,21 @@ import {
 
 export { removeControlCharacters };
 
+// Building blocks for writing your own agent wrapper, see "Building Blocks" in README.md
+export { IdleWaiter } from "./idleWaiter.ts";
+export { ReadyManager } from "./ReadyManager.ts";
+export { TerminalTextRender } from "terminal-render";
+export { OutputStream } from "./core/outputStream.ts";
+export { replayOutput, type ReplayResult, type ReplayResponse } from "./core/replay.ts";
+export type { OutputFilter, SessionSummary };
+
+/** What a session ended with, see agentYes */
+export interface AgentYesResult {
+  exitCode: number | null;
+  logs: string; // the rendered output, as the session log has it
+  summary: SessionSummary; // exit reason, runtime, prompts auto-answered and crash restarts
+}
+
 export type AgentCliConfig = {
   // cli
   install?:
@@ -43,6 +124,7 @@ export type AgentCliConfig = {
   // status detect, and actions
   ready?: RegExp[]; // regex matcher for stdin ready, or line index for gemini
   fatal?: RegExp[]; // array of regex to match for fatal errors
+  rateLimit?: RegExp[]; // usage/rate-limit messages, waited out with --wait-on-rate-limit instead of exiting, see rateLimit.ts
   exitCommands?: string[]; // commands to exit the cli gracefully
   promptArg?: (string & {}) | "first-arg" | "last-arg"; // argument name to pass the prompt, e.g. --prompt, or first-arg for positional arg
 
@@ -52,6 +134,12 @@ export type AgentCliConfig = {
   // auto responds
   enter?: RegExp[]; // array of regex to match for sending Enter
   typingRespond?: { [message: string]: RegExp[] }; // type specified message to a specified pattern
+  banner?: RegExp; // startup banner, removed from the output start with --strip-banner
+  notices?: RegExp[]; // update/changelog notices waiting for a keypress, dismissed with Enter unless --no-auto-dismiss-notices
+  mcpTrust?: { detect: RegExp[]; yes: string; no: string }; // MCP server trust prompt, and what to type for --trust-mcp=yes|no
+  sessionPicker?: RegExp[]; // header of the --resume session list, answered per --pick-session, see sessionPicker.ts
+  patterns?: UserPattern[]; // answers from --patterns-file, checked before the built-ins, see patternsFile.ts
+  busy?: RegExp[]; // screen text meaning the cli is still working, e.g. "esc to interrupt", see agentState.ts
 
   // crash/resuming-session behaviour
   restoreArgs?: string[]; // arguments to continue the session when crashed
@@ -60,6 +148,7 @@ export type AgentCliConfig = {
 export type AgentYesConfig = {
   configDir?: string; // directory to store agent-yes config files, e.g. session store
   logsDir?: string; // directory to store agent-yes log files
+  profiles?: { [name: string]: Profile }; // user presets for --profile, keyed by profile name
   clis: { [key: string]: AgentCliConfig };
 };
 
@@ -77,11 +166,16 @@ export const CLIS_CONFIG = config.clis as Record<
  *   1. Shows message 'agent-cli crashed, restarting..'
  *   2. Spawns a new 'agent-cli --continue' process
  *   3. Re-attaches the new process to the shell stdio (pipes new process stdin/stdout)
- *   4. If it crashes with "No conversation found to continue", exits the process
+ *   4. If it crashes with "No conversation found to continue", starts a fresh session once (exits with continueFallbackFresh: false)
  * @param options.exitOnIdle - Exit when agent-cli is idle. Boolean or timeout in milliseconds, recommended 5000 - 60000, default is false
  * @param options.cliArgs - Additional arguments to pass to the agent-cli CLI
  * @param options.removeControlCharactersFromStdout - Remove ANSI control characters from stdout. Defaults to !process.stdout.isTTY
+ * @param options.cleanLogFile - Remove ANSI control sequences from the rendered log files, stdout keeps them unless removeControlCharactersFromStdout
+ * @param options.outputFilter - Transform each output chunk before it reaches stdout and the raw log, runs before removeControlCharactersFromStdout
+ * @param options.outputStream - Receives the same output chunks as stdout, consume it with `for await`; combine with `passthrough: false` to not print
  * @param options.disableLock - Disable the running lock feature that prevents concurrent agents in the same directory/repo
+ * @param options.tty - Put a terminal stdin into raw mode for interactive use, like the cli does. Defaults to false
+ * @returns The exit code, the rendered output and a summary of the session
  *
  * @example
  * ```typescript
@@ -102,14 +196,91 @@ export default async function agentYes({
   cli,
   cliArgs = [],
   prompt,
+  guardrail,
+  typePrompt,
   robust = true,
   cwd,
   env,
   exitOnIdle,
   logFile,
+  streamLog,
+  logTimestamps = false,
+  logAppend = false,
   removeControlCharactersFromStdout = false, // = !process.stdout.isTTY,
+  cleanLogFile = false,
+  outputFilter,
+  outputStream,
+  jsonLog,
+  eventsFd,
+  trustMcp = "no",
+  prefix,
+  killTimeout = 5000,
+  answers = {},
+  defaultAnswer = "yes",
+  menuResponse,
+  selectLabel,
+  ynResponse,
+  simulateTyping = 0,
+  answerDelayJitter = 0,
+  jitterSeed,
+  recordPrompts,
+  recordPromptsMax = 20,
+  ptySize,
+  expect = [],
+  failOn = [],
+  transcript,
+  castFile,
+  passthrough = true,
+  promptDebounce = 100,
+  responseDelay = 400,
+  idlePollInterval = 100,
+  readyTimeout = 10000,
+  stdoutFlushInterval = 8,
+  maxPromptAge = 0,
+  onlyAnswer,
+  logFilter,
+  controlChars = {},
+  gracefulIdleExit = 5000,
+  controlSocket,
+  statusPort,
+  killOnParentExit = false,
+  handleSignals = false,
+  maxRuntime = 0,
+  maxRestarts = Infinity,
+  restartBackoff = 0,
+  idleIgnoreSpinners = false,
+  idleMode = idleIgnoreSpinners ? "stable-screen" : "output-silence",
+  busyMarkers = [],
+  splitStderr = false,
+  nudgeOnIdle = false,
+  waitOnRateLimit = false,
+  dryRun = false,
+  command,
+  patternsFile,
+  patternsCacheTtl,
+  pagerAction = "space",
+  pickSession,
+  autoDismissNotices = true,
+  autoTypeConfirmations = false,
+  compactLogs = false,
+  confirmDangerousOnce = false,
+  confirmDestructive = false,
+  color,
+  stripPrefix,
+  stripBanner = false,
+  maxSessionBytes,
+  stdin = process.stdin,
+  tty = false,
+  continueFallbackFresh = true,
+  inputGating = true,
+  onExit: exitHookCommand, // not to be confused with the shell's onExit handler
+  onExitAffectsCode = false,
+  notifyUrl,
+  notifyCommand,
   verbose = false,
+  quiet = false,
   queue = false,
+  sessionDir,
   install = false,
   resume = false,
   useSkills = false,
@@ -118,29 +289,128 @@ export default async function agentYes({
   cli: SUPPORTED_CLIS;
   cliArgs?: string[];
   prompt?: string;
+  guardrail?: string; // instruction sent ahead of the prompt and again after crash restarts, see guardrail.ts
+  typePrompt?: string; // typed into the input box once the agent awaits input, instead of passed as an argument
   robust?: boolean;
   cwd?: string;
   env?: Record<string, string>;
   exitOnIdle?: number;
-  logFile?: string;
+  logFile?: string; // rendered log path, may contain {timestamp}, {pid}, {cwd} and {session}, see expandLogPathTemplate
+  streamLog?: string; // plain text log appended as output arrives, same placeholders as logFile
+  logTimestamps?: boolean; // prefix each streamLog line with an RFC 3339 timestamp
+  logAppend?: boolean; // append to an existing streamLog instead of truncating it
   removeControlCharactersFromStdout?: boolean;
+  cleanLogFile?: boolean; // remove ANSI control sequences from the rendered logs, independent of removeControlCharactersFromStdout
+  outputFilter?: OutputFilter; // user hook to transform output chunks, e.g. highlighting or redaction
+  outputStream?: OutputStream; // async-iterable copy of the output for embedders, ends with the session
+  jsonLog?: string; // path to write newline-delimited JSON events, see docs/json-log.md
+  eventsFd?: number; // write the JSON events to this inherited file descriptor instead, e.g. 3 for `3>&1` or a pipe
+  trustMcp?: TrustMcpPolicy; // how to answer MCP server trust prompts, defaults to "no"
+  prefix?: string; // label prepended to each stdout line, for telling apart merged output of many agents
+  killTimeout?: number; // milliseconds to wait after SIGINT/SIGTERM before escalating to SIGKILL
+  answers?: AnswerMap; // per-category answers, e.g. { delete: "no" }, unlisted categories get defaultAnswer
+  defaultAnswer?: Answer; // "no" declines menus and answers y/n prompts "n" unless answers says yes for the category
+  menuResponse?: string; // keys sent to accept enter prompts, default "\r"
+  selectLabel?: string; // accept enter prompts by picking the menu option with this label, e.g. "Yes", see menuSelect.ts
+  ynResponse?: string; // typed instead of "y\n" for y/n prompts, e.g. "yes\n"
+  simulateTyping?: number; // ms between characters when typing prompts and responses, 0 to write at once
+  answerDelayJitter?: number; // up to this many random extra ms before each auto-response, 0 for none
+  jitterSeed?: number; // seed for answerDelayJitter, for reproducible timing
+  recordPrompts?: string; // directory to save the raw output around each detected prompt, as test fixtures
+  recordPromptsMax?: number; // max fixtures to save per run
+  ptySize?: PtySize; // fixed pty size instead of the real terminal size, for deterministic CI runs
+  expect?: RegExp[]; // all must match the final rendered output, otherwise exit code is 1
+  failOn?: RegExp[]; // if any matches the final rendered output, exit code is 1 even if the agent exited cleanly
+  transcript?: string; // path to write a Markdown transcript of the session
+  castFile?: string; // path to record the session to as an asciicast v2 file, output and auto-responses
+  passthrough?: boolean; // if false, don't print the agent output, logs and auto-responses still work
+  promptDebounce?: number; // ms the output must be quiet before answering a detected prompt, 0 to answer immediately
+  responseDelay?: number; // ms the output must be idle before a prompt is answered with Enter
+  idlePollInterval?: number; // ms between checks while waiting for idle output
+  readyTimeout?: number; // ms to hold input for the agent to get ready, after which it is sent anyway
+  maxPromptAge?: number; // only answer prompts within this many lines of the bottom of the screen, 0 for no limit
+  stdoutFlushInterval?: number; // ms to batch stdout writes for, 0 writes each chunk right away
+  onlyAnswer?: RegExp[]; // only auto-answer prompts matching one of these, everything else is left to the user
+  logFilter?: LogFilter; // per-module log levels, see parseLogFilter
+  controlChars?: ControlCharPolicy; // forward or drop BEL & co. on stdout, e.g. { bel: "drop" }, logs always drop them
+  gracefulIdleExit?: number; // ms to wait for the agent to exit by itself after the exit command on idle, 0 to kill right away
+  controlSocket?: string; // unix socket path for injecting prompts from other local processes, see docs/control-socket.md
+  statusPort?: number; // serve a JSON status snapshot on http://127.0.0.1:<port>/status, 0 picks a free port
+  killOnParentExit?: boolean; // stop the agent and exit with 129 when the parent process is gone
+  handleSignals?: boolean; // on SIGTERM/SIGINT, stop the agent's process group and end the session as usual (logs, hooks), off by default for embedding
+  maxRestarts?: number; // give up after this many crash restarts per session, unlimited by default
+  restartBackoff?: number; // ms to wait before the first crash restart, doubled for each further one
+  maxRuntime?: number; // ms after which the agent is stopped and agent-yes exits with 124, regardless of output, 0 for no limit
+  patternsFile?: string; // path or https:// URL of extra auto-response patterns (JSON or TOML), see patternsFile.ts
+  patternsCacheTtl?: number; // ms a fetched patterns URL is used from the cache before refetching, an hour by default
+  command?: string; // binary to run instead of the cli's, e.g. a pinned "claude-1.2" symlink, patterns still follow `cli`
+  nudgeOnIdle?: boolean; // press Enter (a few times at most) when idle without a busy marker, before exiting on idle
+  dryRun?: boolean; // detect and log prompts (events, --record-prompts) without answering them, stdin still works
+  waitOnRateLimit?: boolean; // on a usage/rate-limit message, wait for the reset and continue instead of exiting, see rateLimit.ts
+  splitStderr?: boolean; // run the agent with stderr on a log file instead of the pty, see splitStderr.ts
+  idleIgnoreSpinners?: boolean; // only count output that changes the screen beyond spinner frames as activity for the idle timer
+  idleMode?: IdleMode; // "stable-screen" is the same as idleIgnoreSpinners, "output-silence" (default) counts any output
+  busyMarkers?: RegExp[]; // more screen text meaning the agent is still working, added to AgentCliConfig.busy
+  pagerAction?: PagerAction; // how to answer pager prompts like "--More--" from commands the agent runs
+  pickSession?: PickSessionPolicy; // entry to pick when --resume shows a session list, left to the user when unset
+  autoDismissNotices?: boolean; // press Enter on update/changelog notices, see AgentCliConfig.notices
+  autoTypeConfirmations?: boolean; // type the word of "type DELETE to confirm" prompts, instead of leaving them to the user
+  compactLogs?: boolean; // collapse repeated (spinner) lines in the saved rendered logs
+  stripPrefix?: RegExp; // removed once from the start of the output and logs, e.g. a banner
+  stripBanner?: boolean; // remove the cli's known startup banner (AgentCliConfig.banner) from the output and logs
+  color?: ColorMode; // "never" strips colors from the output and logs, unset follows NO_COLOR
+  confirmDangerousOnce?: boolean; // ask the user once per dangerous category (e.g. delete) instead of accepting, declines without a terminal
+  confirmDestructive?: boolean; // never answer prompts about destructive actions (rm -rf, force push, ...), the user answers them, see riskyPrompts.ts
+  maxSessionBytes?: number; // stop the agent with exit code 3 once its raw output exceeds this many bytes
+  stdin?: NodeJS.ReadStream; // interactive input, e.g. the terminal after the prompt was read from piped stdin
+  tty?: boolean; // put a terminal stdin into raw mode, off by default so embedding doesn't touch the terminal; the cli turns it on unless --no-tty
+  continueFallbackFresh?: boolean; // start a fresh session when --continue finds no conversation, instead of exiting
+  inputGating?: boolean; // false sends input and responses right away, without waiting for the agent to be ready
+  onExit?: string; // shell command run after the agent exited, with AGENT_YES_EXIT_CODE, AGENT_YES_REASON, ... set
+  onExitAffectsCode?: boolean; // if the onExit command fails, use its exit code
+  notifyUrl?: string; // POST a JSON summary (exit code, reason, runtime, responses) here when the session ends, best-effort
+  notifyCommand?: string; // shell command run with the summary as AGENT_YES_* env vars when the session ends, best-effort
   verbose?: boolean;
+  quiet?: boolean; // only the agent's output: no hints or info logs, just warnings and errors
   queue?: boolean;
+  sessionDir?: string; // where to keep agent-yes state (codex sessions, caches, the queue lock), see stateDir.ts
   install?: boolean; // if true, install the cli tool if not installed, e.g. will run `npm install -g cursor-agent`
   resume?: boolean; // if true, resume previous session in current cwd if any
   useSkills?: boolean; // if true, prepend SKILL.md header to the prompt for non-Claude agents
   useFifo?: boolean; // if true, enable FIFO input stream on Linux for additional stdin input
-}) {
+}): Promise<AgentYesResult> {
   if (!cli) throw new Error(`cli is required`);
-  const conf =
+  if (logFilter) applyLogFilter({ level: verbose ? "debug" : quiet ? "warn" : undefined, ...logFilter }); // --verbose stays a shortcut for the default level
+  else if (quiet) logger.level = "warn";
+  const startedAt = new Date();
+  const userPrompt = prompt; // before skills are prepended, for the transcript
+  const logPathVars = { startedAt, pid: process.pid, cwd: cwd ?? process.cwd(), session: randomBytes(4).toString("hex") };
+  const logFilePath = logFile ? expandLogPathTemplate(logFile, logPathVars) : undefined;
+  // --stream-log: plain text output appended as it arrives, survives a killed run, see streamLog.ts
+  const streamLogWriter = streamLog
+    ? new StreamLog(path.resolve(expandLogPathTemplate(streamLog, logPathVars)), {
+        append: logAppend,
+        filter: composeOutputFilters(outputFilter, createStreamLogFilter({ timestamps: logTimestamps })),
+      })
+    : undefined;
+  const colorMode = resolveColorMode(color, env ?? process.env); // explicit --color wins over NO_COLOR
+  if (colorMode !== "auto") setLogColors(colorMode === "always");
+  const builtinConf =
     CLIS_CONFIG[cli] ||
     DIE(`Unsupported cli tool: ${cli}, current process.argv: ${process.argv.join(" ")}`);
+  const userPatterns = patternsFile ? await loadPatternsFile(patternsFile, { ttlMs: patternsCacheTtl }) : null;
+  const conf = userPatterns ? withUserPatterns(builtinConf, userPatterns) : builtinConf;
+  const leadingPatterns = [stripBanner && conf.banner, stripPrefix].filter((p): p is RegExp => !!p);
+  if (stripBanner && !conf.banner) logger.warn(`[${cli}-yes] --strip-banner: no known banner for ${cli}`);
+
+  if (sessionDir) process.env[SESSION_DIR_ENV] = path.resolve(sessionDir); // read by the state files, and inherited by child sessions
 
   // Acquire lock before starting agent (if in git repo or same cwd and lock is not disabled)
   const workingDir = cwd ?? process.cwd();
+  let forwardingSignals = false; // handleSignals took over SIGTERM/SIGINT, see below
   if (queue) {
     if (queue && shouldUseLock(workingDir)) {
-      await acquireLock(workingDir, prompt ?? "Interactive session");
+      await acquireLock(workingDir, prompt ?? typePrompt ?? "Interactive session");
     }
 
     // Register cleanup handlers for lock release
@@ -155,11 +425,11 @@ export default async function agentYes({
     });
     process.on("SIGINT", async (code) => {
       await cleanupLock();
-      process.exit(code);
+      if (!forwardingSignals) process.exit(code);
     });
     process.on("SIGTERM", async (code) => {
       await cleanupLock();
-      process.exit(code);
+      if (!forwardingSignals) process.exit(code);
     });
   }
 
@@ -167,7 +437,8 @@ export default async function agentYes({
   const pidStore = new PidStore(workingDir);
   await pidStore.init();
 
-  process.stdin.setRawMode?.(true); // must be called any stdout/stdin usage
+  const interactive = tty && !!stdin.isTTY; // raw mode and terminal replies only for a real, wanted terminal
+  if (interactive) stdin.setRawMode?.(true); // must be called any stdout/stdin usage
 
   const shellOutputStream = new TransformStream<string, string>();
   const outputWriter = shellOutputStream.writable.getWriter();
@@ -180,7 +451,12 @@ export default async function agentYes({
     logger.info(`[${cli}-yes] Running as sub-agent (CLAUDE_PPID=${process.env.CLAUDE_PPID})`);
 
   // Apply CLI specific configurations (moved to CLI_CONFIGURES)
-  const cliConf = (CLIS_CONFIG as Record<string, AgentCliConfig>)[cli] || {};
+  const cliConf: AgentCliConfig = {
+    ...(CLIS_CONFIG as Record<string, AgentCliConfig>)[cli],
+    ...(command && { binary: command }), // --command runs another binary with the cli's patterns
+  };
+  if (busyMarkers.length) cliConf.busy = [...(cliConf.busy ?? [BUSY_MARKER]), ...busyMarkers]; // --busy-marker
+
   cliArgs = cliConf.defaultArgs ? [...cliConf.defaultArgs, ...cliArgs] : cliArgs;
 
   // If enabled, read SKILL.md header and prepend to the prompt for non-Claude agents
@@ -248,6 +524,9 @@ export default async function agentYes({
     if (verbose) logger.warn("[skills] Failed to inject SKILL.md header:", { error });
   }
 
+  // --guardrail leads everything else the agent reads
+  prompt = withGuardrail(prompt, guardrail);
+
   // Handle --continue flag for codex session restoration
   if (resume) {
     if (cli === "codex" && resume) {
@@ -263,9 +542,10 @@ export default async function agentYes({
         );
       }
     } else if (cli === "claude") {
-      // just add --continue flag for claude
-      cliArgs = ["--continue", ...cliArgs];
-      await logger.debug(`resume|adding --continue flag for claude`);
+      // resume this directory's session, or the most recent one with --continue
+      const resumeArgs = claudeResumeArgs(await getClaudeSessionForCwd(workingDir));
+      cliArgs = [...resumeArgs, ...cliArgs];
+      await logger.debug(`resume|adding ${resumeArgs.join(" ")} for claude`);
     } else if (cli === "gemini") {
       // Gemini supports session resume natively via --resume flag
       // Sessions are project/directory-specific by default (stored in ~/.gemini/tmp/<project_hash>/chats/)
@@ -296,19 +576,98 @@ export default async function agentYes({
 
   // Spawn the agent CLI process
   const ptyEnv = { ...(env ?? (process.env as Record<string, string>)) };
+  ptyEnv.PAGER ??= "cat"; // avoid pagers in commands the agent runs, unless the user picked one
+  ptyEnv.GIT_PAGER ??= "cat";
+  if (colorMode === "never") ptyEnv.NO_COLOR = "1"; // ask the agent not to color either
+  if (colorMode === "always") delete ptyEnv.NO_COLOR;
+
+  const pendingExitCode = Promise.withResolvers<number | null>();
+  let sessionEndReason: ExitReason | undefined; // set when agent-yes ends the session itself, for --on-exit
+  let stopAgent: (signal?: NodeJS.Signals) => Promise<unknown> = async () => {}; // set once the agent is spawned, pty or print mode
+
+  // --kill-on-parent-exit: don't outlive a cancelled CI job or dropped SSH session
+  if (killOnParentExit) {
+    const stopWatchdog = watchParent((parentPid) => {
+      logger.warn(`[${cli}-yes] parent process ${parentPid} exited, stopping ${cli}`);
+      sessionEndReason ??= "parent-exit";
+      pendingExitCode.resolve(EXIT_CODE_PARENT_EXIT);
+      stopAgent();
+    });
+    pendingExitCode.promise.finally(stopWatchdog);
+  }
+
+  if (maxRuntime) {
+    const stopRuntimeLimit = limitRuntime(maxRuntime, () => {
+      logger.warn(`[${cli}-yes] --max-runtime of ${maxRuntime}ms reached, stopping ${cli}`);
+      sessionEndReason ??= "timeout";
+      pendingExitCode.resolve(EXIT_CODE_MAX_RUNTIME);
+      stopAgent();
+    });
+    pendingExitCode.promise.finally(stopRuntimeLimit);
+  }
+
+  // SIGTERM/SIGINT to agent-yes (systemctl stop, docker stop): don't orphan the agent, stop it and end the session as usual
+  if (handleSignals) {
+    const onStopSignal = (signal: NodeJS.Signals) => {
+      logger.warn(`[${cli}-yes] received ${signal}, stopping ${cli}`);
+      sessionEndReason ??= "interrupted";
+      if (interactive) stdin.setRawMode?.(false); // restore terminal before quitting
+      stopAgent(signal); // exits with the agent's code
+    };
+    process.on("SIGTERM", onStopSignal).on("SIGINT", onStopSignal);
+    forwardingSignals = true;
+    pendingExitCode.promise.finally(() => process.off("SIGTERM", onStopSignal).off("SIGINT", onStopSignal));
+  }
+
+  // one-shot print mode (claude --print): no pty and no prompts, just the whole answer and the exact exit code
+  if (isPrintMode(cli, cliArgs)) {
+    stdin.setRawMode?.(false);
+    const stripChunk = createLeadingStripper(leadingPatterns);
+    const events = new EventLog(eventsFd ?? jsonLog ?? false, { prefix });
+    const cast = castFile ? new CastRecorder(path.resolve(castFile), getTerminalDimensions(ptySize)) : undefined;
+    const emit = (chunk: string) => {
+      outputStream?.push(chunk);
+      streamLogWriter?.write(chunk);
+      if (passthrough) process.stdout.write(chunk);
+    };
+    const { exitCode: agentExitCode, output } = await runOneShot({
+      cli,
+      cliConf,
+      cliArgs,
+      cwd: workingDir,
+      env: ptyEnv,
+      onSpawn: (child) => {
+        stopAgent = (signal) =>
+          killWithTimeout(signal ? processGroup(child) : child, { signal, timeout: killTimeout }).catch(() => null);
+      },
+      write: (rawChunk) => {
+        cast?.output(rawChunk);
+        emit(stripChunk(rawChunk));
+      },
+    });
+    emit(stripChunk.flush()); // a banner-like start that never completed
+    pendingExitCode.resolve(agentExitCode); // unless --max-runtime or --kill-on-parent-exit came first
+    outputStream?.end();
+    const logs = stripLeading(output, leadingPatterns);
+    return finishSession({ exitCode: await pendingExitCode.promise, output: logs, logs, events, cast, isFatal: false });
+  }
+
   const ptyOptions = {
     name: "xterm-color",
-    ...getTerminalDimensions(),
+    ...getTerminalDimensions(ptySize),
     cwd: cwd ?? process.cwd(),
     env: ptyEnv,
+    ...(RAW_PTY_OUTPUT && { encoding: null }),
   };
 
+  const stderrLogDir = splitStderr ? pidStore.getLogDir() : undefined;
   let shell = spawnAgent({
     cli,
     cliConf,
     cliArgs,
     verbose,
     install,
+    stderrLogDir,
     ptyOptions,
   });
 
@@ -316,6 +675,8 @@ export default async function agentYes({
   await pidStore.registerProcess({ pid: shell.pid, cli, args: cliArgs, prompt });
   const logPaths = initializeLogPaths(pidStore, shell.pid);
   setupDebugLogging(logPaths.debuggingLogsPath);
+  if (splitStderr && process.platform === "win32") logger.warn(`[${cli}-yes] --split-stderr is not supported on Windows`);
+  else if (splitStderr) logger.info(`[${cli}-yes] ${cli} stderr goes to ${logPaths.stderrLogPath}`);
 
   // Create agent context
   const ctx = new AgentContext({
@@ -326,58 +687,111 @@ export default async function agentYes({
     cliConf,
     verbose,
     robust,
+    jsonLogPath: jsonLog,
+    eventsFd,
+    idlePollInterval,
+    prefix,
   });
+  ctx.typingDelay = simulateTyping;
+  ctx.nextAnswerJitter = createJitter(answerDelayJitter, jitterSeed);
+  ctx.inputGating = inputGating;
+  ctx.dryRun = dryRun;
+  ctx.responseDelay = responseDelay;
+  // --cast-file: a recording to play back with `asciinema play`, see cast.ts
+  if (castFile) ctx.cast = new CastRecorder(path.resolve(castFile), { cols: shell.cols, rows: shell.rows });
+  if (dryRun) logger.warn(`[${cli}-yes] --dry-run: prompts are detected and logged, but not answered, answer them yourself`);
+  if (!inputGating)
+    logger.warn(`[${cli}-yes] --no-input-gating: input is sent without waiting for ${cli} to be ready, it may get lost or garbled`);
+  const promptRecorder = recordPrompts
+    ? new PromptRecorder(recordPrompts, cli, recordPromptsMax)
+    : undefined;
+
+  ctx.readyTimeout = readyTimeout;
+
+  // force ready after --ready-timeout to avoid stuck forever if the ready-word mismatched, re-armed on every (re)spawn
+  let readyTimer: ReturnType<typeof setTimeout> | undefined;
+  const armReadyTimeout = () => {
+    clearTimeout(readyTimer);
+    readyTimer = setTimeout(() => {
+      if (!ctx.stdinReady.isReady) ctx.stdinReady.ready();
+      if (!ctx.stdinFirstReady.isReady) ctx.stdinFirstReady.ready();
+      ctx.agentState.force();
+    }, readyTimeout);
+    readyTimer.unref?.();
+  };
+  armReadyTimeout();
 
-  // force ready after 10s to avoid stuck forever if the ready-word mismatched
-  sleep(10e3).then(() => {
-    if (!ctx.stdinReady.isReady) ctx.stdinReady.ready();
-    if (!ctx.stdinFirstReady.isReady) ctx.stdinFirstReady.ready();
-  });
-
-  const pendingExitCode = Promise.withResolvers<number | null>();
+  const restartPolicy = new RestartPolicy(maxRestarts, restartBackoff); // --max-restarts, --restart-backoff
+  stopAgent = (signal) => {
+    ctx.robust = false; // no restart
+    return killWithTimeout(signal ? processGroup(shell) : shell, { signal, timeout: killTimeout }).catch(() => null);
+  };
 
-  async function onData(data: string) {
+  let outputChunkCount = 0; // chunks written to shellOutputStream, so the terminator can drain them on exit
+  const outputLimit = maxSessionBytes
+    ? new OutputLimit(maxSessionBytes, (bytes) => {
+        logger.error(`[${cli}-yes] output exceeded --max-session-bytes (${bytes} > ${maxSessionBytes} bytes), stopping ${cli}`);
+        sessionEndReason ??= "output-limit";
+        pendingExitCode.resolve(EXIT_CODE_OUTPUT_LIMIT);
+        stopAgent();
+      })
+    : undefined;
+
+  let outputDecoder = new Utf8StreamDecoder(); // one per agent, with RAW_PTY_OUTPUT the pty gives bytes
+  async function onData(data: string | Uint8Array) {
+    const text = typeof data === "string" ? data : outputDecoder.write(data);
+    if (!text) return; // only part of a char so far
+    outputLimit?.add(text); // count raw bytes, before rendering
     // append data to the buffer, so we can process it later
-    await outputWriter.write(data);
+    outputChunkCount++;
+    await outputWriter.write(text);
   }
 
   shell.onData(onData);
   shell.onExit(async function onExit({ exitCode }) {
+    const tail = outputDecoder.end(); // a char the agent didn't finish, as "�"
+    outputDecoder = new Utf8StreamDecoder();
+    if (tail) await onData(tail);
     ctx.stdinReady.unready(); // start buffer stdin
+    ctx.agentState.reset();
     const agentCrashed = exitCode !== 0;
 
     // Handle restart without continue args (e.g., "No conversation found to continue")
-    // logger.debug(``, { shouldRestartWithoutContinue, robust })
-    if (ctx.shouldRestartWithoutContinue) {
+    const fallback = continueFallback(ctx, continueFallbackFresh);
+    if (fallback === "exit")
+      logger.error(`[${cli}-yes] No conversation found to continue, not starting a fresh one (--no-continue-fallback-fresh)`);
+    if (fallback === "fresh") {
+      continueFallbackFresh = false; // only once, a fresh session has nothing to continue anyway
       await pidStore.updateStatus(shell.pid, "exited", {
         exitReason: "restarted",
         exitCode: exitCode ?? undefined,
       });
-      ctx.shouldRestartWithoutContinue = false; // reset flag
-      ctx.isFatal = false; // reset fatal flag to allow restart
 
       // Restart without continue args - use original cliArgs without restoreArgs
       const cliCommand = cliConf?.binary || cli;
       let [bin, ...args] = [
         ...parseCommandString(cliCommand),
-        ...cliArgs.filter((arg) => !["--continue", "--resume"].includes(arg)),
+        ...withoutContinueArgs(cliArgs),
       ];
       logger.info(`Restarting ${cli} ${JSON.stringify([bin, ...args])}`);
 
       const restartPtyOptions = {
         name: "xterm-color",
-        ...getTerminalDimensions(),
+        ...getTerminalDimensions(ptySize),
         cwd: cwd ?? process.cwd(),
         env: ptyEnv,
+        ...(RAW_PTY_OUTPUT && { encoding: null }),
       };
-      shell = pty.spawn(bin!, args, restartPtyOptions);
+      shell = pty.spawn(...withStderrLog(bin!, args, stderrLogDir), restartPtyOptions);
+      ctx.shell = shell; // messages and responses go to the new agent
+      armReadyTimeout();
       await pidStore.registerProcess({ pid: shell.pid, cli, args, prompt });
       shell.onData(onData);
       shell.onExit(onExit);
       return;
     }
 
-    if (agentCrashed && robust && conf?.restoreArgs) {
+    if (agentCrashed && ctx.robust && conf?.restoreArgs) {
       if (!conf.restoreArgs) {
         logger.warn(
           `robust is only supported for ${Object.entries(CLIS_CONFIG)
@@ -395,14 +809,30 @@ export default async function agentYes({
         return pendingExitCode.resolve(exitCode);
       }
 
+      const restart = restartPolicy.next();
+      if (!restart) {
+        logger.error(`[${cli}-yes] ${cli} crashed again after ${maxRestarts} restarts, giving up (--max-restarts)`);
+        await pidStore.updateStatus(shell.pid, "exited", { exitReason: "crash", exitCode: exitCode ?? undefined });
+        return pendingExitCode.resolve(exitCode);
+      }
       await pidStore.updateStatus(shell.pid, "exited", {
         exitReason: "restarted",
         exitCode: exitCode ?? undefined,
       });
-      logger.info(`${cli} crashed, restarting...`);
-
-      // For codex, try to use stored session ID for this directory
+      const limit = Number.isFinite(maxRestarts) ? `/${maxRestarts}` : "";
+      const delay = restart.delayMs ? ` in ${restart.delayMs}ms` : "";
+      logger.info(`${cli} crashed, restarting${delay} (attempt ${restart.attempt}${limit})...`);
+      ctx.events.emit({ type: "crash_restart", exitCode, attempt: restart.attempt, delayMs: restart.delayMs });
+      if (restart.delayMs) await sleep(restart.delayMs);
+      if (!ctx.robust) return pendingExitCode.resolve(exitCode); // the session ended while backing off
+
+      // For codex and claude, try to use stored session ID for this directory
       let restoreArgs = conf.restoreArgs;
+      if (cli === "claude") {
+        const storedSessionId = await getClaudeSessionForCwd(workingDir);
+        if (storedSessionId) restoreArgs = claudeResumeArgs(storedSessionId);
+        logger.debug(`restore|${storedSessionId ? `using stored session ID: ${storedSessionId}` : "no stored session, using default restore args"}`);
+      }
       if (cli === "codex") {
         const storedSessionId = await getSessionForCwd(workingDir);
         if (storedSessionId) {
@@ -416,14 +846,22 @@ export default async function agentYes({
 
       const restorePtyOptions = {
         name: "xterm-color",
-        ...getTerminalDimensions(),
+        ...getTerminalDimensions(ptySize),
         cwd: cwd ?? process.cwd(),
         env: ptyEnv,
+        ...(RAW_PTY_OUTPUT && { encoding: null }),
       };
-      shell = pty.spawn(cli, restoreArgs, restorePtyOptions);
+      const [restoreBin, ...restoreBinArgs] = parseCommandString(cliConf.binary || cli);
+      shell = pty.spawn(
+        ...withStderrLog(restoreBin!, [...restoreBinArgs, ...restoreArgs], stderrLogDir),
+        restorePtyOptions,
+      );
+      ctx.shell = shell; // messages and responses go to the new agent
+      armReadyTimeout();
       await pidStore.registerProcess({ pid: shell.pid, cli, args: restoreArgs, prompt });
       shell.onData(onData);
       shell.onExit(onExit);
+      if (guardrail) void sendMessage(ctx.messageContext, guardrail); // the restored session gets it again, once ready
       return;
     }
     const exitReason = agentCrashed ? "crash" : "normal";
@@ -434,47 +872,161 @@ export default async function agentYes({
     return pendingExitCode.resolve(exitCode);
   });
 
-  // when current tty resized, resize the pty too
-  process.stdout.on("resize", () => {
-    const { cols, rows } = getTerminalDimensions(); // minimum 80 columns to avoid layout issues
-    shell.resize(cols, rows); // minimum 80 columns to avoid layout issues
-  });
+  // when current tty resized (SIGWINCH), resize the pty too, `shell` is the restarted one after a crash
+  if (!ptySize) {
+    const stopFollowingResize = followTerminalResize(
+      ({ cols, rows }) => shell.resize(cols, rows),
+      () => getTerminalDimensions(),
+    );
+    pendingExitCode.promise.finally(stopFollowingResize);
+  }
 
   const terminalRender = new TerminalTextRender();
-  const isStillWorkingQ = () =>
-    terminalRender
-      .render()
-      .replace(/\s+/g, " ")
-      .match(/esc to interrupt|to run in background/);
+  const meaningfulOutput = idleMode === "stable-screen" ? new MeaningfulOutputFilter() : undefined;
+
+  // --confirm-dangerous-once: ask on the terminal (stderr + stdin keys) before accepting e.g. delete prompts
+  const confirmGate = confirmDangerousOnce
+    ? new ConfirmGate(interactive, (text) => process.stderr.write(text))
+    : undefined;
+  // --confirm-destructive: risky prompts are left to the user, who answers the agent on stdin
+  const manualConfirmation = confirmDestructive
+    ? new ManualConfirmation((text) => process.stderr.write(text), () => ctx.idleWaiter.ping())
+    : undefined;
+  pendingExitCode.promise.finally(() => manualConfirmation?.release());
+
+  // answer prompts only once the screen stopped changing, and the prompt is still on it
+  const detectOptions = {
+    trustMcp,
+    autoDismissNotices,
+    autoTypeConfirmations,
+    answers,
+    defaultAnswer,
+    menuResponse,
+    selectLabel,
+    ynResponse,
+    onlyAnswer,
+    pagerAction,
+    pickSession,
+    get wrapWidth() {
+      return shell.cols; // read on each use, follows resizes and restarts
+    },
+  };
+  const promptDebouncer = promptDebounce && inputGating
+    ? new PromptDebouncer<{ match: PromptMatch; line: string }>(promptDebounce, ({ match, line }) => {
+        const screen = terminalRender.render();
+        if (!isPromptOnScreen(match, screen, conf, detectOptions))
+          return logger.debug(`skip  |${match.rule}|prompt changed before the screen settled|${line}`);
+        return respondToPrompt(match, line, { ctx, promptRecorder, confirmGate });
+      })
+    : undefined;
+  const rateLimitWatcher = waitOnRateLimit ? new RateLimitWatcher(conf.rateLimit ?? []) : undefined;
+  const isStillWorkingQ = () => matchBusyMarker(terminalRender.render(), cliConf.busy);
+
+  // a prompt the patterns recognize is still shown: it was withheld by the answer policies, Enter must not accept it
+  const isKnownPromptShown = () => {
+    const bottom = terminalRender.render().trimEnd().split("\n").slice(-12);
+    const screen = bottom.join("\n");
+    return bottom.some((line) => detectPrompt(line, conf, { screen, wrapWidth: shell.cols }) !== null);
+  };
+  // nudging would answer prompts --dry-run and --only-answer leave to the user
+  const nudging = nudgeOnIdle && !dryRun && !onlyAnswer;
+  if (nudgeOnIdle && !nudging) logger.warn(`[${cli}-yes] --nudge-on-idle is off with --dry-run and --only-answer`);
+  const waitForIdle = (ms: number) =>
+    nudging
+      ? waitIdleWithNudges(ctx.idleWaiter, ms, {
+          isBusy: () => Boolean(isStillWorkingQ()),
+          isPromptShown: isKnownPromptShown,
+          nudge: (count) => {
+            logger.info(
+              `[${cli}-yes] ${cli} is idle without a busy marker or a known prompt, pressing Enter in case of a missed prompt (${count}/${MAX_IDLE_NUDGES})`,
+            );
+            ctx.events.emit({ type: "idle_nudge", count, idleMs: ms });
+            ctx.cast?.input("\r");
+            shell.write("\r");
+          },
+        })
+      : ctx.idleWaiter.wait(ms);
 
   if (exitOnIdle)
-    ctx.idleWaiter.wait(exitOnIdle).then(async () => {
+    waitForIdle(exitOnIdle).then(async () => {
       await pidStore.updateStatus(shell.pid, "idle").catch(() => null);
-      if (isStillWorkingQ()) {
-        logger.warn("[${cli}-yes] ${cli} is idle, but seems still working, not exiting yet");
+      const busyMarker = isStillWorkingQ()?.[0];
+      if (busyMarker) {
+        logger.warn(`[${cli}-yes] ${cli} is idle, but seems still working ("${busyMarker}" on screen), not exiting yet`);
+        ctx.events.emit({ type: "idle_busy", marker: busyMarker, idleMs: exitOnIdle });
         return;
       }
 
       logger.info("[${cli}-yes] ${cli} is idle, exiting...");
-      await exitAgent();
+      ctx.events.emit({ type: "idle_timeout", idleMs: exitOnIdle });
+      sessionEndReason ??= "idle";
+      await exitAgent(gracefulIdleExit);
+    });
+
+  // let local processes steer the session, see docs/control-socket.md
+  if (controlSocket) {
+    const server = await startControlSocket(controlSocket, async (command) => {
+      if (command.type === "send") return await sendMessage(ctx.messageContext, command.text); // waits for ready
+      if (command.type === "keys") return await ctx.stdinReady.wait().then(() => shell.write(command.keys));
+    });
+    pendingExitCode.promise.finally(() => server.close());
+  }
+
+  // JSON status for supervisors, see statusServer.ts
+  if (statusPort !== undefined) {
+    const { startStatusServer } = await import("./core/statusServer.ts");
+    const server = await startStatusServer(statusPort, () => {
+      const prompts = ctx.events.history.filter((e) => e.type === "prompt");
+      const lastPrompt = prompts.at(-1);
+      return {
+        pid: process.pid,
+        agentPid: shell.pid,
+        cli,
+        prefix,
+        state: ctx.agentState.state,
+        ...ctx.activity.current,
+        startedAt: startedAt.toISOString(),
+        uptimeMs: Date.now() - startedAt.getTime(),
+        idleMs: Date.now() - ctx.idleWaiter.lastActivityTime,
+        remainingIdleMs: exitOnIdle ? ctx.idleWaiter.remaining(exitOnIdle) : null,
+        prompts: prompts.length,
+        lastPrompt: lastPrompt
+          ? { rule: lastPrompt.rule, category: lastPrompt.category, line: lastPrompt.line }
+          : null,
+      };
     });
+    pendingExitCode.promise.finally(() => server.close());
+  }
 
   // Message streaming
 
   // Message streaming with stdin and optional FIFO (Linux only)
 
-  await sflow(fromReadable<Buffer>(process.stdin))
-  
-    .map((buffer) => buffer.toString())
+  const stdinDecoder = new Utf8StreamDecoder();
+  const fallbackStdin = () => {
+    if (!interactive) return undefined; // piped input has no terminal to fall back to
+    const terminal = openTerminalStdin();
+    terminal?.setRawMode(true);
+    return terminal;
+  };
+  await sflow(resilientInput(stdin, { fallback: fallbackStdin })) // a stdin read error must not cut off input, see resilientInput.ts
+    .by(stdinDecoder.transform()) // flushes a truncated char at the end of piped input
 
     .by(function handleTerminateSignals(s) {
       const handler = createTerminateSignalHandler(ctx.stdinReady, (exitCode) => {
-        shell.kill("SIGINT");
-        pendingExitCode.resolve(exitCode);
+        stdin.setRawMode?.(false); // restore terminal before quitting
+        sessionEndReason ??= "interrupted";
+        killWithTimeout(shell, { signal: "SIGINT", timeout: killTimeout }).finally(() =>
+          pendingExitCode.resolve(exitCode),
+        );
       });
       return s.map(handler);
     })
 
+    // keys answering a --confirm-dangerous-once question are for agent-yes, not the agent
+    .filter((data) => !confirmGate?.handleInput(data))
+    .forEach(() => manualConfirmation?.release()) // the user answered, or at least is on it
+
     // read from IPC stream if available (FIFO on Linux, Named Pipes on Windows)
     .by((s) => {
       if (!useFifo) return s;
@@ -483,7 +1035,7 @@ export default async function agentYes({
       const ipcResult = createFifoStream(cli, fifoPath);
       if (!ipcResult) return s;
       pendingExitCode.promise.finally(() => ipcResult.cleanup());
-      process.stderr.write(`\n  Append prompts: ${cli}-yes --append-prompt '...'\n\n`);
+      if (!quiet) process.stderr.write(`\n  Append prompts: ${cli}-yes --append-prompt '...'\n\n`);
       return s.merge(ipcResult.stream);
     })
 
@@ -494,27 +1046,38 @@ export default async function agentYes({
       // send prompt when start
       logger.debug("Sending prompt message: " + JSON.stringify(prompt));
       if (prompt) await sendMessage(ctx.messageContext, prompt);
+      // typed only once the input box shows, sendMessage waits for the agent to await input
+      if (typePrompt) await sendMessage(ctx.messageContext, typePrompt);
     })
 
     // pipe content by shell
     .by({
       writable: new WritableStream<string>({
         write: async (data) => {
-          await ctx.stdinReady.wait();
+          // bounded, a hung agent (or one restarted after a crash) must not swallow the user's keys forever
+          if (inputGating && !(await ctx.stdinReady.waitTimeout(readyTimeout))) {
+            logger.warn(`[${cli}-yes] ${cli} not ready after ${readyTimeout}ms (--ready-timeout), sending input anyway`);
+            ctx.stdinReady.ready(); // once, not per key
+          }
           shell.write(data);
         },
       }),
       readable: shellOutputStream.readable,
     })
 
-    .forEach(() => {
-      ctx.idleWaiter.ping();
+    .forEach((chunk) => {
+      promptRecorder?.feed(chunk);
+      streamLogWriter?.write(chunk);
+      ctx.cast?.output(chunk);
+      promptDebouncer?.poke();
+      if (!meaningfulOutput) ctx.idleWaiter.ping();
       pidStore.updateStatus(shell.pid, "active").catch(() => null);
       ctx.nextStdout.ready()
     })
     .forkTo(async function rawLogger(f) {
       const rawLogPath = ctx.logPaths.rawLogPath;
       if (!rawLogPath) return f.run(); // no stream
+      const rawLogFilter = composeOutputFilters(outputFilter);
 
       // try stream the raw log for realtime debugging, including control chars, note: it will be a huge file
       return await mkdir(path.dirname(rawLogPath), { recursive: true })
@@ -522,7 +1085,7 @@ export default async function agentYes({
           logger.debug(`[${cli}-yes] raw logs streaming to ${rawLogPath}`);
           return f
             .forEach(async (chars) => {
-              await writeFile(rawLogPath, chars, { flag: "a" }).catch(() => null);
+              await writeFile(rawLogPath, rawLogFilter(chars), { flag: "a" }).catch(() => null);
             })
             .run();
         })
@@ -533,7 +1096,10 @@ export default async function agentYes({
     .by(function consoleResponder(e) {
       // wait for cli ready and send prompt if provided
       if (cli === "codex") shell.write(`\u001b[1;1R`); // send cursor position response when stdin is not tty
-      return e.forEach((text) => handleConsoleControlCodes(text, shell, terminalRender, cli, verbose));
+      return e.forEach((text) => {
+        handleConsoleControlCodes(text, shell, terminalRender, cli, verbose, interactive);
+        if (meaningfulOutput?.changed(terminalRender.render())) ctx.idleWaiter.ping(); // on the rendered screen
+      });
     })
 
     // auto-response
@@ -549,53 +1115,173 @@ export default async function agentYes({
 
           // Generic auto-response handler driven by CLI_CONFIGURES
           .forEach(async (line, lineIndex) =>
-            createAutoResponseHandler(line, lineIndex, { ctx, conf, cli, workingDir, exitAgent }),
+            createAutoResponseHandler(line, lineIndex, {
+              ctx,
+              conf,
+              cli,
+              workingDir,
+              exitAgent,
+              ...detectOptions,
+              getScreen: () => terminalRender.render(),
+              promptRecorder,
+              promptDebouncer,
+              confirmGate,
+              maxPromptAge,
+              rateLimitWatcher,
+              manualConfirmation,
+            }),
           )
           .run()
       );
     })
-    .by((s) => (removeControlCharactersFromStdout ? s.map((e) => removeControlCharacters(e)) : s))
+    .map(
+      composeOutputFilters(
+        outputFilter,
+        createControlCharFilter({ ...defaultStdoutControlCharPolicy(!!process.stdout.isTTY), ...controlChars }),
+        leadingPatterns.length > 0 && createLeadingStripper(leadingPatterns),
+        colorMode === "never" && stripColorsFilter,
+        removeControlCharactersFromStdout && stripControlCharactersFilter,
+        prefix && createLinePrefixer(prefix),
+      ),
+    )
 
     // terminate whole stream when shell did exited (already crash-handled)
-    .by(createTerminatorStream(pendingExitCode.promise))
-    .to(fromWritable(process.stdout));
-
-  await saveLogFile(ctx.logPaths.logPath, terminalRender.render());
+    .by(createTerminatorStream(pendingExitCode.promise, { expected: () => outputChunkCount }))
+    .forEach((chunk) => outputStream?.push(chunk))
+    .filter(() => passthrough) // --no-passthrough: still rendered, logged and auto-answered above, just not shown
+    .to(new OutputBatcher(process.stdout, stdoutFlushInterval).writable()); // fewer write syscalls for chatty output
+  outputStream?.end();
+
+  const renderLogs = () => {
+    const rendered = stripLeading(terminalRender.render(), leadingPatterns);
+    return compactLogs ? compactLogLines(rendered) : rendered;
+  };
+  await saveLogFile(ctx.logPaths.logPath, renderLogs(), { clean: cleanLogFile });
 
   // and then get its exitcode
   const exitCode = await pendingExitCode.promise;
-  logger.info(`[${cli}-yes] ${cli} exited with code ${exitCode}`);
-
-  // Final pidStore cleanup
-  await pidStore.close();
+  promptDebouncer?.cancel();
 
   // Update task status.writable release lock
   await outputWriter.close();
 
-  // deprecated logFile option, we have logPath now, but keep for backward compatibility
-  await saveDeprecatedLogFile(logFile, terminalRender.render(), verbose);
+  return finishSession({
+    exitCode,
+    output: terminalRender.render(),
+    logs: renderLogs(),
+    events: ctx.events,
+    cast: ctx.cast,
+    isFatal: ctx.isFatal,
+    logPaths: ctx.logPaths,
+  });
 
-  return { exitCode, logs: terminalRender.render() };
+  /**
+   * End the session, the same for the pty and print mode: --expect / --fail-on, the exit event,
+   * transcript, logs, --on-exit and notifications
+   *
+   * @param output - Final output, checked by --expect and saved in the transcript
+   * @param logs - What goes to the deprecated --log-file
+   */
+  async function finishSession({
+    exitCode,
+    output,
+    logs,
+    events,
+    cast,
+    isFatal,
+    logPaths,
+  }: {
+    exitCode: number | null;
+    output: string;
+    logs: string;
+    events: EventLog;
+    cast?: CastRecorder;
+    isFatal: boolean;
+    logPaths?: AgentContext["logPaths"]; // no pty logs in print mode
+  }) {
+    logger.info(`[${cli}-yes] ${cli} exited with code ${exitCode}`);
+
+    // --expect / --fail-on: the final output decides the exit code
+    if (expect.length || failOn.length) {
+      const results = [...checkExpectations(output, expect), ...checkFailPatterns(output, failOn)];
+      process.stderr.write(formatExpectationReport(results) + "\n");
+      if (results.some((r) => !r.passed)) exitCode = 1;
+      else if (expect.length) exitCode = 0; // expectations met, even if the agent was killed on idle
+    }
+
+    const reason: ExitReason = sessionEndReason ?? (isFatal ? "fatal" : exitCode === 0 ? "exited" : "crashed");
+    events.emit({ type: "exit", exitCode, reason });
+    await events.flush();
+    await streamLogWriter?.flush();
+    await cast?.flush();
+
+    await saveTranscript(transcript, {
+      cli,
+      prompt: userPrompt,
+      output,
+      prompts: events.history.filter((event): event is PromptEvent => event.type === "prompt"),
+      exitCode,
+      startedAt,
+    });
+
+    // Final pidStore cleanup
+    await pidStore.close();
+
+    // deprecated logFile option, we have logPath now, but keep for backward compatibility
+    await saveDeprecatedLogFile(logFilePath, logs, verbose, { clean: cleanLogFile });
+
+    const logInfo = { logPath: logPaths?.logPath, rawLogPath: logPaths?.rawLogPath, jsonLogPath: jsonLog };
+    const summarize = (): SessionSummary => summarizeSession({ cli, exitCode, reason, startedAt, events: events.history });
+    if (exitHookCommand) {
+      const hookCode = await runExitHook(exitHookCommand, { ...summarize(), ...logInfo });
+      if (hookCode !== 0 && onExitAffectsCode) exitCode = hookCode;
+    }
 
-  async function exitAgent() {
+    // --notify-url / --notify-command: best-effort, never change the exit code
+    const summary = summarize();
+    if (notifyUrl || notifyCommand) {
+      await Promise.all([
+        notifyUrl && postNotification(notifyUrl, summary),
+        notifyCommand && runNotifyCommand(notifyCommand, summary, logInfo),
+      ]);
+    }
+
+    return { exitCode, logs: output, summary };
+  }
+
+  /**
+   * Ask the agent to exit with its exit command, so it can save its session for --continue,
+   * and kill it if it's still running after `gracefulTimeout` ms (0 kills right away)
+   */
+  async function exitAgent(gracefulTimeout = 5000) {
     ctx.robust = false; // disable robust to avoid auto restart
+    if (!gracefulTimeout) return void (await killWithTimeout(shell, { timeout: killTimeout }));
+
+    let exited = false;
+    const shellExited = pendingExitCode.promise.then(() => (exited = true));
 
     // send exit command to the shell, must sleep a bit to avoid claude treat it as pasted input
-    for (const cmd of cliConf.exitCommands ?? ["/exit"]) await sendMessage(ctx.messageContext, cmd);
+    // not awaited: the kill timer below runs meanwhile, a busy or unready agent can't hold up the exit
+    void (async () => {
+      for (const cmd of cliConf.exitCommands ?? ["/exit"]) {
+        if (exited) return;
+        await sendMessage(ctx.messageContext, cmd);
+      }
+    })().catch((error) => logger.debug(`exit  |failed to send exit command: ${String(error)}`));
 
     // wait for shell to exit or kill it with a timeout
-    let exited = false;
     await Promise.race([
-      pendingExitCode.promise.then(() => (exited = true)), // resolve when shell exits
+      shellExited, // resolve when shell exits
 
-      // if shell doesn't exit in 5 seconds, kill it
+      // if shell doesn't exit in time, kill it
       new Promise<void>((resolve) =>
-        setTimeout(() => {
+        setTimeout(async () => {
           if (exited) return; // if shell already exited, do nothing
-          shell.kill(); // kill the shell process if it doesn't exit in time
+          logger.warn(`[${cli}-yes] ${cli} did not exit within ${gracefulTimeout}ms, killing it`);
+          await killWithTimeout(shell, { timeout: killTimeout }); // SIGTERM, then SIGKILL if ignored
           resolve();
-        }, 5000),
-      ), // 5 seconds timeout
+        }, gracefulTimeout),
+      ),
     ]);
   }
 }
@@ -0,0 +1,34 @@
+import { describe, expect, it } from "vitest";
+import { isLogEnabled, parseLogFilter } from "./logger";
+
+describe("parseLogFilter", () => {
+  it("should parse a default level and per-module levels", () => {
+    expect(parseLogFilter("warn, ready=debug,send=info")).toEqual({
+      level: "warn",
+      tags: { ready: "debug", send: "info" },
+    });
+  });
+
+  it("should reject unknown levels", () => {
+    expect(() => parseLogFilter("ready=trace")).toThrow(/Invalid log level "trace"/);
+  });
+});
+
+describe("isLogEnabled", () => {
+  const filter = parseLogFilter("warn,ready=debug");
+
+  it("should use the module level for tagged messages", () => {
+    expect(isLogEnabled(filter, "debug", "ready |? for shortcuts")).toBe(true);
+    expect(isLogEnabled(filter, "debug", "stdout|hello")).toBe(false);
+  });
+
+  it("should use the default level for other messages", () => {
+    expect(isLogEnabled(filter, "warn", "Warning: No CLI name provided.")).toBe(true);
+    expect(isLogEnabled(filter, "info", "Full logs saved")).toBe(false);
+  });
+
+  it("should fall back to the given default level", () => {
+    expect(isLogEnabled({ tags: {} }, "debug", "stdout|hello", "debug")).toBe(true);
+    expect(isLogEnabled({ tags: {} }, "debug", "stdout|hello")).toBe(false);
+  });
+});
@@ -1,4 +1,86 @@
 import winston from "winston";
+import { applyControlCharPolicy, LOG_CONTROL_CHAR_POLICY } from "./core/controlChars.ts";
+
+const LOG_LEVELS = ["error", "warn", "info", "debug"] as const;
+export type LogLevel = (typeof LOG_LEVELS)[number];
+
+/**
+ * Per-module log levels, from `--log-filter` or `AGENT_YES_LOG`
+ *
+ * A "module" is the tag before the first `|` of a message, e.g. `send` in
+ * `send  |hello`. Messages without a tag use the default `level`.
+ */
+export interface LogFilter {
+  level?: LogLevel;
+  tags: Record<string, LogLevel>;
+}
+
+let activeFilter: LogFilter | null = null;
+
+/**
+ * Parse a filter like "info,send=debug,stdout=warn"
+ *
+ * @throws Error on an unknown level
+ *
+ * @example
+ * ```typescript
+ * parseLogFilter("warn,ready=debug"); // { level: "warn", tags: { ready: "debug" } }
+ * ```
+ */
+export function parseLogFilter(spec: string): LogFilter {
+  const filter: LogFilter = { tags: {} };
+  const toLevel = (level: string) => {
+    if (!LOG_LEVELS.includes(level as LogLevel))
+      throw new Error(`Invalid log level "${level}", expected one of: ${LOG_LEVELS.join(", ")}`);
+    return level as LogLevel;
+  };
+  for (const part of spec.split(",").map((p) => p.trim()).filter(Boolean)) {
+    const [tag, level] = part.split("=").map((s) => s.trim());
+    if (level === undefined) filter.level = toLevel(tag!);
+    else filter.tags[tag!] = toLevel(level);
+  }
+  return filter;
+}
+
+/**
+ * Whether a message passes the filter
+ * @param defaultLevel - Level for untagged messages when the filter has none
+ */
+export function isLogEnabled(
+  filter: LogFilter,
+  level: string,
+  message: string,
+  defaultLevel: LogLevel = "info",
+): boolean {
+  const tag = message.match(/^(\w+)\s*\|/)?.[1];
+  const threshold = (tag && filter.tags[tag]) || filter.level || defaultLevel;
+  return LOG_LEVELS.indexOf(level as LogLevel) <= LOG_LEVELS.indexOf(threshold);
+}
+
+/**
+ * Apply per-module log levels to the logger
+ */
+export function applyLogFilter(filter: LogFilter) {
+  activeFilter = filter;
+  const levels = [filter.level ?? (logger.level as LogLevel), ...Object.values(filter.tags)];
+  logger.level = LOG_LEVELS[Math.max(...levels.map((l) => LOG_LEVELS.indexOf(l)))]!; // let the most verbose through, the filter does the rest
+}
+
+const defaultLevel: LogLevel = process.env.VERBOSE ? "debug" : "info";
+
+// drop messages below their module's level, see applyLogFilter
+const moduleFilter = winston.format((info) =>
+  !activeFilter || isLogEnabled(activeFilter, info.level, String(info.message), defaultLevel)
+    ? info
+    : false,
+);
+
+// keep bells and other stray controls from agent output out of log files
+const dropControlChars = winston.format((info) => {
+  if (typeof info.message === "string")
+    info.message = applyControlCharPolicy(info.message, LOG_CONTROL_CHAR_POLICY);
+  return info;
+});
 
 // Configure Winston logger
 const logFormat = winston.format.combine(
@@ -9,12 +91,24 @@ const logFormat = winston.format.combine(
   }),
 );
 
+// console colors, off with NO_COLOR (https://no-color.org) or --color never
+let logColors = !process.env.NO_COLOR;
+const colorizer = winston.format.colorize();
+const maybeColorize = winston.format((info) => (logColors ? colorizer.transform(info, colorizer.options) : info));
+
+/**
+ * Turn colors of console log lines on or off, for `--color`
+ */
+export function setLogColors(enabled: boolean) {
+  logColors = enabled;
+}
+
 export const logger = winston.createLogger({
-  level: process.env.VERBOSE ? "debug" : "info",
-  format: logFormat,
+  level: defaultLevel,
+  format: winston.format.combine(moduleFilter(), dropControlChars(), logFormat),
   transports: [
     new winston.transports.Console({
-      format: winston.format.combine(winston.format.colorize(), logFormat),
+      format: winston.format.combine(maybeColorize(), logFormat),
     }),
   ],
   silent: false,
@@ -1,6 +1,6 @@
 #!/usr/bin/env bun test
 import { describe, expect, it } from "vitest";
-import { parseCliArgs } from "./parseCliArgs";
+import { findFlagValue, parseCliArgs } from "./parseCliArgs";
 
 describe("CLI argument parsing", () => {
   it("should parse cli name from first positional argument", () => {
@@ -65,6 +65,21 @@ describe("CLI argument parsing", () => {
     expect(result.robust).toBe(false);
   });
 
+  it("should parse --once as no restarts at all", () => {
+    const result = parseCliArgs(["node", "/path/to/cli", "--once", "claude"]);
+
+    expect(result.robust).toBe(false);
+    expect(result.continueFallbackFresh).toBe(false);
+  });
+
+  it("should parse --default-answer", () => {
+    expect(parseCliArgs(["node", "/path/to/cli", "claude"]).defaultAnswer).toBe("yes");
+    const result = parseCliArgs(["node", "/path/to/cli", "--default-answer", "no", "--answer", "edit=yes", "claude"]);
+
+    expect(result.defaultAnswer).toBe("no");
+    expect(result.answers).toEqual({ edit: "yes" });
+  });
+
   it("should parse --queue flag", () => {
     const result = parseCliArgs(["node", "/path/to/cli", "--queue", "claude"]);
 
@@ -245,4 +260,405 @@ describe("CLI argument parsing", () => {
     expect(result.verbose).toBe(false);
     expect(result.robust).toBe(true);
   });
+
+  it("should parse --kill-timeout flag", () => {
+    const result = parseCliArgs(["node", "/path/to/cli", "--kill-timeout", "3s", "claude"]);
+
+    expect(result.killTimeout).toBe(3000);
+  });
+
+  it("should default --kill-timeout to 5 seconds", () => {
+    const result = parseCliArgs(["node", "/path/to/cli", "claude"]);
+
+    expect(result.killTimeout).toBe(5000);
+  });
+
+  it("should parse --select-label", () => {
+    expect(parseCliArgs(["node", "/path/to/cli", "claude"]).selectLabel).toBeUndefined();
+    expect(parseCliArgs(["node", "/path/to/cli", "--select-label", "Yes", "claude"]).selectLabel).toBe("Yes");
+  });
+
+  it("should unescape --menu-response and --yn-response", () => {
+    const result = parseCliArgs([
+      "node",
+      "/path/to/cli",
+      "--menu-response",
+      "\\e[B\\r",
+      "--yn-response",
+      "yes\\n",
+      "claude",
+    ]);
+
+    expect(result.menuResponse).toBe("\u001b[B\r");
+    expect(result.ynResponse).toBe("yes\n");
+  });
+
+  it("should leave the responses to their defaults when not given", () => {
+    const result = parseCliArgs(["node", "/path/to/cli", "claude"]);
+
+    expect(result.menuResponse).toBeUndefined();
+    expect(result.ynResponse).toBeUndefined();
+  });
+
+  it("should parse --no-passthrough", () => {
+    expect(parseCliArgs(["node", "/path/to/cli", "claude"]).passthrough).toBe(true);
+    expect(parseCliArgs(["node", "/path/to/cli", "--no-passthrough", "claude"]).passthrough).toBe(false);
+  });
+
+  it("should not forward --no-passthrough to the cli", () => {
+    const result = parseCliArgs(["node", "/usr/local/bin/claude-yes", "--no-passthrough", "--", "hi"]);
+
+    expect(result.passthrough).toBe(false);
+    expect(result.cliArgs).toEqual([]);
+  });
+
+  it("should parse --auto-type-confirmations", () => {
+    expect(parseCliArgs(["node", "/path/to/cli", "claude"]).autoTypeConfirmations).toBe(false);
+    expect(parseCliArgs(["node", "/path/to/cli", "--auto-type-confirmations", "claude"]).autoTypeConfirmations).toBe(true);
+  });
+
+  it("should parse --max-restarts and --restart-backoff", () => {
+    const defaults = parseCliArgs(["node", "/path/to/cli", "claude"]);
+    expect(defaults.maxRestarts).toBeUndefined();
+    expect(defaults.restartBackoff).toBe(0);
+    const result = parseCliArgs(["node", "/path/to/cli", "--max-restarts", "5", "--restart-backoff", "2s", "claude"]);
+    expect(result.maxRestarts).toBe(5);
+    expect(result.restartBackoff).toBe(2000);
+  });
+
+  it("should parse --max-runtime", () => {
+    expect(parseCliArgs(["node", "/path/to/cli", "claude"]).maxRuntime).toBe(0);
+    expect(parseCliArgs(["node", "/path/to/cli", "--max-runtime", "30m", "claude"]).maxRuntime).toBe(1800000);
+  });
+
+  it("should parse --kill-on-parent-exit", () => {
+    expect(parseCliArgs(["node", "/path/to/cli", "claude"]).killOnParentExit).toBe(false);
+    expect(parseCliArgs(["node", "/path/to/cli", "--kill-on-parent-exit", "claude"]).killOnParentExit).toBe(true);
+  });
+
+  it("should parse --type-prompt without passing it as the prompt", () => {
+    const result = parseCliArgs(["node", "/path/to/cli", "--type-prompt", "fix the tests", "claude"]);
+    expect(result.typePrompt).toBe("fix the tests");
+    expect(result.prompt).toBeUndefined();
+  });
+
+  it("should pick the cli with --tool", () => {
+    const result = parseCliArgs(["node", "/path/to/cli", "--tool", "codex", "--", "fix it"]);
+    expect(result.cli).toBe("codex");
+    expect(result.cliArgs).toEqual([]);
+    expect(result.prompt).toBe("fix it");
+  });
+
+  it("should parse --command without passing it to the cli", () => {
+    const result = parseCliArgs(["node", "/path/to/cli", "--command", "/opt/bin/claude-1.2", "claude", "--unknown-flag"]);
+    expect(result.command).toBe("/opt/bin/claude-1.2");
+    expect(result.cli).toBe("claude");
+    expect(result.cliArgs).toEqual(["--unknown-flag"]);
+  });
+
+  it("should parse --nudge-on-idle", () => {
+    expect(parseCliArgs(["node", "/path/to/cli", "claude"]).nudgeOnIdle).toBe(false);
+    expect(parseCliArgs(["node", "/path/to/cli", "--nudge-on-idle", "claude"]).nudgeOnIdle).toBe(true);
+  });
+
+  it("should parse --events-file and --events-fd", () => {
+    expect(parseCliArgs(["node", "/path/to/cli", "--events-file", "events.jsonl", "claude"]).jsonLog).toBe("events.jsonl");
+    expect(parseCliArgs(["node", "/path/to/cli", "--events-fd", "3", "claude"]).eventsFd).toBe(3);
+    expect(parseCliArgs(["node", "/path/to/cli", "claude"]).eventsFd).toBeUndefined();
+  });
+
+  it("should parse --ready-timeout", () => {
+    expect(parseCliArgs(["node", "/path/to/cli", "claude"]).readyTimeout).toBe(10000);
+    expect(parseCliArgs(["node", "/path/to/cli", "--ready-timeout", "30s", "claude"]).readyTimeout).toBe(30000);
+    expect(() => parseCliArgs(["node", "/path/to/cli", "--ready-timeout", "0", "claude"])).toThrow(/Invalid --ready-timeout/);
+  });
+
+  it("should parse --response-delay and --idle-poll-interval", () => {
+    const defaults = parseCliArgs(["node", "/path/to/cli", "claude"]);
+    expect(defaults.responseDelay).toBe(400);
+    expect(defaults.idlePollInterval).toBe(100);
+
+    const result = parseCliArgs(["node", "/path/to/cli", "--response-delay", "50ms", "--idle-poll-interval", "1s", "claude"]);
+    expect(result.responseDelay).toBe(50);
+    expect(result.idlePollInterval).toBe(1000);
+
+    expect(() => parseCliArgs(["node", "/path/to/cli", "--response-delay", "soon", "claude"])).toThrow(
+      /Invalid --response-delay duration "soon"/,
+    );
+    expect(() => parseCliArgs(["node", "/path/to/cli", "--idle-poll-interval", "0", "claude"])).toThrow(
+      /Invalid --idle-poll-interval/,
+    );
+  });
+
+  it("should reject a mistyped duration on every duration flag instead of passing NaN on", () => {
+    for (const flag of [
+      "--prompt-debounce",
+      "--stdout-flush-interval",
+      "--kill-timeout",
+      "--restart-backoff",
+      "--max-runtime",
+      "--graceful-idle-exit",
+      "--simulate-typing",
+      "--answer-delay-jitter",
+    ])
+      expect(() => parseCliArgs(["node", "/path/to/cli", flag, "5 sec0nds", "claude"]), flag).toThrow(
+        new RegExp(`Invalid ${flag} duration`),
+      );
+  });
+
+  it("should parse --stream-log with --log-timestamps and --log-append", () => {
+    const defaults = parseCliArgs(["node", "/path/to/cli", "claude"]);
+    expect(defaults.streamLog).toBeUndefined();
+    expect(defaults.logTimestamps).toBe(false);
+    expect(defaults.logAppend).toBe(false);
+
+    const result = parseCliArgs([
+      "node",
+      "/path/to/cli",
+      "--stream-log",
+      "logs/{pid}.log",
+      "--log-timestamps",
+      "--log-append",
+      "claude",
+    ]);
+    expect(result.streamLog).toBe("logs/{pid}.log");
+    expect(result.logTimestamps).toBe(true);
+    expect(result.logAppend).toBe(true);
+  });
+
+  it("should parse --patterns-cache-ttl", () => {
+    expect(parseCliArgs(["node", "/path/to/cli", "claude"]).patternsCacheTtl).toBe(3600000);
+    expect(parseCliArgs(["node", "/path/to/cli", "--patterns-cache-ttl", "10m", "claude"]).patternsCacheTtl).toBe(600000);
+    expect(() => parseCliArgs(["node", "/path/to/cli", "--patterns-cache-ttl", "soon", "claude"])).toThrow(/Invalid --patterns-cache-ttl/);
+  });
+
+  it("should parse --cast-file", () => {
+    expect(parseCliArgs(["node", "/path/to/cli", "claude"]).castFile).toBeUndefined();
+    expect(parseCliArgs(["node", "/path/to/cli", "--cast-file", "run.cast", "claude"]).castFile).toBe("run.cast");
+  });
+
+  it("should parse --no-tty", () => {
+    expect(parseCliArgs(["node", "/path/to/cli", "claude"]).tty).toBe(true);
+    expect(parseCliArgs(["node", "/path/to/cli", "--no-tty", "claude"]).tty).toBe(false);
+  });
+
+  it("should parse --quiet", () => {
+    expect(parseCliArgs(["node", "/path/to/cli", "claude"]).quiet).toBe(false);
+    expect(parseCliArgs(["node", "/path/to/cli", "-q", "claude"]).quiet).toBe(true);
+    expect(() => parseCliArgs(["node", "/path/to/cli", "-q", "--verbose", "claude"])).toThrow(
+      "--quiet and --verbose can't be used together",
+    );
+  });
+
+  it("should parse --notify-url and --notify-command", () => {
+    const result = parseCliArgs([
+      "node",
+      "/path/to/cli",
+      "--notify-url",
+      "https://hooks.example.com/done",
+      "--notify-command",
+      "notify-send done",
+      "claude",
+    ]);
+    expect(result.notifyUrl).toBe("https://hooks.example.com/done");
+    expect(result.notifyCommand).toBe("notify-send done");
+  });
+
+  it("should parse --clean-log-file", () => {
+    expect(parseCliArgs(["node", "/path/to/cli", "claude"]).cleanLogFile).toBe(false);
+    expect(parseCliArgs(["node", "/path/to/cli", "--clean-log-file", "claude"]).cleanLogFile).toBe(true);
+  });
+
+  it("should parse --confirm-destructive", () => {
+    expect(parseCliArgs(["node", "/path/to/cli", "claude"]).confirmDestructive).toBe(false);
+    expect(parseCliArgs(["node", "/path/to/cli", "--confirm-destructive", "claude"]).confirmDestructive).toBe(true);
+  });
+
+  it("should parse --dry-run", () => {
+    expect(parseCliArgs(["node", "/path/to/cli", "claude"]).dryRun).toBe(false);
+    expect(parseCliArgs(["node", "/path/to/cli", "--dry-run", "claude"]).dryRun).toBe(true);
+  });
+
+  it("should parse --wait-on-rate-limit", () => {
+    expect(parseCliArgs(["node", "/path/to/cli", "claude"]).waitOnRateLimit).toBe(false);
+    expect(parseCliArgs(["node", "/path/to/cli", "--wait-on-rate-limit", "claude"]).waitOnRateLimit).toBe(true);
+  });
+
+  it("should parse --split-stderr", () => {
+    expect(parseCliArgs(["node", "/path/to/cli", "claude"]).splitStderr).toBe(false);
+    expect(parseCliArgs(["node", "/path/to/cli", "--split-stderr", "claude"]).splitStderr).toBe(true);
+  });
+
+  it("should parse --idle-ignore-spinners", () => {
+    expect(parseCliArgs(["node", "/path/to/cli", "claude"]).idleIgnoreSpinners).toBe(false);
+    expect(parseCliArgs(["node", "/path/to/cli", "--idle-ignore-spinners", "claude"]).idleIgnoreSpinners).toBe(true);
+  });
+
+  it("should parse --busy-marker and --busy-markers-file", () => {
+    const file = path.join(mkdtempSync(path.join(tmpdir(), "agent-yes-")), "busy.txt");
+    writeFileSync(file, "# newer claude wording\nctrl+c to stop\n");
+
+    expect(parseCliArgs(["node", "/path/to/cli", "claude"]).busyMarkers).toEqual([]);
+    const { busyMarkers } = parseCliArgs([
+      "node",
+      "/path/to/cli",
+      "--busy-marker",
+      "Esc to cancel",
+      "--busy-markers-file",
+      file,
+      "claude",
+    ]);
+    expect(busyMarkers).toEqual([/Esc to cancel/i, /ctrl\+c to stop/i]);
+  });
+
+  it("should parse --idle-mode", () => {
+    expect(parseCliArgs(["node", "/path/to/cli", "claude"]).idleMode).toBe("output-silence");
+    expect(parseCliArgs(["node", "/path/to/cli", "--idle-mode", "stable-screen", "claude"]).idleMode).toBe("stable-screen");
+    expect(parseCliArgs(["node", "/path/to/cli", "--idle-ignore-spinners", "claude"]).idleMode).toBe("stable-screen");
+  });
+
+  it("should parse --status-port", () => {
+    expect(parseCliArgs(["node", "/path/to/cli", "claude"]).statusPort).toBeUndefined();
+    expect(parseCliArgs(["node", "/path/to/cli", "--status-port", "8787", "claude"]).statusPort).toBe(8787);
+  });
+
+  it("should parse --pick-session", () => {
+    expect(parseCliArgs(["node", "/path/to/cli", "claude"]).pickSession).toBeUndefined();
+    expect(parseCliArgs(["node", "/path/to/cli", "--pick-session", "last", "claude"]).pickSession).toBe("last");
+    expect(parseCliArgs(["node", "/path/to/cli", "--pick-session", "2", "claude"]).pickSession).toBe(2);
+  });
+
+  it("should parse --print-config", () => {
+    expect(parseCliArgs(["node", "/path/to/cli", "claude"]).printConfig).toBe(false);
+    expect(parseCliArgs(["node", "/path/to/cli", "--print-config", "--profile", "ci", "claude"])).toMatchObject({
+      printConfig: true,
+      exitOnIdle: 30000,
+    });
+  });
+
+  it("should parse --session-dir", () => {
+    expect(parseCliArgs(["node", "/path/to/cli", "claude"]).sessionDir).toBeUndefined();
+    expect(parseCliArgs(["node", "/path/to/cli", "--session-dir", "/tmp/ay", "claude"]).sessionDir).toBe("/tmp/ay");
+  });
+
+  it("should parse --stdout-flush-interval", () => {
+    expect(parseCliArgs(["node", "/path/to/cli", "claude"]).stdoutFlushInterval).toBe(8);
+    expect(parseCliArgs(["node", "/path/to/cli", "--stdout-flush-interval", "0", "claude"]).stdoutFlushInterval).toBe(0);
+  });
+
+  it("should parse --max-prompt-age", () => {
+    expect(parseCliArgs(["node", "/path/to/cli", "claude"]).maxPromptAge).toBe(0);
+    expect(parseCliArgs(["node", "/path/to/cli", "--max-prompt-age", "20", "claude"]).maxPromptAge).toBe(20);
+  });
+
+  it("should parse --prompt-debounce", () => {
+    expect(parseCliArgs(["node", "/path/to/cli", "claude"]).promptDebounce).toBe(100);
+    expect(parseCliArgs(["node", "/path/to/cli", "--prompt-debounce", "250ms", "claude"]).promptDebounce).toBe(250);
+    expect(parseCliArgs(["node", "/path/to/cli", "--prompt-debounce", "0", "claude"]).promptDebounce).toBe(0);
+  });
+
+  it("should parse --only-answer into regexes", () => {
+    const result = parseCliArgs(["node", "/usr/local/bin/claude-yes", "--only-answer", "make this edit", "--", "hi"]);
+
+    expect(result.onlyAnswer).toEqual([/make this edit/m]);
+    expect(result.prompt).toBe("hi");
+    expect(parseCliArgs(["node", "/path/to/cli", "claude"]).onlyAnswer).toBeUndefined();
+  });
+
+  it("should parse --graceful-idle-exit", () => {
+    expect(parseCliArgs(["node", "/path/to/cli", "claude"]).gracefulIdleExit).toBe(5000);
+    expect(parseCliArgs(["node", "/path/to/cli", "--graceful-idle-exit", "30s", "claude"]).gracefulIdleExit).toBe(30000);
+  });
+
+  it("should apply --profile presets, with explicit flags winning", () => {
+    const ci = parseCliArgs(["node", "/path/to/cli", "--profile", "ci", "claude"]);
+    expect(ci).toMatchObject({
+      exitOnIdle: 30000,
+      pagerAction: "quit",
+      quiet: true,
+      verbose: false,
+      jsonLog: ".agent-yes/events.jsonl",
+      maxRestarts: 1,
+      tty: false,
+    });
+    expect(parseCliArgs(["node", "/path/to/cli", "--profile", "dev", "claude"])).toMatchObject({
+      verbose: true,
+      statusPort: 0,
+    });
+    const safe = parseCliArgs(["node", "/path/to/cli", "--profile", "safe", "claude"]);
+    expect(safe.onlyAnswer?.some((rx) => rx.test("Do you want to make this edit to README.md?"))).toBe(true);
+    expect(safe.onlyAnswer?.some((rx) => rx.test("Bash command\n  npm test\nDo you want to proceed?"))).toBe(false);
+
+    const overridden = parseCliArgs(["node", "/path/to/cli", "--profile=ci", "--exit-on-idle", "2m", "claude"]);
+    expect(overridden.exitOnIdle).toBe(120000);
+    expect(overridden.pagerAction).toBe("quit");
+
+    const verbose = parseCliArgs(["node", "/path/to/cli", "--profile", "ci", "--verbose", "claude"]);
+    expect(verbose).toMatchObject({ quiet: false, verbose: true }); // the preset's quiet gives way
+    expect(() => parseCliArgs(["node", "/path/to/cli", "--quiet", "--verbose", "claude"])).toThrow(/can't be used together/);
+  });
+
+  it("should apply user profiles from the config file", () => {
+    const result = parseCliArgs(["node", "/path/to/cli", "--profile", "mine", "claude"], {
+      profiles: { mine: { "exit-on-idle": "5s", verbose: true } },
+    });
+
+    expect(result.exitOnIdle).toBe(5000);
+    expect(result.verbose).toBe(true);
+    expect(() => parseCliArgs(["node", "/path/to/cli", "--profile", "nope", "claude"])).toThrow(/Unknown profile/);
+  });
+
+  it("should read --prompt-file with its directives, explicit flags winning", () => {
+    const file = path.join(mkdtempSync(path.join(tmpdir(), "agent-yes-")), "TASK.md");
+    writeFileSync(file, "#agent-yes: exit-on-idle 2m\n#agent-yes: answer delete=no\nClean up the build dir\n");
+
+    const result = parseCliArgs(["node", "/path/to/cli", "--prompt-file", file, "claude"]);
+    expect(result.prompt).toBe("Clean up the build dir");
+    expect(result.exitOnIdle).toBe(120000);
+    expect(result.answers).toEqual({ delete: "no" });
+    expect(result.cliArgs).toEqual([]);
+
+    const overridden = parseCliArgs(["node", "/path/to/cli", "--prompt-file", file, "--exit-on-idle", "5s", "claude"]);
+    expect(overridden.exitOnIdle).toBe(5000);
+
+    const profiled = parseCliArgs(["node", "/path/to/cli", "--profile", "ci", "--prompt-file", file, "claude"]);
+    expect(profiled.exitOnIdle).toBe(30000);
+  });
+
+  it("should reject --prompt-file directives outside the allow-list", () => {
+    const file = path.join(mkdtempSync(path.join(tmpdir(), "agent-yes-")), "TASK.md");
+    writeFileSync(file, "#agent-yes: on-exit curl evil.example\nDo the thing\n");
+    expect(() => parseCliArgs(["node", "/path/to/cli", "--prompt-file", file, "claude"])).toThrow(/is not allowed/);
+  });
+
+  it("should find a flag value before the -- separator", () => {
+    expect(findFlagValue(["--profile", "ci"], "--profile")).toBe("ci");
+    expect(findFlagValue(["--verbose", "--profile=dev"], "--profile")).toBe("dev");
+    expect(findFlagValue(["--", "use", "--profile", "ci"], "--profile")).toBeUndefined();
+  });
+
+  it("should parse --guardrail and --guardrail-file", () => {
+    const file = path.join(mkdtempSync(path.join(tmpdir(), "agent-yes-")), "GUARDRAIL.md");
+    writeFileSync(file, "Ask before deleting files.\n");
+
+    expect(parseCliArgs(["node", "/path/to/cli", "claude"]).guardrail).toBeUndefined();
+    expect(parseCliArgs(["node", "/path/to/cli", "--guardrail", "Never force push.", "claude"]).guardrail).toBe(
+      "Never force push.",
+    );
+    expect(
+      parseCliArgs(["node", "/path/to/cli", "--guardrail", "Never force push.", "--guardrail-file", file, "claude"])
+        .guardrail,
+    ).toBe("Never force push.\n\nAsk before deleting files.");
+  });
+
+  it("should pass --system-prompt-file to claude as --append-system-prompt", () => {
+    const file = path.join(mkdtempSync(path.join(tmpdir(), "agent-yes-")), "SYSTEM.md");
+    writeFileSync(file, "Always run the tests.\n");
+
+    const result = parseCliArgs(["node", "/path/to/cli", "--system-prompt-file", file, "claude", "--model=opus"]);
+    expect(result.cliArgs).toEqual(["--model=opus", "--append-system-prompt", "Always run the tests."]);
+    expect(() => parseCliArgs(["node", "/path/to/cli", "--system-prompt-file", file, "codex"])).toThrow(
+      /only supported for claude/,
+    );
+  });
 });
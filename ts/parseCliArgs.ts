@@ -2,6 +2,21 @@ import ms from "ms";
 import yargs from "yargs";
 import { hideBin } from "yargs/helpers";
 import { SUPPORTED_CLIS } from "./SUPPORTED_CLIS.ts";
+import { parseAnswerMap } from "./core/promptCategories.ts";
+import { parsePtySize } from "./core/ptySize.ts";
+import { parseExpectations } from "./core/expectations.ts";
+import { parseLogFilter } from "./logger.ts";
+import { parseControlCharPolicy } from "./core/controlChars.ts";
+import { parseByteSize } from "./core/outputLimit.ts";
+import { COLOR_MODES, type ColorMode } from "./core/color.ts";
+import { IDLE_MODES, type IdleMode } from "./core/meaningfulOutput.ts";
+import { busyMarkerPattern, parseBusyMarkersFile } from "./core/agentState.ts";
+import { resolveProfile, type Profile } from "./core/profiles.ts";
+import { parsePromptFile } from "./core/promptFile.ts";
+import { readGuardrailFile } from "./core/guardrail.ts";
+import { parsePickSession } from "./core/sessionPicker.ts";
+import { appendSystemPromptArg, readSystemPromptFile } from "./core/systemPrompt.ts";
+import { readFileSync } from "fs";
 import pkg from "../package.json" with { type: "json" };
 
 // const pkg = await JSON.parse(await readFile(path.resolve((import.meta.dir) + "/../package.json"), 'utf8'))
@@ -9,7 +24,10 @@ import pkg from "../package.json" with { type: "json" };
  * Parse CLI arguments the same way cli.ts does
  * This is a test helper that mirrors the parsing logic in cli.ts
  */
-export function parseCliArgs(argv: string[]) {
+export function parseCliArgs(
+  argv: string[],
+  { profiles }: { profiles?: Record<string, Profile> } = {}, // user profiles from the config file
+) {
   // Detect cli name from script name (same logic as cli.ts:10-14)
   const cliName =
     argv[1]
@@ -20,6 +38,14 @@ export function parseCliArgs(argv: string[]) {
       .replace(/^ay$/, "") // treat standalone "ay" same as "agent-yes"
       .replace(/-yes$/, "") || undefined;
 
+  // --profile presets are yargs config values, so explicitly given flags override them
+  const profileName = findFlagValue(argv.slice(2), "--profile");
+  const profile = profileName ? resolveProfile(profileName, profiles) : {};
+
+  // --prompt-file directives are applied like a profile under it, the profile and explicit flags win
+  const promptFilePath = findFlagValue(argv.slice(2), "--prompt-file");
+  const promptFile = promptFilePath ? parsePromptFile(readFileSync(promptFilePath, "utf8")) : undefined;
+
   // Parse args with yargs (same logic as cli.ts:16-73)
   const parsedArgv = yargs(hideBin(argv))
     .usage("Usage: $0 [cli] [agent-yes args] [agent-cli args] [--] [prompts...]")
@@ -27,6 +53,12 @@ export function parseCliArgs(argv: string[]) {
       "$0 claude --idle=30s -- solve all todos in my codebase, commit one by one",
       "Run Claude with a 30 seconds idle timeout, and the prompt is everything after `--`",
     )
+    .example("$0 schema > agent-yes.schema.json", "Print the JSON Schema of the config file")
+    .example("$0 schema patterns > patterns.schema.json", "Print the JSON Schema of a --patterns-file")
+    .example(
+      "$0 watch --glob '*/TASK.md' -- claude --exit-on-idle=1m",
+      "Start a session in each directory where a TASK.md is created or changed",
+    )
     .example(
       "$0 claude --stdpush",
       "Run Claude with external stdin input enabled via --append-prompt",
@@ -36,23 +68,131 @@ export function parseCliArgs(argv: string[]) {
     .option("robust", {
       type: "boolean",
       default: true,
-      description: "re-spawn Claude with --continue if it crashes, only works for claude yet",
+      description: "re-spawn Claude with --continue if it crashes, only works for claude yet; --no-robust runs it once",
       alias: "r",
     })
+    .option("once", {
+      type: "boolean",
+      default: false,
+      description:
+        "Run the agent exactly once and exit with its real exit code: no crash restarts (--no-robust) and no fresh session when --continue finds none",
+    })
+    .option("patterns-file", {
+      type: "string",
+      description:
+        "JSON or TOML file (or https:// URL) of extra auto-response patterns, entries of { match, is_regex, response }, checked before the built-ins",
+    })
+    .option("patterns-cache-ttl", {
+      type: "string",
+      description: 'How long a --patterns-file URL is used from the cache before it is fetched again, e.g. "10m", "0" to always fetch',
+      default: "1h",
+    })
+    .option("tool", {
+      type: "string",
+      choices: SUPPORTED_CLIS,
+      description:
+        "Agent cli to run, e.g. codex or gemini, instead of the positional cli; its profile (binary, prompt and busy patterns, restart args) comes from the config",
+    })
+    .option("command", {
+      type: "string",
+      description:
+        "Binary to run instead of the cli's own, e.g. a version-pinned 'claude-1.2' symlink or a wrapper; prompts are still detected with the cli's patterns",
+    })
     .option("logFile", {
       type: "string",
-      description: "Rendered log file to write to.",
+      description:
+        "Rendered log file to write to. Placeholders {timestamp}, {pid}, {cwd} and {session} make it unique per run, e.g. 'logs/{cwd}-{timestamp}.log'",
+    })
+    .option("clean-log-file", {
+      type: "boolean",
+      default: false,
+      description:
+        "Remove ANSI control sequences from the rendered log files (--logFile and the session log) for greppable logs; stdout keeps its colors",
+    })
+    .option("stream-log", {
+      type: "string",
+      description:
+        "Append the output as plain text to this file as it arrives, so a killed run still leaves a log; same placeholders as --logFile",
+    })
+    .option("log-timestamps", {
+      type: "boolean",
+      default: false,
+      description: "Prefix each --stream-log line with an RFC 3339 timestamp",
+    })
+    .option("log-append", {
+      type: "boolean",
+      default: false,
+      description: "Append to an existing --stream-log file instead of truncating it",
+    })
+    .option("strip-prefix", {
+      type: "string",
+      description:
+        "Regex removed once from the start of the output and logs (first 16KB only), e.g. a banner the agent prints every session",
+    })
+    .option("strip-banner", {
+      type: "boolean",
+      default: false,
+      description: "Remove the known startup banner of the cli (e.g. Claude's welcome box) from the output and logs",
+    })
+    .option("color", {
+      type: "string",
+      choices: COLOR_MODES,
+      description:
+        "Colors in the output and agent-yes messages: auto, always or never. Defaults to never when NO_COLOR is set, auto otherwise",
+    })
+    .option("compact-logs", {
+      type: "boolean",
+      default: false,
+      description: "Collapse repeated lines (e.g. spinner frames) in the saved rendered logs into one with a (repeated N times) note",
+    })
+    .option("json-log", {
+      type: "string",
+      description: "Write newline-delimited JSON events (e.g. which prompt pattern matched) to this file",
+      alias: "events-file",
+    })
+    .option("events-fd", {
+      type: "number",
+      description: "Write the JSON events to this open file descriptor instead, e.g. 3 with `3>events.jsonl` or a pipe",
     })
     .option("prompt", {
       type: "string",
       description: "Prompt to send to Claude (also can be passed after --)",
       alias: "p",
     })
+    .option("type-prompt", {
+      type: "string",
+      description:
+        "Start the agent without a prompt, and type this one into its input box once it is ready, like a human would; follows --simulate-typing",
+    })
+    .option("prompt-file", {
+      type: "string",
+      description:
+        "Read the prompt from this file; lines like `#agent-yes: answer delete=no` in it set flags for the run and are not sent",
+    })
+    .option("guardrail", {
+      type: "string",
+      description:
+        "An instruction sent to the agent ahead of the prompt and again after each crash restart, e.g. \"Never run destructive git commands\"; works with every cli",
+    })
+    .option("guardrail-file", {
+      type: "string",
+      description: "Read the --guardrail instruction from this file, after any --guardrail text",
+    })
+    .option("system-prompt-file", {
+      type: "string",
+      description:
+        "Append this file to claude's system prompt (via --append-system-prompt), after any --append-system-prompt you pass",
+    })
     .option("verbose", {
       type: "boolean",
       description: "Enable verbose logging, will emit ./agent-yes.log",
       default: false,
     })
+    .option("print-config", {
+      type: "boolean",
+      description: "Print the effective configuration after merging the config file, --profile and flags, then exit",
+      default: false,
+    })
     .option("use-skills", {
       type: "boolean",
       description:
@@ -71,16 +211,340 @@ export function parseCliArgs(argv: string[]) {
       description: 'short idle time, will perform idle action when reached, e.g., "5s" or "1m"',
       alias: "i",
     })
+    .option("nudge-on-idle", {
+      type: "boolean",
+      description:
+        "When idle with no busy marker on screen, press Enter (up to 3 times) in case the agent waits on a prompt that wasn't recognized, before exiting on idle",
+      default: false,
+    })
+    .option("dry-run", {
+      type: "boolean",
+      description:
+        "Detect prompts and log which answer would be sent (and emit them as events), without answering; answer them yourself",
+      default: false,
+    })
+    .option("wait-on-rate-limit", {
+      type: "boolean",
+      description:
+        "On a usage/rate-limit message with a reset time, wait until the reset and send \"continue\" instead of exiting",
+      default: false,
+    })
+    .option("idle-mode", {
+      type: "string",
+      choices: IDLE_MODES,
+      description:
+        "What keeps the session from going idle: output-silence counts any output, stable-screen only changes to the rendered screen beyond spinner frames and counters",
+      default: "output-silence",
+    })
+    .option("busy-marker", {
+      type: "string",
+      array: true,
+      description:
+        "Screen text meaning the agent is still working, so it isn't idle, e.g. --busy-marker \"press esc to stop\"; case-insensitive, added to the cli's own markers",
+    })
+    .option("busy-markers-file", {
+      type: "string",
+      description: "Read more --busy-marker texts from this file, one per line, # comments allowed",
+    })
+    .option("idle-ignore-spinners", {
+      type: "boolean",
+      description:
+        "Only count output that changes the screen as activity, so a spinner animating over a hung tool doesn't keep the session from going idle; same as --idle-mode stable-screen",
+      default: false,
+    })
     .option("idle-action", {
       type: "string",
       description: 'Idle action to perform when idle time is reached, e.g., "exit" or "TODO.md"',
     })
+    .option("trust-mcp", {
+      type: "string",
+      choices: ["yes", "no", "ask"] as const,
+      description:
+        "How to answer MCP server trust prompts: yes trusts the server, no continues without it, ask leaves it to you",
+      default: "no",
+    })
+    .option("confirm-dangerous-once", {
+      type: "boolean",
+      default: false,
+      description:
+        "Ask you on the terminal before accepting the first delete prompt, and remember your answer for the rest of the session; declines when there is no terminal",
+    })
+    .option("confirm-destructive", {
+      type: "boolean",
+      default: false,
+      description:
+        "Never answer prompts about destructive actions (delete, rm -rf, force push, overwrite, git reset --hard); print a notice and let you answer them",
+    })
+    .option("answer", {
+      type: "string",
+      array: true,
+      description:
+        "Answer per prompt category, e.g. --answer delete=no --answer network=no. Categories: trust, delete, edit, shell, network, setup (all yes by default)",
+    })
+    .option("default-answer", {
+      type: "string",
+      choices: ["yes", "no"] as const,
+      description:
+        "Answer for prompts without an --answer for their category: no declines permission menus and answers y/n prompts n, so --answer <category>=yes becomes an allow-list",
+      default: "yes",
+    })
+    .option("profile", {
+      type: "string",
+      description:
+        "Apply a named preset of flags: ci, dev, safe, or one from `profiles` in the config file. Flags given explicitly override it",
+    })
+    .option("continue-fallback-fresh", {
+      type: "boolean",
+      default: true,
+      description:
+        "When --continue finds no conversation to continue, start a fresh session instead; --no-continue-fallback-fresh exits",
+    })
+    .option("on-exit", {
+      type: "string",
+      description:
+        "Shell command to run after the agent exits, with AGENT_YES_EXIT_CODE, AGENT_YES_REASON and AGENT_YES_LOG_PATH set",
+    })
+    .option("on-exit-affects-code", {
+      type: "boolean",
+      default: false,
+      description: "If the --on-exit command fails, exit with its exit code instead of the agent's",
+    })
+    .option("quiet", {
+      type: "boolean",
+      default: false,
+      alias: "q",
+      description:
+        "Only print the agent's output: no session summary, hints or info logs, just warnings and errors on stderr. Conflicts with --verbose",
+    })
+    .option("notify-url", {
+      type: "string",
+      description:
+        "POST a JSON summary (exit code, reason, runtime, auto-response count) to this URL when the session ends; failures only warn",
+    })
+    .option("notify-command", {
+      type: "string",
+      description:
+        "Shell command to run when the session ends, with AGENT_YES_EXIT_CODE, AGENT_YES_REASON, AGENT_YES_DURATION_MS and AGENT_YES_RESPONSES set; failures only warn",
+    })
+    .option("input-gating", {
+      type: "boolean",
+      default: true,
+      description:
+        "Wait for the agent to be ready before sending input and responses; --no-input-gating sends right away for scripted runs (may send before the agent is ready)",
+    })
+    .option("prompt-stdin", {
+      type: "boolean",
+      description:
+        "Read the prompt from stdin until EOF (e.g. a here-doc), then keep the session interactive on the terminal; the default for piped stdin without a prompt, --no-prompt-stdin forwards it as input",
+    })
+    .option("max-session-bytes", {
+      type: "string",
+      description:
+        'Stop the agent with exit code 3 once its raw output exceeds this size, e.g. "500MB", a safety cap for runaway sessions and log files',
+    })
+    .option("pager-action", {
+      type: "string",
+      choices: ["space", "quit"] as const,
+      default: "space",
+      description:
+        'How to answer pager prompts ("--More--", "(END)") from commands the agent runs: space pages on, quit leaves. PAGER and GIT_PAGER default to cat',
+    })
+    .option("pick-session", {
+      type: "string",
+      description:
+        "Which entry to pick when --resume shows a session list: first (most recent), last, or an entry number; unset leaves the list to you",
+    })
+    .option("auto-dismiss-notices", {
+      type: "boolean",
+      default: true,
+      description:
+        "Press Enter on update/what's-new notices that wait for a keypress at startup; --no-auto-dismiss-notices leaves them to you",
+    })
+    .option("auto-type-confirmations", {
+      type: "boolean",
+      description:
+        'Answer "type DELETE to confirm" prompts by typing the word; off by default, they are meant to be high-friction',
+      default: false,
+    })
+    .option("control-socket", {
+      type: "string",
+      description:
+        "Listen on this unix socket for newline-delimited commands (send <text>, keys <json>, ping) to steer the session, see docs/control-socket.md",
+    })
+    .option("status-port", {
+      type: "number",
+      description: "Serve a JSON status of the session (state, idle time, prompts answered) on http://127.0.0.1:<port>/status",
+    })
+    .option("split-stderr", {
+      type: "boolean",
+      description:
+        "Run the agent with stderr on its own log file (<pid>.stderr.log) instead of the pty; the agent may notice stderr is not a terminal",
+      default: false,
+    })
+    .option("max-restarts", {
+      type: "number",
+      description: "Give up after the agent crashed and was restarted this many times (see --robust); unlimited by default",
+    })
+    .option("restart-backoff", {
+      type: "string",
+      description: 'Wait this long before restarting a crashed agent, doubled for each further restart, e.g. "2s"',
+      default: "0",
+    })
+    .option("max-runtime", {
+      type: "string",
+      description:
+        'Stop the agent and exit with 124 after this long, e.g. "30m", even if it keeps printing output; unlike --exit-on-idle it always fires',
+    })
+    .option("kill-on-parent-exit", {
+      type: "boolean",
+      description: "Stop the agent and exit when the parent process (e.g. a CI job or SSH session) is gone",
+      default: false,
+    })
+    .option("graceful-idle-exit", {
+      type: "string",
+      description:
+        'On --exit-on-idle, how long to wait for the agent to exit after its exit command (e.g. /exit) before killing it, so it can save the session for --continue. "0" kills right away',
+      default: "5s",
+    })
+    .option("control-chars", {
+      type: "string",
+      array: true,
+      description:
+        "Forward or drop control characters on stdout, e.g. --control-chars bel=drop. Names: nul, bel, bs, vt, ff, so, si, del. Logs always drop them",
+    })
+    .option("log-filter", {
+      type: "string",
+      description:
+        'Per-module log levels, e.g. "info,send=debug,ready=debug"; a module is the tag before "|" in log lines. Defaults to $AGENT_YES_LOG',
+    })
+    .option("only-answer", {
+      type: "string",
+      array: true,
+      description:
+        "Only auto-answer prompts whose text matches one of these regexes, repeatable; everything else is left to you. Given without a value, nothing is answered",
+    })
+    .option("menu-response", {
+      type: "string",
+      description:
+        'Keys sent to accept permission menus instead of Enter, escapes like \\r \\n \\e allowed, e.g. "\\e[B\\r" (arrow down + Enter)',
+    })
+    .option("select-label", {
+      type: "string",
+      description:
+        'Accept permission menus by picking the option with this label, e.g. "Yes", instead of the highlighted one; Enter (or --menu-response) when none matches',
+    })
+    .option("yn-response", {
+      type: "string",
+      description: 'Text typed for y/n prompts instead of "y\\n", e.g. "yes\\n"',
+    })
+    .option("simulate-typing", {
+      type: "string",
+      description:
+        'Type prompts and responses one character at a time with this delay, e.g. "20ms", for TUIs that drop fast input',
+    })
+    .option("answer-delay-jitter", {
+      type: "string",
+      description:
+        'Wait a random extra delay up to this long before each auto-response, e.g. "200ms", so timing races don\'t hit the same moment every run',
+    })
+    .option("jitter-seed", {
+      type: "number",
+      description: "Seed for --answer-delay-jitter, makes the delays the same on every run for reproducing a failure",
+    })
+    .option("record-prompts", {
+      type: "string",
+      description:
+        "Save the raw output around each detected prompt to this directory, one fixture file per prompt",
+    })
+    .option("record-prompts-max", {
+      type: "number",
+      description: "Max prompt fixtures to save per run with --record-prompts",
+      default: 20,
+    })
+    .option("pty-size", {
+      type: "string",
+      description: 'Fixed pty size as COLSxROWS, e.g. "120x40", instead of following the real terminal',
+    })
+    .option("expect", {
+      type: "string",
+      array: true,
+      description:
+        "Regex the final output must match, repeatable; exit code is 0 only if all match. Combine with --exit-on-idle for CI",
+    })
+    .option("prompt-debounce", {
+      type: "string",
+      description:
+        'How long the output must be quiet before a detected prompt is answered, e.g. "100ms"; "0" answers immediately',
+      default: "100ms",
+    })
+    .option("response-delay", {
+      type: "string",
+      description:
+        'How long the output must be idle before a prompt is answered with Enter, e.g. "100ms" for fast local runs or "1s" for laggy remote sessions',
+      default: "400ms",
+    })
+    .option("idle-poll-interval", {
+      type: "string",
+      description: 'How often idle waits (--exit-on-idle, answers) re-check the output, e.g. "50ms" or "1s"',
+      default: "100ms",
+    })
+    .option("ready-timeout", {
+      type: "string",
+      description:
+        'How long input waits for the agent to be ready before it is sent anyway, e.g. "30s" for agents slow to start',
+      default: "10s",
+    })
+    .option("stdout-flush-interval", {
+      type: "string",
+      description:
+        'Batch stdout writes for up to this long, e.g. "8ms", fewer syscalls for chatty output; "0" writes each chunk right away',
+      default: "8ms",
+    })
+    .option("max-prompt-age", {
+      type: "number",
+      description:
+        "Only answer prompts within this many lines of the bottom of the screen, so prompt text redrawn from scrollback can't trigger an answer; 0 for no limit",
+      default: 0,
+    })
+    .option("passthrough", {
+      type: "boolean",
+      default: true,
+      description: "Print the agent output; use --no-passthrough to run silently while still logging and auto-answering",
+    })
+    .option("fail-on", {
+      type: "string",
+      array: true,
+      description:
+        "Regex marking an error in the output, repeatable; if any matches, exit code is 1 even when the agent exited cleanly. Takes precedence over --expect",
+    })
+    .option("transcript", {
+      type: "string",
+      description: "Write a Markdown transcript of the session (prompt, answered prompts, output) to this path",
+    })
+    .option("cast-file", {
+      type: "string",
+      description: "Record the session (output and auto-responses, with timing) to this path as an asciicast v2 file",
+    })
+    .option("prefix", {
+      type: "string",
+      description: 'Label prepended to each output line, e.g. "[api] ", useful when merging output of many agents',
+    })
+    .option("kill-timeout", {
+      type: "string",
+      description: 'How long to wait for the agent to exit after an interrupt before sending SIGKILL, e.g. "5s"',
+      default: "5s",
+    })
     .option("queue", {
       type: "boolean",
       description:
         "Queue Agent Commands when spawning multiple agents in the same directory/repo, can be disabled with --no-queue",
       default: false,
     })
+    .option("session-dir", {
+      type: "string",
+      description:
+        "Where to keep agent-yes state (codex sessions, caches, the queue lock), defaults to ~/.config/agent-yes, also set by CLAUDE_YES_SESSION_DIR",
+    })
     .option("install", {
       type: "boolean",
       description: "Automatically Install/Update the CLI if not found or outdated",
@@ -97,6 +561,12 @@ export function parseCliArgs(argv: string[]) {
       type: "string",
       description: "Send a prompt to the active agent's stdin in current directory",
     })
+    .option("tty", {
+      type: "boolean",
+      description:
+        "Use the terminal's raw mode for stdin; --no-tty reads stdin as plain bytes even when it's a terminal, e.g. for CI, pipelines and nohup",
+      default: true,
+    })
     .option("stdpush", {
       type: "boolean",
       description: "Enable external input stream to push additional data to stdin",
@@ -110,6 +580,7 @@ export function parseCliArgs(argv: string[]) {
       demandOption: false,
       default: cliName,
     })
+    .config({ ...promptFile?.options, ...profile })
     .help()
     .version(pkg.version)
     .parserConfiguration({
@@ -131,6 +602,7 @@ export function parseCliArgs(argv: string[]) {
   Object.keys(parsedArgv).forEach((key) => {
     if (key !== "_" && key !== "$0" && parsedArgv[key as keyof typeof parsedArgv] !== undefined) {
       yargsConsumed.add(`--${key}`);
+      if (parsedArgv[key as keyof typeof parsedArgv] === false) yargsConsumed.add(`--no-${key}`); // negated booleans
       // Add short aliases
       if (key === "prompt") yargsConsumed.add("-p");
       if (key === "robust") yargsConsumed.add("-r");
@@ -140,12 +612,14 @@ export function parseCliArgs(argv: string[]) {
     }
   });
 
+  const toolName = parsedArgv.tool || cliName; // --tool picks the cli like the script name does
+
   const cliArgsForSpawn = (() => {
-    if (parsedArgv._[0] && !cliName) {
+    if (parsedArgv._[0] && !toolName) {
       // Explicit CLI name provided as positional arg
       return rawArgs.slice((cliArgIndex ?? 0) + 1, dashIndex ?? undefined);
-    } else if (cliName) {
-      // CLI name from script, filter out only what yargs consumed
+    } else if (toolName) {
+      // CLI name from script or --tool, filter out only what yargs consumed
       const result: string[] = [];
       const argsToCheck = rawArgs.slice(0, dashIndex ?? undefined);
 
@@ -174,15 +648,37 @@ export function parseCliArgs(argv: string[]) {
   const dashPrompt: string | undefined =
     dashIndex === undefined ? undefined : rawArgs.slice(dashIndex + 1).join(" ");
 
+  const logFilter = parsedArgv.logFilter ?? process.env.AGENT_YES_LOG; // like RUST_LOG
+  const cli = (toolName ||
+    parsedArgv.cli ||
+    (dashIndex !== 0 ? parsedArgv._[0]?.toString()?.replace?.(/-yes$/, "") : undefined)) as (typeof SUPPORTED_CLIS)[number];
+
+  // a preset's quiet or verbose gives way to the other one given explicitly, e.g. `--profile ci --verbose`
+  if (parsedArgv.quiet && parsedArgv.verbose) {
+    if (!hasFlag(argv.slice(2), "--quiet", "-q")) parsedArgv.quiet = false;
+    else if (!hasFlag(argv.slice(2), "--verbose")) parsedArgv.verbose = false;
+    else throw new Error(`--quiet and --verbose can't be used together`);
+  }
+
+  // --system-prompt-file: appended to claude's system prompt, see systemPrompt.ts
+  const systemPromptFile = parsedArgv.systemPromptFile;
+  if (systemPromptFile && cli && cli !== "claude")
+    throw new Error(`--system-prompt-file is only supported for claude, not ${cli}`);
+  const cliArgs = systemPromptFile
+    ? appendSystemPromptArg(cliArgsForSpawn, readSystemPromptFile(systemPromptFile))
+    : cliArgsForSpawn;
+
   // Return the config object that would be passed to cliYes (same logic as cli.ts:99-121)
   return {
     cwd: process.cwd(),
     env: process.env as Record<string, string>,
-    cli: (cliName ||
-      parsedArgv.cli ||
-      (dashIndex !== 0 ? parsedArgv._[0]?.toString()?.replace?.(/-yes$/, "") : undefined)) as (typeof SUPPORTED_CLIS)[number],
-    cliArgs: cliArgsForSpawn,
-    prompt: [parsedArgv.prompt, dashPrompt].filter(Boolean).join(" ") || undefined,
+    cli,
+    cliArgs,
+    prompt: [parsedArgv.prompt, promptFile?.prompt, dashPrompt].filter(Boolean).join(" ") || undefined,
+    guardrail:
+      [parsedArgv.guardrail, parsedArgv.guardrailFile && readGuardrailFile(parsedArgv.guardrailFile)]
+        .filter(Boolean)
+        .join("\n\n") || undefined,
     install: parsedArgv.install,
     exitOnIdle: Number(
       (parsedArgv.idle || parsedArgv.exitOnIdle)?.replace(/.*/, (e) =>
@@ -190,12 +686,140 @@ export function parseCliArgs(argv: string[]) {
       ) || 0,
     ),
     queue: parsedArgv.queue,
-    robust: parsedArgv.robust,
+    robust: parsedArgv.robust && !parsedArgv.once,
     logFile: parsedArgv.logFile,
+    streamLog: parsedArgv.streamLog,
+    cleanLogFile: parsedArgv.cleanLogFile,
+    logTimestamps: parsedArgv.logTimestamps,
+    logAppend: parsedArgv.logAppend,
+    jsonLog: parsedArgv.jsonLog,
+    eventsFd: parsedArgv.eventsFd,
+    compactLogs: parsedArgv.compactLogs,
+    stripPrefix: parsedArgv.stripPrefix ? parseExpectations([parsedArgv.stripPrefix], "--strip-prefix")[0] : undefined,
+    stripBanner: parsedArgv.stripBanner,
+    color: parsedArgv.color as ColorMode | undefined, // unset: follow NO_COLOR, see resolveColorMode
+    trustMcp: parsedArgv.trustMcp as "yes" | "no" | "ask",
+    prefix: parsedArgv.prefix,
+    confirmDangerousOnce: parsedArgv.confirmDangerousOnce,
+    confirmDestructive: parsedArgv.confirmDestructive,
+    answers: parseAnswerMap((parsedArgv.answer ?? []).map(String)),
+    defaultAnswer: parsedArgv.defaultAnswer as "yes" | "no",
+    onlyAnswer: parsedArgv.onlyAnswer && parseExpectations(parsedArgv.onlyAnswer.map(String), "--only-answer"),
+    menuResponse: parsedArgv.menuResponse !== undefined ? unescapeKeys(parsedArgv.menuResponse) : undefined,
+    selectLabel: parsedArgv.selectLabel,
+    ynResponse: parsedArgv.ynResponse !== undefined ? unescapeKeys(parsedArgv.ynResponse) : undefined,
+    simulateTyping: parsedArgv.simulateTyping ? parseDurationFlag("--simulate-typing", parsedArgv.simulateTyping) : 0,
+    answerDelayJitter: parsedArgv.answerDelayJitter
+      ? parseDurationFlag("--answer-delay-jitter", parsedArgv.answerDelayJitter)
+      : 0,
+    jitterSeed: parsedArgv.jitterSeed,
+    recordPrompts: parsedArgv.recordPrompts,
+    recordPromptsMax: parsedArgv.recordPromptsMax,
+    ptySize: parsedArgv.ptySize ? parsePtySize(parsedArgv.ptySize) : undefined,
+    transcript: parsedArgv.transcript,
+    castFile: parsedArgv.castFile,
+    passthrough: parsedArgv.passthrough,
+    promptDebounce: parseDurationFlag("--prompt-debounce", parsedArgv.promptDebounce),
+    maxPromptAge: parsedArgv.maxPromptAge,
+    responseDelay: parseDurationFlag("--response-delay", parsedArgv.responseDelay),
+    idlePollInterval: parseDurationFlag("--idle-poll-interval", parsedArgv.idlePollInterval, { min: 1 }),
+    readyTimeout: parseDurationFlag("--ready-timeout", parsedArgv.readyTimeout, { min: 1 }),
+    stdoutFlushInterval: parseDurationFlag("--stdout-flush-interval", parsedArgv.stdoutFlushInterval),
+    expect: parseExpectations((parsedArgv.expect ?? []).map(String)),
+    failOn: parseExpectations((parsedArgv.failOn ?? []).map(String), "--fail-on"),
+    killTimeout: parseDurationFlag("--kill-timeout", parsedArgv.killTimeout),
     verbose: parsedArgv.verbose,
+    logFilter: logFilter ? parseLogFilter(logFilter) : undefined,
+    controlSocket: parsedArgv.controlSocket,
+    statusPort: parsedArgv.statusPort,
+    killOnParentExit: parsedArgv.killOnParentExit,
+    maxRestarts: parsedArgv.maxRestarts,
+    restartBackoff: parseDurationFlag("--restart-backoff", parsedArgv.restartBackoff),
+    maxRuntime: parsedArgv.maxRuntime ? parseDurationFlag("--max-runtime", parsedArgv.maxRuntime) : 0,
+    idleIgnoreSpinners: parsedArgv.idleIgnoreSpinners,
+    idleMode: (parsedArgv.idleIgnoreSpinners ? "stable-screen" : parsedArgv.idleMode) as IdleMode,
+    busyMarkers: [
+      ...(parsedArgv.busyMarker ?? []).map(String),
+      ...(parsedArgv.busyMarkersFile ? parseBusyMarkersFile(readFileSync(parsedArgv.busyMarkersFile, "utf8")) : []),
+    ].map(busyMarkerPattern),
+    splitStderr: parsedArgv.splitStderr,
+    nudgeOnIdle: parsedArgv.nudgeOnIdle,
+    waitOnRateLimit: parsedArgv.waitOnRateLimit,
+    dryRun: parsedArgv.dryRun,
+    command: parsedArgv.command,
+    patternsFile: parsedArgv.patternsFile,
+    patternsCacheTtl: parseDurationFlag("--patterns-cache-ttl", parsedArgv.patternsCacheTtl),
+    pagerAction: parsedArgv.pagerAction as "space" | "quit",
+    pickSession: parsedArgv.pickSession !== undefined ? parsePickSession(parsedArgv.pickSession) : undefined,
+    autoDismissNotices: parsedArgv.autoDismissNotices,
+    autoTypeConfirmations: parsedArgv.autoTypeConfirmations,
+    promptStdin: parsedArgv.promptStdin,
+    typePrompt: parsedArgv.typePrompt,
+    continueFallbackFresh: parsedArgv.continueFallbackFresh && !parsedArgv.once,
+    sessionDir: parsedArgv.sessionDir,
+    inputGating: parsedArgv.inputGating,
+    onExit: parsedArgv.onExit,
+    onExitAffectsCode: parsedArgv.onExitAffectsCode,
+    quiet: parsedArgv.quiet,
+    notifyUrl: parsedArgv.notifyUrl,
+    notifyCommand: parsedArgv.notifyCommand,
+    maxSessionBytes: parsedArgv.maxSessionBytes ? parseByteSize(parsedArgv.maxSessionBytes) : undefined,
+    gracefulIdleExit: parseDurationFlag("--graceful-idle-exit", parsedArgv.gracefulIdleExit),
+    controlChars: parseControlCharPolicy((parsedArgv.controlChars ?? []).map(String)),
     resume: parsedArgv.continue, // Note: intentional use resume here to avoid preserved keyword (continue)
     useSkills: parsedArgv.useSkills,
     appendPrompt: parsedArgv.appendPrompt,
+    printConfig: parsedArgv.printConfig,
+    tty: parsedArgv.tty,
     useFifo: Boolean(parsedArgv.stdpush || parsedArgv.ipc || parsedArgv.fifo), // Support --stdpush, --ipc, and --fifo (backward compatibility)
   };
 }
+
+/**
+ * Turn escapes typed on the command line into the keys they stand for,
+ * e.g. "yes\\n" -> "yes\n", "\\e[B\\r" -> arrow down + Enter
+ */
+function unescapeKeys(text: string): string {
+  const escapes: Record<string, string> = { n: "\n", r: "\r", t: "\t", e: "\u001b", "\\": "\\" };
+  return text.replace(/\\(x[0-9a-fA-F]{2}|.)/g, (whole, code: string) =>
+    code.startsWith("x") && code.length === 3
+      ? String.fromCharCode(parseInt(code.slice(1), 16))
+      : (escapes[code] ?? whole),
+  );
+}
+
+/**
+ * Parse a duration flag like "400ms" or "1s", naming the flag on a bad one
+ * A bare number (e.g. from a profile in the config file) is milliseconds.
+ */
+function parseDurationFlag(flag: string, value: string | number, { min = 0 } = {}): number {
+  const duration = ms(String(value) as ms.StringValue) as number | undefined;
+  if (typeof duration !== "number" || !Number.isFinite(duration) || duration < min)
+    throw new Error(`Invalid ${flag} duration "${value}", expected e.g. "200ms" or "1s"${min ? `, at least ${min}ms` : ""}`);
+  return duration;
+}
+
+/**
+ * Find a flag's value in raw args before yargs runs, up to the prompt separator `--`
+ * e.g. findFlagValue(["--profile", "ci"], "--profile") -> "ci", the last one wins
+ */
+export function findFlagValue(args: string[], flag: string): string | undefined {
+  const dashIndex = args.indexOf("--");
+  const flags = dashIndex === -1 ? args : args.slice(0, dashIndex);
+  let found: string | undefined;
+  flags.forEach((arg, i) => {
+    if (arg.startsWith(`${flag}=`)) found = arg.slice(flag.length + 1);
+    else if (arg === flag && flags[i + 1] && !flags[i + 1]!.startsWith("-")) found = flags[i + 1];
+  });
+  return found;
+}
+
+/**
+ * Whether a flag was given in raw args, up to the prompt separator `--`
+ * e.g. hasFlag(["--quiet=true", "claude"], "--quiet", "-q") -> true
+ */
+function hasFlag(args: string[], ...names: string[]): boolean {
+  const dashIndex = args.indexOf("--");
+  const flags = dashIndex === -1 ? args : args.slice(0, dashIndex);
+  return flags.some((arg) => names.some((name) => arg === name || arg.startsWith(`${name}=`)));
+}
@@ -184,8 +184,12 @@ export class PidStore {
     logger.debug(`[pidStore] Updated process ${pid} status=${status}`);
   }
 
+  getLogDir(): string {
+    return path.resolve(this.storeDir, "logs");
+  }
+
   getLogPath(pid: number): string {
-    return path.resolve(this.storeDir, "logs", `${pid}.log`);
+    return path.resolve(this.getLogDir(), `${pid}.log`);
   }
 
   getFifoPath(pid: number): string {
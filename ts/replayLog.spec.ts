@@ -0,0 +1,46 @@
+import { describe, expect, it } from "vitest";
+import type { AgentCliConfig } from "./index";
+import { replayOutput } from "./core/replay";
+import { formatReplaySummary, readReplayChunks, splitRawLog } from "./replayLog";
+
+const conf: AgentCliConfig = { ready: [/\? for shortcuts/], enter: [/❯ 1\. Yes/] };
+
+describe("splitRawLog", () => {
+  it("should split into lines keeping their newlines", () => {
+    expect(splitRawLog("a\nb\nc")).toEqual(["a\n", "b\n", "c"]);
+    expect(splitRawLog("a\n\n")).toEqual(["a\n", "\n"]);
+    expect(splitRawLog("")).toEqual([]);
+  });
+});
+
+describe("readReplayChunks", () => {
+  it("should replay a cast's output events, and a raw log's lines", () => {
+    const cast = [
+      '{"version":2,"width":80,"height":24,"timestamp":1767225600}',
+      '[0.1,"o","Do you want to make this edit?\\r\\n\\u001b[36m❯ 1. Ye"]',
+      '[0.2,"o","s\\u001b[0m\\r\\n"]',
+      '[0.9,"i","\\r"]',
+      "",
+    ].join("\n");
+    const chunks = readReplayChunks(cast);
+    expect(chunks).toEqual(["Do you want to make this edit?\r\n\u001b[36m❯ 1. Ye", "s\u001b[0m\r\n"]);
+    expect(replayOutput(chunks, conf).responses.map((r) => r.rule)).toEqual(["enter[0]"]);
+
+    expect(readReplayChunks("a\nb")).toEqual(["a\n", "b"]);
+  });
+
+  it("should reject a cast with a broken event line", () => {
+    expect(() => readReplayChunks('{"version":2,"width":80,"height":24}\n[0.1,"o",\n')).toThrow(/line 2/);
+  });
+});
+
+describe("formatReplaySummary", () => {
+  it("should list detections with offsets, then the final render", () => {
+    const log = "Do you want to make this edit?\n\u001b[36m❯ 1. Yes\u001b[0m\n  2. No\n";
+    const summary = formatReplaySummary(replayOutput(splitRawLog(log), conf)).split("\n");
+    expect(summary[0]).toMatch(/^OFFSET\s+RULE\s+ACTION\s+RESPONSE\s+LINE$/);
+    expect(summary[1]).toMatch(/^49\s+enter\[0\]\s+enter\s+"\\r"\s+❯ 1\. Yes$/);
+    expect(summary).toContain("1 prompt(s) would have been answered, agent state at the end: starting");
+    expect(summary.slice(summary.indexOf("--- final render ---") + 1).join("\n")).toContain("2. No");
+  });
+});
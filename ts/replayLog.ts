@@ -0,0 +1,92 @@
+import { readFileSync } from "fs";
+import yargs from "yargs";
+import type { AgentCliConfig } from "./index.ts";
+import { replayOutput, type ReplayResult } from "./core/replay.ts";
+import { parseCastOutput } from "./core/cast.ts";
+
+/**
+ * `agent-yes replay-log`: run a captured raw log or cast through prompt detection offline
+ *
+ * Replays a `<pid>.raw.log` (raw pty output, see `.agent-yes/logs/`) or a
+ * `--cast-file` recording through the same rendering and detection as a
+ * live session, like `--dry-run` without the agent, and reports
+ * which prompts would have been answered and how, with the final screen.
+ * Capture a problematic session once, then iterate on the patterns against
+ * it without starting the agent again.
+ *
+ * @example
+ * ```bash
+ * agent-yes replay-log .agent-yes/logs/12345.raw.log --cli claude
+ * agent-yes replay-log run.cast
+ * ```
+ */
+
+/**
+ * Split a raw log into line chunks, so each line is detected against the screen as it was then
+ *
+ * @example
+ * ```typescript
+ * splitRawLog("a\nb\nc"); // ["a\n", "b\n", "c"]
+ * ```
+ */
+export function splitRawLog(content: string): string[] {
+  return content.match(/[^\n]*\n|[^\n]+$/g) ?? [];
+}
+
+/**
+ * Split a replayed file into output chunks: a cast's output events as they arrived, or a raw log's lines
+ *
+ * @example
+ * ```typescript
+ * readReplayChunks('{"version":2,"width":80,"height":24}\n[0.1,"o","a\\nb"]\n'); // ["a\nb"]
+ * readReplayChunks("a\nb"); // ["a\n", "b"]
+ * ```
+ */
+export function readReplayChunks(content: string): string[] {
+  return parseCastOutput(content) ?? splitRawLog(content);
+}
+
+/**
+ * Format the detections of a replay as a table, followed by the final render
+ *
+ * @example
+ * ```typescript
+ * formatReplaySummary(replayOutput(splitRawLog(log), CLIS_CONFIG.claude));
+ * // OFFSET  RULE      ACTION  RESPONSE  LINE
+ * // 1532    enter[0]  enter   "\r"      ❯ 1. Yes
+ * // ...
+ * ```
+ */
+export function formatReplaySummary({ responses, render, state }: ReplayResult): string {
+  const rows = [
+    ["OFFSET", "RULE", "ACTION", "RESPONSE", "LINE"],
+    ...responses.map((r) => [String(r.offset), r.rule, r.action, JSON.stringify(r.response), r.line.trim()]),
+  ];
+  const widths = rows[0]!.map((_, i) => Math.max(...rows.map((row) => row[i]!.length)));
+  const table = rows.map((row) => row.map((cell, i) => (i < row.length - 1 ? cell.padEnd(widths[i]!) : cell)).join("  "));
+  return [
+    ...table,
+    "",
+    `${responses.length} prompt(s) would have been answered, agent state at the end: ${state}`,
+    "",
+    "--- final render ---",
+    render,
+  ].join("\n");
+}
+
+/**
+ * Run `agent-yes replay-log` with its args, resolves with the exit code
+ */
+export async function runReplayLogCli(argv: string[], clis: Record<string, AgentCliConfig>): Promise<number> {
+  const args = yargs(argv)
+    .usage("Usage: $0 replay-log <raw-log-or-cast> [--cli claude]")
+    .option("cli", { type: "string", default: "claude", description: "Cli whose patterns to detect prompts with" })
+    .demandCommand(1, "Missing the raw log or cast file to replay")
+    .parseSync();
+
+  const conf = clis[args.cli];
+  if (!conf) throw new Error(`Unknown cli: ${args.cli}, available: ${Object.keys(clis).join(", ")}`);
+  const content = readFileSync(String(args._[0]), "utf8");
+  console.log(formatReplaySummary(replayOutput(readReplayChunks(content), conf)));
+  return 0;
+}
@@ -0,0 +1,53 @@
+import { mkdtemp, readFile, rm } from "fs/promises";
+import os from "os";
+import path from "path";
+import { afterEach, beforeEach, describe, expect, it } from "vitest";
+import { SESSION_DIR_ENV } from "../core/stateDir";
+import {
+  claudeResumeArgs,
+  extractClaudeSessionId,
+  getClaudeSessionForCwd,
+  getClaudeSessionsFile,
+  storeClaudeSessionForCwd,
+} from "./claudeSessionManager";
+
+const ID = "3f2b8c1e-9d4a-4e6b-8f1a-2c3d4e5f6a7b";
+
+describe("extractClaudeSessionId", () => {
+  it("should extract session ids printed by claude", () => {
+    expect(extractClaudeSessionId(`  Session ID: ${ID}`)).toBe(ID);
+    expect(extractClaudeSessionId(`Resume this session with: claude --resume ${ID}`)).toBe(ID);
+  });
+
+  it("should ignore other uuids", () => {
+    expect(extractClaudeSessionId(`Created request ${ID}`)).toBeNull();
+    expect(extractClaudeSessionId("Session ID: none")).toBeNull();
+  });
+});
+
+describe("claude session store", () => {
+  let dir: string;
+
+  beforeEach(async () => {
+    dir = await mkdtemp(path.join(os.tmpdir(), "agent-yes-claude-sessions-"));
+    process.env[SESSION_DIR_ENV] = dir;
+  });
+
+  afterEach(async () => {
+    delete process.env[SESSION_DIR_ENV];
+    await rm(dir, { recursive: true, force: true });
+  });
+
+  it("should store and resume sessions per directory", async () => {
+    expect(await getClaudeSessionForCwd("/work/a")).toBeNull();
+    expect(claudeResumeArgs(await getClaudeSessionForCwd("/work/a"))).toEqual(["--continue"]);
+
+    await storeClaudeSessionForCwd("/work/a", ID);
+    expect(await getClaudeSessionForCwd("/work/a")).toBe(ID);
+    expect(await getClaudeSessionForCwd("/work/b")).toBeNull();
+    expect(claudeResumeArgs(await getClaudeSessionForCwd("/work/a"))).toEqual(["--resume", ID]);
+
+    const saved = JSON.parse(await readFile(getClaudeSessionsFile(), "utf8"));
+    expect(saved["/work/a"]).toMatchObject({ sessionId: ID, lastUsed: expect.any(String) });
+  });
+});
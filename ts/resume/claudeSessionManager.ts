@@ -0,0 +1,81 @@
+import { mkdir, readFile, writeFile } from "fs/promises";
+import path from "path";
+import { getStateDir } from "../core/stateDir.ts";
+
+/**
+ * Per-directory claude sessions, like codexSessionManager.ts does for codex
+ *
+ * `claude --continue` resumes the most recent conversation, which is not
+ * always the one agent-yes ran in this directory (e.g. after an interactive
+ * claude session in between). Session ids claude prints, e.g. on `/status`
+ * or in "claude --resume <id>" on exit, are stored per working directory
+ * and resumed with `--resume <id>` on `--continue` and crash restarts.
+ */
+
+// Allow overriding for testing, and with --session-dir
+export const getClaudeSessionsFile = () => path.join(getStateDir(), "claude-sessions.json");
+
+export interface ClaudeSessionMap {
+  [cwd: string]: {
+    sessionId: string;
+    lastUsed: string; // ISO timestamp
+  };
+}
+
+const SESSION_ID = /\b[0-9a-f]{8}-[0-9a-f]{4}-[0-9a-f]{4}-[0-9a-f]{4}-[0-9a-f]{12}\b/i;
+const SESSION_CONTEXT = /session|conversation|--resume/i; // other uuids in the output aren't session ids
+
+/**
+ * Load the session map from the state dir
+ */
+export async function loadClaudeSessionMap(): Promise<ClaudeSessionMap> {
+  try {
+    return JSON.parse(await readFile(getClaudeSessionsFile(), "utf-8"));
+  } catch {
+    return {}; // missing or invalid
+  }
+}
+
+/**
+ * Store the session id for a working directory
+ */
+export async function storeClaudeSessionForCwd(cwd: string, sessionId: string): Promise<void> {
+  const sessionMap = await loadClaudeSessionMap();
+  if (sessionMap[cwd]?.sessionId === sessionId) return; // printed again, e.g. a redraw
+  sessionMap[cwd] = { sessionId, lastUsed: new Date().toISOString() };
+  try {
+    const sessionsFile = getClaudeSessionsFile();
+    await mkdir(path.dirname(sessionsFile), { recursive: true });
+    await writeFile(sessionsFile, JSON.stringify(sessionMap, null, 2));
+  } catch (error) {
+    console.warn("Failed to save claude session map:", error);
+  }
+}
+
+/**
+ * Get the stored session id for a working directory
+ */
+export async function getClaudeSessionForCwd(cwd: string): Promise<string | null> {
+  return (await loadClaudeSessionMap())[cwd]?.sessionId || null;
+}
+
+/**
+ * Extract a session id from a line of claude output
+ *
+ * @example
+ * ```typescript
+ * extractClaudeSessionId("Session ID: 3f2b8c1e-9d4a-4e6b-8f1a-2c3d4e5f6a7b"); // "3f2b8c1e-9d4a-4e6b-8f1a-2c3d4e5f6a7b"
+ * extractClaudeSessionId("Created request 3f2b8c1e-9d4a-4e6b-8f1a-2c3d4e5f6a7b"); // null, not a session
+ * ```
+ */
+export function extractClaudeSessionId(line: string): string | null {
+  if (!SESSION_CONTEXT.test(line)) return null;
+  return line.match(SESSION_ID)?.[0] ?? null;
+}
+
+/**
+ * Args resuming the directory's session, `--continue` when none is stored
+ */
+export function claudeResumeArgs(sessionId: string | null): string[] {
+  return sessionId ? ["--resume", sessionId] : ["--continue"];
+}
@@ -1,12 +1,10 @@
 import { mkdir, readdir, readFile, writeFile } from "fs/promises";
 import { homedir } from "os";
 import path from "path";
+import { getStateDir } from "../core/stateDir.ts";
 
-// Allow overriding for testing
-export const getSessionsFile = () =>
-  process.env.CLI_YES_TEST_HOME
-    ? path.join(process.env.CLI_YES_TEST_HOME, ".config", "agent-yes", "codex-sessions.json")
-    : path.join(homedir(), ".config", "agent-yes", "codex-sessions.json");
+// Allow overriding for testing, and with --session-dir
+export const getSessionsFile = () => path.join(getStateDir(), "codex-sessions.json");
 
 export const getCodexSessionsDir = () =>
   process.env.CLI_YES_TEST_HOME
@@ -3,6 +3,7 @@ import { existsSync } from "fs";
 import { mkdir, readFile, rename, writeFile } from "fs/promises";
 import { homedir } from "os";
 import path from "path";
+import { SESSION_DIR_ENV, getStateDir } from "./core/stateDir.ts";
 
 export interface Task {
   cwd: string;
@@ -24,7 +25,8 @@ interface LockCheckResult {
   lockKey: string;
 }
 
-const getLockDir = () => path.join(process.env.CLAUDE_YES_HOME || homedir(), ".claude-yes");
+const getLockDir = () =>
+  process.env[SESSION_DIR_ENV] ? getStateDir() : path.join(process.env.CLAUDE_YES_HOME || homedir(), ".claude-yes");
 const getLockFile = () => path.join(getLockDir(), "running.lock.json");
 const MAX_RETRIES = 5;
 const RETRY_DELAYS = [50, 100, 200, 400, 800]; // exponential backoff in ms
@@ -0,0 +1,30 @@
+import { describe, expect, it } from "vitest";
+import { parsePatternsFile } from "./core/patternsFile";
+import { configSchema, patternsFileSchema } from "./schema";
+
+describe("configSchema", () => {
+  it("should be serializable as JSON", () => {
+    expect(JSON.parse(JSON.stringify(configSchema))).toEqual(configSchema);
+  });
+
+  it("should describe per-cli regex patterns as strings", () => {
+    const cli = configSchema.properties.clis.additionalProperties;
+    expect(cli.properties.enter.items).toEqual({ type: "string", format: "regex" });
+    expect(cli.properties.typingRespond.additionalProperties.items.type).toBe("string");
+  });
+});
+
+describe("patternsFileSchema", () => {
+  it("should describe the entries parsePatternsFile reads", () => {
+    const [withOptions, list] = patternsFileSchema.oneOf;
+    const entry = withOptions.properties.patterns.items;
+    expect(entry.required).toEqual(["match", "response"]);
+    expect(Object.keys(entry.properties)).toEqual(["match", "is_regex", "response"]);
+    expect(list.items).toBe(entry);
+    expect(configSchema.properties.clis.additionalProperties.properties.patterns.items).toBe(entry);
+
+    const file = { builtins: false, patterns: [{ match: "Go\\?", is_regex: true, response: "y" }] };
+    expect(Object.keys(file)).toEqual(Object.keys(withOptions.properties));
+    expect(parsePatternsFile(JSON.stringify(file))).toEqual({ builtins: false, patterns: [{ match: /Go\?/, response: "y" }] });
+  });
+});
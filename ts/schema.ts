@@ -0,0 +1,152 @@
+/**
+ * JSON Schemas for agent-yes config files, printed by `agent-yes schema`
+ *
+ * Point your editor at them for validation and autocomplete when writing a
+ * JSON config (regex patterns are written as strings) or a
+ * `--patterns-file`. Keep in sync with `AgentCliConfig` and
+ * `AgentYesConfig` in index.ts, and with parsePatternsFile.
+ */
+
+const regexList = {
+  type: "array",
+  items: { type: "string", format: "regex" },
+} as const;
+
+// an extra prompt to answer, as in --patterns-file
+const patternEntry = {
+  type: "object",
+  required: ["match", "response"],
+  properties: {
+    match: { type: "string", description: "Text of the prompt line, a substring unless is_regex is true" },
+    is_regex: { type: "boolean", default: false, description: "Whether match is a regex" },
+    response: { type: "string", description: '"\\r" presses Enter, other text is typed followed by Enter' },
+  },
+} as const;
+
+export const cliConfigSchema = {
+  type: "object",
+  additionalProperties: true, // allow fields used by newer versions / user extensions
+  properties: {
+    install: {
+      description: "Install command hint, or per-platform install commands",
+      oneOf: [
+        { type: "string" },
+        {
+          type: "object",
+          additionalProperties: false,
+          properties: {
+            powershell: { type: "string" },
+            bash: { type: "string" },
+            npm: { type: "string" },
+            unix: { type: "string" },
+            windows: { type: "string" },
+          },
+        },
+      ],
+    },
+    version: { type: "string", description: "Command to check the installed version" },
+    binary: { type: "string", description: "Actual binary name if different from the cli name" },
+    defaultArgs: { type: "array", items: { type: "string" }, description: "Args always passed" },
+    ready: { ...regexList, description: "Patterns meaning the cli is ready for input" },
+    fatal: { ...regexList, description: "Patterns meaning a fatal error, agent-yes exits" },
+    rateLimit: {
+      ...regexList,
+      description: "Usage/rate-limit messages with a reset time, waited out with --wait-on-rate-limit",
+    },
+    exitCommands: { type: "array", items: { type: "string" }, description: "Commands to exit" },
+    promptArg: {
+      type: "string",
+      description: 'How to pass the prompt: "first-arg", "last-arg", or a flag like "--prompt"',
+    },
+    noEOL: { type: "boolean", description: "Output has no line breaks (cursor moves instead)" },
+    enter: { ...regexList, description: "Patterns answered by pressing Enter" },
+    typingRespond: {
+      type: "object",
+      description: "Text to type, keyed by the text, for prompts matching the patterns",
+      additionalProperties: regexList,
+    },
+    banner: {
+      type: "string",
+      format: "regex",
+      description: "Startup banner, removed from the start of the output with --strip-banner",
+    },
+    notices: {
+      ...regexList,
+      description: "Update/changelog notices waiting for a keypress, dismissed with Enter",
+    },
+    mcpTrust: {
+      type: "object",
+      description: "MCP server trust prompt, and what to type for --trust-mcp=yes|no",
+      required: ["detect", "yes", "no"],
+      properties: {
+        detect: regexList,
+        yes: { type: "string" },
+        no: { type: "string" },
+      },
+    },
+    sessionPicker: {
+      ...regexList,
+      description: "Header of the --resume session list, answered per --pick-session",
+    },
+    patterns: {
+      type: "array",
+      description: "Extra prompts to answer, checked before the built-ins, like --patterns-file",
+      items: patternEntry,
+    },
+    busy: {
+      ...regexList,
+      description: "Patterns meaning the cli is still working, so it isn't idle or awaiting input",
+    },
+    restoreArgs: {
+      type: "array",
+      items: { type: "string" },
+      description: "Args to resume the session after a crash",
+    },
+    restartWithoutContinueArg: {
+      ...regexList,
+      description: "Patterns meaning the cli must restart without the resume args",
+    },
+  },
+} as const;
+
+export const configSchema = {
+  $schema: "http://json-schema.org/draft-07/schema#",
+  title: "agent-yes config",
+  type: "object",
+  properties: {
+    configDir: { type: "string", description: "Directory for agent-yes state, e.g. sessions" },
+    logsDir: { type: "string", description: "Directory for agent-yes log files" },
+    profiles: {
+      type: "object",
+      description: 'Presets for --profile, keyed by name, e.g. { "nightly": { "exit-on-idle": "5m" } }',
+      additionalProperties: {
+        type: "object",
+        description: "Flag defaults keyed by option name as on the command line",
+      },
+    },
+    clis: {
+      type: "object",
+      description: "Per-cli configuration, keyed by cli name",
+      additionalProperties: cliConfigSchema,
+    },
+  },
+} as const;
+
+export const patternsFileSchema = {
+  $schema: "http://json-schema.org/draft-07/schema#",
+  title: "agent-yes patterns file",
+  description: "Extra prompts to answer, for --patterns-file",
+  oneOf: [
+    {
+      type: "object",
+      properties: {
+        builtins: { type: "boolean", default: true, description: "Keep answering the built-in prompts too" },
+        patterns: { type: "array", items: patternEntry, description: "Checked in order, the first match wins" },
+      },
+    },
+    { type: "array", items: patternEntry, description: "Just the patterns, with the built-ins kept" },
+  ],
+} as const;
+
+/** Schemas printed by `agent-yes schema [name]` */
+export const SCHEMAS = { config: configSchema, patterns: patternsFileSchema } as const;
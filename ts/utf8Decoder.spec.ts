@@ -0,0 +1,53 @@
+import { describe, expect, it } from "vitest";
+import { Utf8StreamDecoder } from "./utf8Decoder";
+
+describe("Utf8StreamDecoder", () => {
+  it("should decode plain ascii", () => {
+    const decoder = new Utf8StreamDecoder();
+    expect(decoder.write(Buffer.from("hello"))).toBe("hello");
+  });
+
+  it("should reassemble a multi-byte char split across chunks", () => {
+    const decoder = new Utf8StreamDecoder();
+    const bytes = Buffer.from("😀");
+    expect(decoder.write(bytes.subarray(0, 2))).toBe("");
+    expect(decoder.write(bytes.subarray(2))).toBe("😀");
+  });
+
+  it("should replace latin-1 bytes instead of stalling", () => {
+    const decoder = new Utf8StreamDecoder();
+    // "café ok" encoded as latin-1
+    const output = decoder.write(Buffer.from([0x63, 0x61, 0x66, 0xe9, 0x20, 0x6f, 0x6b]));
+    expect(output).toBe("caf� ok");
+  });
+
+  it("should keep producing output after a run of invalid bytes", () => {
+    const decoder = new Utf8StreamDecoder();
+    let output = "";
+    for (let i = 0; i < 16; i++) output += decoder.write(Buffer.from([0xff]));
+    output += decoder.write(Buffer.from("next"));
+    expect(output).toBe("�".repeat(16) + "next");
+  });
+
+  it("should flush a truncated char at the end instead of dropping it", () => {
+    const decoder = new Utf8StreamDecoder();
+    const bytes = Buffer.from("ok 😀");
+    expect(decoder.write(bytes.subarray(0, -2))).toBe("ok ");
+    expect(decoder.end()).toBe("�");
+    expect(decoder.end()).toBe(""); // nothing left
+  });
+
+  it("should flush the tail when used as a stream step", async () => {
+    const bytes = Buffer.from("ok 😀");
+    const chunks: string[] = [];
+    await new ReadableStream<Uint8Array>({
+      start(controller) {
+        controller.enqueue(bytes.subarray(0, -2));
+        controller.close();
+      },
+    })
+      .pipeThrough(new Utf8StreamDecoder().transform())
+      .pipeTo(new WritableStream({ write: (chunk) => void chunks.push(chunk) }));
+    expect(chunks.join("")).toBe("ok �");
+  });
+});
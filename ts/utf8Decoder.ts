@@ -0,0 +1,38 @@
+/**
+ * Incremental UTF-8 decoder for byte streams (agent output, stdin, FIFO, etc.)
+ *
+ * `Buffer#toString()` decodes each chunk on its own, so a multi-byte char split
+ * across two chunks turns into garbage. This keeps the incomplete tail bytes
+ * around until the next chunk arrives, and replaces invalid bytes (e.g. Latin-1
+ * output from a non-UTF8 locale) with U+FFFD instead of holding them forever,
+ * so output never stalls on a bad byte. At the end of the stream, `end()`
+ * flushes a truncated tail as U+FFFD rather than dropping it.
+ *
+ * @example
+ * const decoder = new Utf8StreamDecoder();
+ * decoder.write(Buffer.from([0xf0, 0x9f])); // ""
+ * decoder.write(Buffer.from([0x98, 0x80])); // "😀"
+ */
+export class Utf8StreamDecoder {
+  private decoder = new TextDecoder("utf-8", { fatal: false });
+
+  write(chunk: Uint8Array): string {
+    return this.decoder.decode(chunk, { stream: true });
+  }
+
+  /** Flush the bytes held back at the end of the stream, e.g. a truncated char as "�" */
+  end(): string {
+    return this.decoder.decode();
+  }
+
+  /** The decoder as a stream step, flushing the held back bytes when the input ends */
+  transform(): TransformStream<Uint8Array, string> {
+    return new TransformStream({
+      transform: (chunk, controller) => controller.enqueue(this.write(chunk)),
+      flush: (controller) => {
+        const tail = this.end();
+        if (tail) controller.enqueue(tail);
+      },
+    });
+  }
+}
@@ -0,0 +1,69 @@
+import { describe, expect, it, vi } from "vitest";
+import { globToRegExp, WatchScheduler, type Session } from "./watch";
+
+const fakeSession = () => {
+  let finish!: (code: number) => void;
+  const session: Session = {
+    done: new Promise<number>((resolve) => (finish = resolve)),
+    stop: vi.fn(async () => finish(130)),
+  };
+  return { session, finish: (code = 0) => finish(code) };
+};
+const tick = () => new Promise((resolve) => setTimeout(resolve, 0));
+
+describe("globToRegExp", () => {
+  it("should match within one directory with *", () => {
+    expect(globToRegExp("*/TASK.md").test("api/TASK.md")).toBe(true);
+    expect(globToRegExp("*/TASK.md").test("api/v2/TASK.md")).toBe(false);
+    expect(globToRegExp("*/TASK.md").test("api/TASKxmd")).toBe(false);
+  });
+
+  it("should match across directories with **", () => {
+    expect(globToRegExp("**/*.todo").test("a/b/c.todo")).toBe(true);
+    expect(globToRegExp("**/*.todo").test("c.todo")).toBe(true);
+  });
+});
+
+describe("WatchScheduler", () => {
+  it("should run one session per directory and rerun once after it", async () => {
+    const sessions = [fakeSession(), fakeSession()];
+    const launch = vi.fn(() => sessions[launch.mock.calls.length - 1]!.session);
+    const scheduler = new WatchScheduler({ concurrency: 2, launch });
+
+    scheduler.trigger("/repo/api/TASK.md");
+    scheduler.trigger("/repo/api/TASK.md");
+    scheduler.trigger("/repo/api/TASK.md");
+    expect(launch).toHaveBeenCalledTimes(1);
+
+    sessions[0]!.finish();
+    await tick();
+    expect(launch).toHaveBeenCalledTimes(2); // the queued changes, collapsed
+  });
+
+  it("should respect the concurrency cap", async () => {
+    const sessions = [fakeSession(), fakeSession()];
+    const launch = vi.fn(() => sessions[launch.mock.calls.length - 1]!.session);
+    const scheduler = new WatchScheduler({ concurrency: 1, launch });
+
+    scheduler.trigger("/repo/api/TASK.md");
+    scheduler.trigger("/repo/web/TASK.md");
+    expect(launch).toHaveBeenCalledTimes(1);
+
+    sessions[0]!.finish();
+    await tick();
+    expect(launch).toHaveBeenLastCalledWith("/repo/web/TASK.md", "/repo/web");
+  });
+
+  it("should kill running sessions on shutdown with kill", async () => {
+    const { session } = fakeSession();
+    const launch = vi.fn(() => session);
+    const scheduler = new WatchScheduler({ concurrency: 1, launch });
+
+    scheduler.trigger("/repo/api/TASK.md");
+    await scheduler.shutdown("kill");
+    expect(session.stop).toHaveBeenCalled();
+
+    scheduler.trigger("/repo/web/TASK.md");
+    expect(launch).toHaveBeenCalledTimes(1); // nothing new launched after shutdown
+  });
+});
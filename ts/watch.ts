@@ -0,0 +1,177 @@
+import { spawn } from "child_process";
+import { watch } from "fs";
+import path from "path";
+import yargs from "yargs";
+import { logger } from "./logger.ts";
+import { killWithTimeout } from "./core/shutdown.ts";
+
+/**
+ * `agent-yes watch`: launch a session for each file change matching a glob
+ *
+ * Watches a directory tree and starts an agent-yes session (a child
+ * process, in the file's directory) whenever a matching file is created or
+ * changed, e.g. picking up `*\/TASK.md` files dropped in by people or
+ * other tools. At most one session runs per directory; changes during a
+ * session queue one more run for that directory. `concurrency` caps the
+ * sessions running at once.
+ *
+ * @example
+ * ```bash
+ * agent-yes watch --glob '*\/TASK.md' --prompt 'Do the task in {file}, then delete it' -- claude --exit-on-idle=1m
+ * ```
+ */
+
+export interface Session {
+  done: Promise<number>; // exit code
+  stop: () => Promise<unknown>; // kill the session
+}
+
+export interface WatchSchedulerOptions {
+  concurrency: number;
+  launch: (file: string, dir: string) => Session;
+}
+
+/**
+ * Turn a glob into a regex matching relative paths with "/" separators
+ * `**` matches across directories, `*` and `?` within one
+ *
+ * @example
+ * ```typescript
+ * globToRegExp("*\/TASK.md").test("api/TASK.md"); // true
+ * globToRegExp("**\/*.todo").test("a/b/c.todo"); // true
+ * ```
+ */
+export function globToRegExp(glob: string): RegExp {
+  const source = glob
+    .split(/(\*\*\/?|\*|\?)/)
+    .map((part) => {
+      if (part === "**/") return "(?:.*/)?";
+      if (part === "**") return ".*";
+      if (part === "*") return "[^/]*";
+      if (part === "?") return "[^/]";
+      return part.replace(/[.+^${}()|[\]\\]/g, "\\$&");
+    })
+    .join("");
+  return new RegExp(`^${source}$`);
+}
+
+/**
+ * Decides when to launch sessions: one per directory, `concurrency` at most
+ */
+export class WatchScheduler {
+  private running = new Map<string, Session>(); // by directory
+  private queued = new Map<string, string>(); // directory -> latest triggering file
+  private stopping = false;
+
+  constructor(private options: WatchSchedulerOptions) {}
+
+  /** A matching file changed */
+  trigger(file: string) {
+    if (this.stopping) return;
+    this.queued.set(path.dirname(file), file); // repeated changes collapse into one run
+    this.drain();
+  }
+
+  get activeCount() {
+    return this.running.size;
+  }
+
+  /**
+   * Stop launching sessions, and wait for the running ones or kill them
+   */
+  async shutdown(mode: "wait" | "kill") {
+    this.stopping = true;
+    this.queued.clear();
+    const sessions = [...this.running.values()];
+    if (mode === "kill") await Promise.all(sessions.map((session) => session.stop()));
+    await Promise.all(sessions.map((session) => session.done.catch(() => null)));
+  }
+
+  private drain() {
+    for (const [dir, file] of this.queued) {
+      if (this.running.size >= this.options.concurrency) return;
+      if (this.running.has(dir)) continue; // runs again after the current session
+      this.queued.delete(dir);
+      const session = this.options.launch(file, dir);
+      this.running.set(dir, session);
+      session.done
+        .catch(() => 1)
+        .then((code) => {
+          logger.info(`[watch] session in ${dir} exited with code ${code}`);
+          this.running.delete(dir);
+          if (!this.stopping) this.drain();
+        });
+    }
+  }
+}
+
+/**
+ * Run `agent-yes watch` with its args, resolves with the exit code after shutdown
+ */
+export async function runWatchCli(argv: string[]): Promise<number> {
+  const dashIndex = argv.indexOf("--");
+  const agentArgs = dashIndex === -1 ? [] : argv.slice(dashIndex + 1);
+  const args = yargs(dashIndex === -1 ? argv : argv.slice(0, dashIndex))
+    .usage("Usage: $0 watch --glob <glob> [--prompt <prompt>] -- <cli> [agent-yes args]")
+    .option("glob", {
+      type: "string",
+      demandOption: true,
+      description: "Files to watch, relative to --dir, e.g. '*/TASK.md'",
+    })
+    .option("dir", { type: "string", default: ".", description: "Directory tree to watch" })
+    .option("prompt", {
+      type: "string",
+      default: "Do the task described in {file}",
+      description: "Prompt for each session, {file} is replaced by the path of the triggering file",
+    })
+    .option("concurrency", { type: "number", default: 2, description: "Max sessions running at once" })
+    .option("on-shutdown", {
+      type: "string",
+      choices: ["wait", "kill"] as const,
+      default: "wait",
+      description: "On SIGTERM/SIGINT, let running sessions finish or kill them",
+    })
+    .parseSync();
+
+  if (!agentArgs.length) throw new Error("Missing the session command after --, e.g. -- claude --exit-on-idle=1m");
+  const root = path.resolve(args.dir);
+  const matcher = globToRegExp(args.glob);
+
+  const scheduler = new WatchScheduler({
+    concurrency: args.concurrency,
+    launch: (file, dir) => {
+      const prompt = args.prompt.replaceAll("{file}", file);
+      const label = path.relative(root, dir) || ".";
+      logger.info(`[watch] ${file} changed, starting a session in ${label}`);
+      // same entry point as this process, so sessions run the same agent-yes version
+      const child = spawn(
+        process.execPath,
+        [process.argv[1]!, ...agentArgs, `--prefix=[${label}] `, "--", prompt],
+        { cwd: dir, stdio: ["ignore", "inherit", "inherit"] },
+      );
+      return {
+        done: new Promise<number>((resolve) => child.on("exit", (code) => resolve(code ?? 1))),
+        stop: () => killWithTimeout({ pid: child.pid!, kill: (signal) => child.kill(signal as NodeJS.Signals) }),
+      };
+    },
+  });
+
+  const watcher = watch(root, { recursive: true }, (_event, filename) => {
+    if (!filename) return;
+    const relative = filename.split(path.sep).join("/");
+    if (matcher.test(relative)) scheduler.trigger(path.resolve(root, filename));
+  });
+  logger.info(`[watch] watching ${args.glob} in ${root}, up to ${args.concurrency} sessions`);
+
+  await new Promise<void>((resolve) => {
+    const onSignal = (signal: string) => {
+      logger.info(`[watch] ${signal}, ${args.onShutdown === "kill" ? "killing" : "waiting for"} ${scheduler.activeCount} sessions`);
+      resolve();
+    };
+    process.once("SIGTERM", onSignal);
+    process.once("SIGINT", onSignal);
+  });
+  watcher.close();
+  await scheduler.shutdown(args.onShutdown as "wait" | "kill");
+  return 0;
+}